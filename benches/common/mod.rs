@@ -0,0 +1,20 @@
+// a small linear congruential generator, good enough to spread points
+pub struct Points(pub u64);
+
+impl Points {
+    pub fn next_point<const N: usize>(&mut self) -> [f64; N] {
+        let mut point = [0.0; N];
+        for coordinate in point.iter_mut() {
+            self.0 = self
+                .0
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            *coordinate = (self.0 >> 11) as f64 / (1_u64 << 53) as f64 * 2.0 - 1.0;
+        }
+        point
+    }
+
+    pub fn take<const N: usize>(&mut self, count: usize) -> Vec<[f64; N]> {
+        (0..count).map(|_| self.next_point::<N>()).collect()
+    }
+}
@@ -0,0 +1,33 @@
+//! Times dividing trees of one to six dimensions to a depth of roughly 4096 leaves.
+//!
+//! Run with `cargo bench --bench divide`.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use tpntree::tpntree::TpnTree;
+
+fn divide<const N: usize>(c: &mut Criterion) {
+    // keep the node count roughly equal across dimensions
+    let depth = 12 / N;
+    c.benchmark_group("divide_to_depth").bench_with_input(
+        BenchmarkId::new(format!("N={}", N), depth),
+        &depth,
+        |b, &depth| {
+            b.iter(|| {
+                let mut tree = TpnTree::<(), N>::root(1.0);
+                tree.divide_to_depth(depth).expect("Couldn't divide.");
+                tree
+            })
+        },
+    );
+}
+
+criterion_group!(
+    benches,
+    divide::<1>,
+    divide::<2>,
+    divide::<3>,
+    divide::<4>,
+    divide::<5>,
+    divide::<6>
+);
+criterion_main!(benches);
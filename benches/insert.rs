@@ -0,0 +1,39 @@
+//! Times inserting 10000 points into trees of one to six dimensions.
+//!
+//! Run with `cargo bench --bench insert`.
+
+mod common;
+
+use common::Points;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use tpntree::tpntree::SpatialTree;
+
+fn insert<const N: usize>(c: &mut Criterion) {
+    let data = Points(N as u64).take::<N>(10_000);
+    c.benchmark_group("insert").bench_with_input(
+        BenchmarkId::from_parameter(format!("N={}", N)),
+        &data,
+        |b, data| {
+            b.iter(|| {
+                let mut tree = SpatialTree::<[f64; N], N>::builder()
+                    .leaf_capacity(16)
+                    .build();
+                for point in data {
+                    tree.insert(*point).expect("Couldn't insert.");
+                }
+                tree
+            })
+        },
+    );
+}
+
+criterion_group!(
+    benches,
+    insert::<1>,
+    insert::<2>,
+    insert::<3>,
+    insert::<4>,
+    insert::<5>,
+    insert::<6>
+);
+criterion_main!(benches);
@@ -0,0 +1,37 @@
+//! Times querying the 8 nearest neighbors of 1000 points among 10000 in trees of one to six dimensions.
+//!
+//! Run with `cargo bench --bench query`.
+
+mod common;
+
+use common::Points;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use tpntree::tpntree::SpatialTree;
+
+fn query<const N: usize>(c: &mut Criterion) {
+    let mut points = Points(N as u64);
+    let mut tree = SpatialTree::<[f64; N], N>::builder()
+        .leaf_capacity(16)
+        .build();
+    for point in points.take::<N>(10_000) {
+        tree.insert(point).expect("Couldn't insert.");
+    }
+    let queries = points.take::<N>(1_000);
+
+    let mut group = c.benchmark_group("nearest_neighbors");
+    let id = format!("N={}", N);
+    group.bench_with_input(BenchmarkId::new("single", &id), &queries, |b, queries| {
+        b.iter(|| {
+            queries
+                .iter()
+                .map(|query| tree.nearest_neighbors(query, 8))
+                .collect::<Vec<_>>()
+        })
+    });
+    group.bench_with_input(BenchmarkId::new("batch", &id), &queries, |b, queries| {
+        b.iter(|| tree.batch_nearest_neighbors(queries, 8))
+    });
+}
+
+criterion_group!(benches, query::<1>, query::<2>, query::<3>, query::<4>, query::<5>, query::<6>);
+criterion_main!(benches);
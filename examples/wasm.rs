@@ -0,0 +1,3 @@
+//! Links the C ABI of the `wasm` feature into a WebAssembly module, see the `tpntree::wasm` module on how to build and use it.
+
+pub use tpntree::wasm::*;
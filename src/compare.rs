@@ -0,0 +1,90 @@
+use crate::{tpntree, tpntree_dynamic};
+
+impl<T, const N: usize> tpntree::TpnTree<T, N> {
+    /// Checks if both trees cover the same regions with the same structure, ignoring their data.
+    ///
+    /// Use `==` to compare the data as well.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::TpnTree;
+    /// let mut tree = TpnTree::<f64, 2>::root(1.0);
+    /// let mut other = TpnTree::<&str, 2>::root(1.0);
+    ///
+    /// *tree.data_mut() = Some(1.0);
+    /// assert!(tree.structurally_equal(&other));
+    ///
+    /// tree.divide().expect("Couldn't divide.");
+    /// assert!(!tree.structurally_equal(&other));
+    /// ```
+    pub fn structurally_equal<U>(&self, other: &tpntree::TpnTree<U, N>) -> bool {
+        self.coordinates == other.coordinates
+            && self.span == other.span
+            && self.level == other.level
+            && self.children.len() == other.children.len()
+            && self
+                .children
+                .iter()
+                .zip(other.children.iter())
+                .all(|(child, other_child)| child.structurally_equal(other_child))
+    }
+}
+
+impl<T> tpntree_dynamic::TpnTree<T> {
+    /// Checks if both trees cover the same regions with the same structure, ignoring their data.
+    ///
+    /// Use `==` to compare the data as well.
+    pub fn structurally_equal<U>(&self, other: &tpntree_dynamic::TpnTree<U>) -> bool {
+        self.coordinates == other.coordinates
+            && self.span == other.span
+            && self.level == other.level
+            && self.children.len() == other.children.len()
+            && self
+                .children
+                .iter()
+                .zip(other.children.iter())
+                .all(|(child, other_child)| child.structurally_equal(other_child))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{tpntree, tpntree_dynamic};
+
+    #[test]
+    fn equal_trees() {
+        let mut tree = tpntree::TpnTree::<usize, 2>::root(1.0);
+        assert!(tree.divide().is_ok());
+        *tree.get_child_mut(1).unwrap().data_mut() = Some(1);
+
+        let mut other = tree.clone();
+        assert_eq!(tree, other);
+
+        *other.get_child_mut(1).unwrap().data_mut() = Some(2);
+        assert_ne!(tree, other);
+        assert!(tree.structurally_equal(&other));
+    }
+
+    #[test]
+    fn regions_differ() {
+        let tree = tpntree_dynamic::TpnTree::<()>::root(1.0, 2);
+        let other = tpntree_dynamic::TpnTree::<()>::root(2.0, 2);
+        let deeper =
+            tpntree_dynamic::TpnTree::<()>::new(vec![0.0, 0.0], vec![1.0, 1.0], 1).unwrap();
+
+        assert_ne!(tree, other);
+        assert!(!tree.structurally_equal(&other));
+        assert!(!tree.structurally_equal(&deeper));
+    }
+
+    #[test]
+    fn divided_dynamic_trees() {
+        let mut tree = tpntree_dynamic::TpnTree::<()>::root(1.0, 3);
+        let mut other = tpntree_dynamic::TpnTree::<u8>::root(1.0, 3);
+
+        assert!(tree.divide().is_ok());
+        assert!(!tree.structurally_equal(&other));
+
+        assert!(other.divide().is_ok());
+        assert!(tree.structurally_equal(&other));
+    }
+}
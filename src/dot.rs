@@ -0,0 +1,124 @@
+use alloc::{format, string::String};
+use core::fmt::Write;
+
+use crate::{tpntree, tpntree_dynamic, TreeNode};
+
+// writes the tree as Graphviz DOT, naming nodes by their depth first index
+fn to_dot<T, Tree: TreeNode<T>>(tree: &Tree, label_fn: impl Fn(&Tree) -> String) -> String {
+    let mut dot = String::from("digraph tpntree {\n");
+    write_node(tree, &label_fn, &mut 0, &mut dot);
+    dot.push_str("}\n");
+    dot
+}
+
+fn write_node<T, Tree: TreeNode<T>>(
+    tree: &Tree,
+    label_fn: &impl Fn(&Tree) -> String,
+    next_id: &mut usize,
+    dot: &mut String,
+) -> usize {
+    let id = *next_id;
+    *next_id += 1;
+
+    let mut label = format!(
+        "level {}\\ncenter {:?}\\nspan {:?}",
+        tree.level(),
+        tree.coordinates(),
+        tree.span()
+    );
+    let user_label = label_fn(tree);
+    if !user_label.is_empty() {
+        label.push_str("\\n");
+        label.push_str(&user_label.replace('\\', "\\\\").replace('"', "\\\""));
+    }
+    // writing into a String can not fail
+    let _ = writeln!(dot, "    node{} [label=\"{}\"];", id, label);
+
+    for child in tree.children() {
+        let child_id = write_node(child, label_fn, next_id, dot);
+        let _ = writeln!(dot, "    node{} -> node{};", id, child_id);
+    }
+    id
+}
+
+impl<T, const N: usize> tpntree::TpnTree<T, N> {
+    /// Describes the tree as a Graphviz DOT graph with an edge from every parent to each of its children.
+    ///
+    /// Every node is labeled with its level, center and span followed by the label returned from `label_fn`, if not empty.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::TpnTree;
+    /// let mut root = TpnTree::<(), 1>::root(1.0);
+    /// root.divide().expect("Couldn't divide.");
+    ///
+    /// let dot = root.to_dot(|_| String::new());
+    ///
+    /// assert!(dot.starts_with("digraph tpntree {"));
+    /// assert!(dot.contains("node0 -> node1;"));
+    /// assert!(dot.contains("node0 -> node2;"));
+    /// ```
+    pub fn to_dot(&self, label_fn: impl Fn(&Self) -> String) -> String {
+        to_dot(self, label_fn)
+    }
+}
+
+impl<T> tpntree_dynamic::TpnTree<T> {
+    /// Describes the tree as a Graphviz DOT graph with an edge from every parent to each of its children.
+    ///
+    /// Every node is labeled with its level, center and span followed by the label returned from `label_fn`, if not empty.
+    ///
+    /// ```
+    /// # use tpntree::tpntree_dynamic::TpnTree;
+    /// let mut root = TpnTree::<()>::root(1.0, 1);
+    /// root.divide().expect("Couldn't divide.");
+    ///
+    /// let dot = root.to_dot(|_| String::new());
+    ///
+    /// assert!(dot.starts_with("digraph tpntree {"));
+    /// assert!(dot.contains("node0 -> node1;"));
+    /// assert!(dot.contains("node0 -> node2;"));
+    /// ```
+    pub fn to_dot(&self, label_fn: impl Fn(&Self) -> String) -> String {
+        to_dot(self, label_fn)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{tpntree, tpntree_dynamic};
+
+    #[test]
+    fn dot_of_single_node() {
+        let tree = tpntree::TpnTree::<(), 2>::root(1.0);
+
+        assert_eq!(
+            tree.to_dot(|_| String::new()),
+            "digraph tpntree {\n    node0 [label=\"level 0\\ncenter [0.0, 0.0]\\nspan [1.0, 1.0]\"];\n}\n"
+        );
+    }
+
+    #[test]
+    fn dot_contains_all_edges() {
+        let mut tree = tpntree_dynamic::TpnTree::<()>::root(1.0, 2);
+
+        assert!(tree.divide().is_ok());
+        assert!(tree.get_child_mut(3).unwrap().divide().is_ok());
+
+        let dot = tree.to_dot(|_| String::new());
+
+        assert_eq!(dot.matches("->").count(), 8);
+        assert!(dot.contains("node4 -> node5;"));
+        assert!(dot.contains("node0 -> node4;"));
+    }
+
+    #[test]
+    fn dot_escapes_labels() {
+        let mut tree = tpntree::TpnTree::<&str, 1>::root(1.0);
+
+        *tree.data_mut() = Some("say \"hi\"");
+
+        let dot = tree.to_dot(|tree| tree.data().map(|d| d.to_string()).unwrap_or_default());
+
+        assert!(dot.contains("\\nsay \\\"hi\\\"\"];"));
+    }
+}
@@ -1,21 +1,140 @@
-use std::{error::Error, fmt::Display};
+use alloc::vec::Vec;
+use core::fmt::Display;
 
-#[derive(Debug, Eq, PartialEq)]
+/// Errors returned by the operations on trees.
+///
+/// New variants may be added without a major version bump, so matches need a wildcard arm.
+#[derive(Debug, PartialEq)]
+#[non_exhaustive]
 pub enum TpnTreeError {
-    DoesNotSpan,
+    /// The tree does not span over the point.
+    DoesNotSpan {
+        /// Coordinates of the point.
+        point: Vec<f64>,
+        /// Min and max corner of the tree.
+        bounds: (Vec<f64>, Vec<f64>),
+    },
+    /// The number of dimensions of some coordinates does not match the tree.
+    DimensionMismatch { expected: usize, got: usize },
+    /// The tree would need to grow deeper than allowed.
+    MaxDepthExceeded { max_level: usize },
+    /// The tree has been divided before and can not be divided again.
     CanNotDivide,
+    /// The leaf is at capacity and rejects further data, see [`Spillover::Reject`](crate::tpntree::Spillover::Reject).
+    CapacityExceeded,
+    /// The tree has no child at the index.
+    NoSuchChild { index: usize },
+    /// The tree has no axis with the index.
+    NoSuchAxis { axis: usize },
+    /// A tree can not be divided into this many parts per axis.
+    InvalidBranching { branching: usize },
+    /// The region of a tree does not match the region it is meant to cover.
+    RegionMismatch {
+        /// Center and span of the expected region.
+        expected: (Vec<f64>, Vec<f64>),
+        /// Center and span of the provided tree.
+        got: (Vec<f64>, Vec<f64>),
+    },
+    /// Two trees expected to be structurally equal differ in their regions or children.
+    StructureMismatch,
+    /// There is no item with the id.
+    NoSuchItem,
+    /// The arrays of a linear tree do not describe a tree.
+    MalformedLinearTree,
+    /// The min corner exceeds the max corner along some axis.
+    InvalidBounds { min: Vec<f64>, max: Vec<f64> },
+    /// The tree already holds an item at the coordinates, see [`DuplicatePolicy`](crate::tpntree::DuplicatePolicy).
+    Duplicate { point: Vec<f64> },
+    /// The linear part of a transform is not invertible.
+    SingularTransform,
+}
+
+impl TpnTreeError {
+    // builds the error for a tree given by its center and span
+    pub(crate) fn does_not_span(point: &[f64], coordinates: &[f64], span: &[f64]) -> Self {
+        TpnTreeError::DoesNotSpan {
+            point: point.to_vec(),
+            bounds: (
+                coordinates.iter().zip(span).map(|(c, s)| c - s).collect(),
+                coordinates.iter().zip(span).map(|(c, s)| c + s).collect(),
+            ),
+        }
+    }
 }
 
 impl Display for TpnTreeError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match &self {
-            TpnTreeError::DoesNotSpan => write!(
+            TpnTreeError::DoesNotSpan { point, bounds } => write!(
                 f,
-                "The tree does not span over the provided data coordinates."
+                "The tree spanning from {:?} to {:?} does not span over the provided data coordinates {:?}.",
+                bounds.0, bounds.1, point
             ),
+            TpnTreeError::DimensionMismatch { expected, got } => write!(
+                f,
+                "Expected coordinates of dimension {}, got dimension {}.",
+                expected, got
+            ),
+            TpnTreeError::MaxDepthExceeded { max_level } => {
+                write!(f, "The tree can not grow deeper than level {}.", max_level)
+            }
             TpnTreeError::CanNotDivide => write!(f, "The tree has been divided before."),
+            TpnTreeError::CapacityExceeded => write!(f, "The leaf is at capacity."),
+            TpnTreeError::NoSuchChild { index } => {
+                write!(f, "The tree has no child at index {}.", index)
+            }
+            TpnTreeError::NoSuchAxis { axis } => {
+                write!(f, "The tree has no axis {}.", axis)
+            }
+            TpnTreeError::InvalidBranching { branching } => write!(
+                f,
+                "The tree can not be divided into {} parts per axis.",
+                branching
+            ),
+            TpnTreeError::RegionMismatch { expected, got } => write!(
+                f,
+                "Expected a tree with center {:?} and span {:?}, got center {:?} and span {:?}.",
+                expected.0, expected.1, got.0, got.1
+            ),
+            TpnTreeError::StructureMismatch => write!(f, "The trees differ in structure."),
+            TpnTreeError::NoSuchItem => write!(f, "There is no item with the id."),
+            TpnTreeError::MalformedLinearTree => {
+                write!(f, "The arrays do not describe a tree.")
+            }
+            TpnTreeError::InvalidBounds { min, max } => write!(
+                f,
+                "The min corner {:?} exceeds the max corner {:?}.",
+                min, max
+            ),
+            TpnTreeError::Duplicate { point } => {
+                write!(f, "The tree already holds an item at {:?}.", point)
+            }
+            TpnTreeError::SingularTransform => write!(f, "The transform is not invertible."),
         }
     }
 }
 
-impl Error for TpnTreeError {}
+#[cfg(feature = "std")]
+impl std::error::Error for TpnTreeError {}
+
+#[cfg(test)]
+mod tests {
+    use super::TpnTreeError;
+
+    #[test]
+    fn does_not_span_reports_bounds() {
+        let error = TpnTreeError::does_not_span(&[2.0, 0.0], &[0.0, 1.0], &[1.0, 0.5]);
+
+        assert_eq!(
+            error,
+            TpnTreeError::DoesNotSpan {
+                point: vec![2.0, 0.0],
+                bounds: (vec![-1.0, 0.5], vec![1.0, 1.5])
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "The tree spanning from [-1.0, 0.5] to [1.0, 1.5] does not span over the provided data coordinates [2.0, 0.0]."
+        );
+    }
+}
@@ -0,0 +1,129 @@
+use alloc::vec::Vec;
+
+macro_rules! impl_fold {
+    ( $( $n:ident )? ) => {
+        impl<T $(, const $n: usize)?> $crate::get_tree_type!( $( $n )?) {
+            /// Folds all trees of the subtree into an accumulator, visiting them depth first, starting with the tree itself.
+            ///
+            /// ```
+            /// # use tpntree::tpntree::TpnTree;
+            /// let mut root = TpnTree::<(), 2>::root(1.0);
+            /// root.divide().expect("Couldn't divide.");
+            ///
+            /// let leaf_count = root.fold_subtree(0, |count, tree| count + tree.is_leaf() as usize);
+            ///
+            /// assert_eq!(leaf_count, 4);
+            /// ```
+            pub fn fold_subtree<A>(&self, init: A, mut f: impl FnMut(A, &Self) -> A) -> A {
+                self.fold_subtree_recursive(init, &mut f)
+            }
+
+            fn fold_subtree_recursive<A>(&self, init: A, f: &mut impl FnMut(A, &Self) -> A) -> A {
+                let accumulator = f(init, self);
+                self.children
+                    .iter()
+                    .fold(accumulator, |accumulator, child| {
+                        child.fold_subtree_recursive(accumulator, f)
+                    })
+            }
+
+            /// Computes the data of every divided tree from the data of its children, bottom up.
+            ///
+            /// `combine` receives the data of all children holding data.
+            /// Leaves keep their data.
+            ///
+            /// ```
+            /// # use tpntree::tpntree::TpnTree;
+            /// let mut root = TpnTree::<f64, 2>::root(1.0);
+            /// root.divide().expect("Couldn't divide.");
+            /// for child in root.iter_children_mut() {
+            ///     *child.data_mut() = Some(2.0);
+            /// }
+            ///
+            /// root.aggregate(|data| data.iter().copied().sum());
+            ///
+            /// assert_eq!(root.data(), Some(&8.0));
+            /// ```
+            pub fn aggregate(&mut self, combine: impl Fn(&[&T]) -> T) {
+                self.aggregate_recursive(&combine);
+            }
+
+            fn aggregate_recursive(&mut self, combine: &impl Fn(&[&T]) -> T) {
+                if self.children.is_empty() {
+                    return;
+                }
+                for child in self.children.iter_mut() {
+                    child.aggregate_recursive(combine);
+                }
+                let data = self
+                    .children
+                    .iter()
+                    .filter_map(|child| child.data.as_ref())
+                    .collect::<Vec<_>>();
+                self.data = Some(combine(&data));
+            }
+        }
+    };
+}
+
+impl_fold!(N);
+impl_fold!();
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use crate::{tpntree, tpntree_dynamic};
+
+    #[test]
+    fn fold_levels_depth_first() {
+        let mut tree = tpntree::TpnTree::<(), 1>::root(1.0);
+
+        assert!(tree.divide().is_ok());
+        assert!(tree.get_child_mut(1).unwrap().divide().is_ok());
+
+        let levels = tree.fold_subtree(Vec::new(), |mut levels, tree| {
+            levels.push(tree.level());
+            levels
+        });
+
+        assert_eq!(levels, vec![0, 1, 1, 2, 2]);
+    }
+
+    #[test]
+    fn aggregate_bottom_up() {
+        let mut tree = tpntree_dynamic::TpnTree::<f64>::root(1.0, 1);
+
+        assert!(tree.divide().is_ok());
+        assert!(tree.get_child_mut(0).unwrap().divide().is_ok());
+
+        *tree
+            .get_child_mut(0)
+            .unwrap()
+            .get_child_mut(0)
+            .unwrap()
+            .data_mut() = Some(1.0);
+        *tree
+            .get_child_mut(0)
+            .unwrap()
+            .get_child_mut(1)
+            .unwrap()
+            .data_mut() = Some(3.0);
+        *tree.get_child_mut(1).unwrap().data_mut() = Some(6.0);
+
+        // mean of the children
+        tree.aggregate(|data| data.iter().copied().sum::<f64>() / data.len() as f64);
+
+        assert_eq!(tree.get_child(0).and_then(|child| *child.data()), Some(2.0));
+        assert_eq!(*tree.data(), Some(4.0));
+    }
+
+    #[test]
+    fn aggregate_leaves_untouched() {
+        let mut tree = tpntree::TpnTree::<usize, 2>::root(1.0);
+
+        *tree.data_mut() = Some(7);
+        tree.aggregate(|data| data.len());
+
+        assert_eq!(tree.data(), Some(&7));
+    }
+}
@@ -0,0 +1,210 @@
+use alloc::vec::Vec;
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use num_traits::Float;
+
+use crate::{tpntree, tpntree_dynamic, TpnTreeError};
+
+// product of the edge lengths
+fn volume(span: &[f64]) -> f64 {
+    span.iter().map(|span| 2.0 * span).product()
+}
+
+// sum of the volumes of all faces, two per axis
+fn surface_area(span: &[f64]) -> f64 {
+    (0..span.len())
+        .map(|axis| {
+            2.0 * span
+                .iter()
+                .enumerate()
+                .filter(|&(i, _)| i != axis)
+                .map(|(_, span)| 2.0 * span)
+                .product::<f64>()
+        })
+        .sum()
+}
+
+// inclusive on both sides, like the spatial methods
+fn contains_point(coordinates: &[f64], span: &[f64], point: &[f64]) -> bool {
+    coordinates
+        .iter()
+        .zip(span)
+        .zip(point)
+        .all(|((coordinate, span), point)| (point - coordinate).abs() <= *span)
+}
+
+// euclidean distance to the closest point of the hyperrectangle
+fn distance_to_point(coordinates: &[f64], span: &[f64], point: &[f64]) -> f64 {
+    coordinates
+        .iter()
+        .zip(span)
+        .zip(point)
+        .map(|((coordinate, span), point)| ((point - coordinate).abs() - span).max(0.0).powi(2))
+        .sum::<f64>()
+        .sqrt()
+}
+
+impl<T, const N: usize> tpntree::TpnTree<T, N> {
+    /// Returns the volume of the hyperrectangle covered by the tree.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::TpnTree;
+    /// let tree = TpnTree::<(), 3>::new([0.0; 3], [1.0, 2.0, 0.5], 0);
+    ///
+    /// assert_eq!(tree.volume(), 8.0);
+    /// assert_eq!(tree.surface_area(), 2.0 * (8.0 + 2.0 + 4.0));
+    /// ```
+    pub fn volume(&self) -> f64 {
+        volume(&self.span)
+    }
+
+    /// Returns the surface area of the hyperrectangle covered by the tree, i.e. the summed volume of its 2 * N faces.
+    pub fn surface_area(&self) -> f64 {
+        surface_area(&self.span)
+    }
+
+    /// Returns the corner with the smallest coordinates along every axis.
+    pub fn min_corner(&self) -> [f64; N] {
+        let mut corner = self.coordinates;
+        for (coordinate, span) in corner.iter_mut().zip(self.span) {
+            *coordinate -= span;
+        }
+        corner
+    }
+
+    /// Returns the corner with the largest coordinates along every axis.
+    pub fn max_corner(&self) -> [f64; N] {
+        let mut corner = self.coordinates;
+        for (coordinate, span) in corner.iter_mut().zip(self.span) {
+            *coordinate += span;
+        }
+        corner
+    }
+
+    /// Checks if the point lies within the tree, including its faces.
+    ///
+    /// Neighboring trees both contain the points on their shared face.
+    /// Use [`tpntree::TpnTree::child_index_for_point`] to assign such points to exactly one child.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::TpnTree;
+    /// let tree = TpnTree::<(), 2>::root(1.0);
+    ///
+    /// assert!(tree.contains_point(&[1.0, -1.0]));
+    /// assert!(!tree.contains_point(&[1.0, -1.1]));
+    /// ```
+    pub fn contains_point(&self, point: &[f64; N]) -> bool {
+        contains_point(&self.coordinates, &self.span, point)
+    }
+
+    /// Returns the euclidean distance from the point to the closest point of the tree, which is zero for points within.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::TpnTree;
+    /// let tree = TpnTree::<(), 2>::root(1.0);
+    ///
+    /// assert_eq!(tree.distance_to_point(&[0.5, 0.5]), 0.0);
+    /// assert_eq!(tree.distance_to_point(&[4.0, 5.0]), 5.0);
+    /// ```
+    pub fn distance_to_point(&self, point: &[f64; N]) -> f64 {
+        distance_to_point(&self.coordinates, &self.span, point)
+    }
+}
+
+impl<T> tpntree_dynamic::TpnTree<T> {
+    /// Returns the volume of the hyperrectangle covered by the tree.
+    pub fn volume(&self) -> f64 {
+        volume(&self.span)
+    }
+
+    /// Returns the surface area of the hyperrectangle covered by the tree, i.e. the summed volume of its 2 * N faces.
+    pub fn surface_area(&self) -> f64 {
+        surface_area(&self.span)
+    }
+
+    /// Returns the corner with the smallest coordinates along every axis.
+    pub fn min_corner(&self) -> Vec<f64> {
+        self.coordinates
+            .iter()
+            .zip(&self.span)
+            .map(|(coordinate, span)| coordinate - span)
+            .collect()
+    }
+
+    /// Returns the corner with the largest coordinates along every axis.
+    pub fn max_corner(&self) -> Vec<f64> {
+        self.coordinates
+            .iter()
+            .zip(&self.span)
+            .map(|(coordinate, span)| coordinate + span)
+            .collect()
+    }
+
+    /// Checks if the point lies within the tree, including its faces.
+    ///
+    /// Errors if the point has other dimension than the tree.
+    pub fn contains_point(&self, point: &[f64]) -> Result<bool, TpnTreeError> {
+        self.check_dimensions(point)?;
+        Ok(contains_point(&self.coordinates, &self.span, point))
+    }
+
+    /// Returns the euclidean distance from the point to the closest point of the tree, which is zero for points within.
+    ///
+    /// Errors if the point has other dimension than the tree.
+    pub fn distance_to_point(&self, point: &[f64]) -> Result<f64, TpnTreeError> {
+        self.check_dimensions(point)?;
+        Ok(distance_to_point(&self.coordinates, &self.span, point))
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use crate::{tpntree, tpntree_dynamic, TpnTreeError};
+
+    #[test]
+    fn corners_match_children() {
+        let mut tree = tpntree::TpnTree::<(), 2>::new([1.0, 2.0], [1.0, 0.5], 0);
+        assert!(tree.divide().is_ok());
+
+        assert_eq!(tree.min_corner(), [0.0, 1.5]);
+        assert_eq!(tree.max_corner(), [2.0, 2.5]);
+        assert_eq!(tree.surface_area(), 2.0 * (1.0 + 2.0));
+        assert_eq!(
+            tree.iter_children()
+                .map(|child| child.volume())
+                .sum::<f64>(),
+            tree.volume()
+        );
+        // every corner of the tree is the corner of exactly one child
+        assert!(tree
+            .iter_children()
+            .any(|child| child.min_corner() == tree.min_corner()));
+        assert!(tree
+            .iter_children()
+            .any(|child| child.max_corner() == tree.max_corner()));
+        assert!(tree
+            .iter_children()
+            .all(|child| child.contains_point(&tree.coordinates())));
+        assert_eq!(tree.distance_to_point(&[3.0, 2.0]), 1.0);
+    }
+
+    #[test]
+    fn dynamic_geometry() {
+        let tree = tpntree_dynamic::TpnTree::<()>::root(1.0, 3);
+
+        assert_eq!(tree.volume(), 8.0);
+        assert_eq!(tree.surface_area(), 24.0);
+        assert_eq!(tree.min_corner(), vec![-1.0; 3]);
+        assert_eq!(tree.contains_point(&[1.0, 1.0, 1.0]), Ok(true));
+        assert!(tree.contains_point(&[0.0, 0.0]).is_err());
+        assert_eq!(tree.distance_to_point(&[0.0, 0.0, 3.0]), Ok(2.0));
+        assert_eq!(
+            tree.distance_to_point(&[0.0]),
+            Err(TpnTreeError::DimensionMismatch {
+                expected: 3,
+                got: 1
+            })
+        );
+    }
+}
@@ -1,10 +1,10 @@
 #[macro_export]
 macro_rules! get_tree_type {
     ( $n:ident ) => {
-        crate::tpntree::TpnTree<T, $n >
+        $crate::tpntree::TpnTree<T, $n >
     };
     ( ) => {
-        crate::tpntree_dynamic::TpnTree<T>
+        $crate::tpntree_dynamic::TpnTree<T>
     };
 }
 
@@ -15,7 +15,7 @@ macro_rules! impl_depth_first_iterator {
                 const $n: usize
             )?> $crate::get_tree_type!( $( $n )?) {
                 /// Iterate the tree depth first, starting with the root.
-                pub fn iter_depth_first(&self) -> DepthFirstIterator<T $(,
+                pub fn iter_depth_first(&self) -> DepthFirstIterator<'_, T $(,
                 $n
             )?> {
                     DepthFirstIterator::new(self)
@@ -25,7 +25,7 @@ macro_rules! impl_depth_first_iterator {
             pub struct DepthFirstIterator<'a, T $(,
                 const $n: usize
             )?> {
-                stack: Vec<&'a $crate::get_tree_type!( $( $n )?)>,
+                stack: alloc::vec::Vec<&'a $crate::get_tree_type!( $( $n )?)>,
             }
 
             impl<'a, T $(,
@@ -34,7 +34,7 @@ macro_rules! impl_depth_first_iterator {
                 $n
             )?> {
                 fn new(root: &'a $crate::get_tree_type!( $( $n )?)) -> Self {
-                    Self { stack: vec![root] }
+                    Self { stack: alloc::vec![root] }
                 }
             }
 
@@ -64,17 +64,28 @@ macro_rules! impl_breadth_first_iterator {
                 const $n: usize
             )?> $crate::get_tree_type!( $( $n )?) {
                 /// Iterate the tree breadth first, starting with the root.
-                pub fn iter_breadth_first(&self) -> BreadthFirstIterator<T $(,
+                pub fn iter_breadth_first(&self) -> BreadthFirstIterator<'_, T $(,
                 $n
             )?> {
-                    BreadthFirstIterator::new(self)
+                    BreadthFirstIterator::new(self, usize::MAX)
+                }
+
+                /// Iterate the tree breadth first, starting with the root, without descending below the level.
+                ///
+                /// Yields the coarse representation of the tree, e.g. for a snapshot at a lower level of detail.
+                /// Yields nothing if the tree itself is below the level.
+                pub fn iter_to_level(&self, max_level: usize) -> BreadthFirstIterator<'_, T $(,
+                $n
+            )?> {
+                    BreadthFirstIterator::new(self, max_level)
                 }
             }
 
             pub struct BreadthFirstIterator<'a, T $(,
                 const $n: usize
             )?> {
-                queue: std::collections::VecDeque<&'a $crate::get_tree_type!( $( $n )?)>,
+                queue: alloc::collections::VecDeque<&'a $crate::get_tree_type!( $( $n )?)>,
+                max_level: usize,
             }
 
             impl<'a, T $(,
@@ -82,9 +93,13 @@ macro_rules! impl_breadth_first_iterator {
             )?> BreadthFirstIterator<'a, T $(,
                 $n
             )?> {
-                fn new(root: &'a $crate::get_tree_type!( $( $n )?)) -> Self {
+                fn new(root: &'a $crate::get_tree_type!( $( $n )?), max_level: usize) -> Self {
                     Self {
-                        queue: vec![root].into_iter().collect(),
+                        queue: Some(root)
+                            .filter(|root| root.level() <= max_level)
+                            .into_iter()
+                            .collect(),
+                        max_level,
                     }
                 }
             }
@@ -98,8 +113,10 @@ macro_rules! impl_breadth_first_iterator {
 
                 fn next(&mut self) -> Option<Self::Item> {
                     self.queue.pop_front().map(|tree| {
-                        for child in tree.iter_children() {
-                            self.queue.push_back(child);
+                        if tree.level() < self.max_level {
+                            for child in tree.iter_children() {
+                                self.queue.push_back(child);
+                            }
                         }
                         tree
                     })
@@ -107,3 +124,55 @@ macro_rules! impl_breadth_first_iterator {
             }
     };
 }
+
+#[macro_export]
+macro_rules! impl_levels_iterator {
+    ( $( $n:ident )? ) => {
+            impl<T $(,
+                const $n: usize
+            )?> $crate::get_tree_type!( $( $n )?) {
+                /// Iterate the tree level by level, starting with the root.
+                ///
+                /// Yields the level together with all trees on that level, ordered like in [`Self::iter_breadth_first`].
+                pub fn iter_levels(&self) -> LevelsIterator<'_, T $(,
+                $n
+            )?> {
+                    LevelsIterator::new(self)
+                }
+            }
+
+            pub struct LevelsIterator<'a, T $(,
+                const $n: usize
+            )?> {
+                level: alloc::vec::Vec<&'a $crate::get_tree_type!( $( $n )?)>,
+            }
+
+            impl<'a, T $(,
+                const $n: usize
+            )?> LevelsIterator<'a, T $(,
+                $n
+            )?> {
+                fn new(root: &'a $crate::get_tree_type!( $( $n )?)) -> Self {
+                    Self { level: alloc::vec![root] }
+                }
+            }
+
+            impl<'a, T $(,
+                const $n: usize
+            )?> Iterator for LevelsIterator<'a, T $(,
+                $n
+            )?> {
+                type Item = (usize, alloc::vec::Vec<&'a $crate::get_tree_type!( $( $n )?)>);
+
+                fn next(&mut self) -> Option<Self::Item> {
+                    let level = self.level.first()?.level();
+                    let next_level = self
+                        .level
+                        .iter()
+                        .flat_map(|tree| tree.iter_children())
+                        .collect();
+                    Some((level, core::mem::replace(&mut self.level, next_level)))
+                }
+            }
+    };
+}
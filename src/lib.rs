@@ -1,19 +1,73 @@
 //! This crate contains an N-dimensional generalization of a region quadtree called **T**wo-**p**ower-__n__-tree or tpntree,
 //! as there exist 2^N children per node, where N is the number of dimensions.
 //! A quadtree is the two-dimensional case, an octtree is the three-dimensional case of the tpntree.
+//!
+//! Without the default `std` feature the crate is `no_std` and only requires `alloc`, e.g. for embedded targets or WASM.
+//! It then needs the `libm` feature for floating point math.
+//! Histograms and the `concurrent` feature are only available with `std`.
+//!
+//! The `persist` feature, which implies `std`, adds saving and loading trees in a compact binary format with `TpnTree::save_to` and `TpnTree::load_from`.
+// the unit tests link `std` through the test harness anyway and use its prelude
+#![cfg_attr(all(not(feature = "std"), not(test)), no_std)]
 
+// Without `std` the modules import `num_traits::Float` for floating point math.
+// The imports are allowed to go unused, as linking `std` anywhere, e.g. through the test harness or a dev-dependency,
+// brings back the inherent float methods, which take precedence over the trait.
+#[cfg(not(any(feature = "std", feature = "libm")))]
+compile_error!("Either the `std` or the `libm` feature is required for floating point math.");
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+
+mod compare;
+mod dot;
 mod errors;
+mod fold;
+mod geometry;
 mod iterators;
+mod map;
+mod ops;
+mod pretty;
+mod statistics;
 pub mod tpntree;
 pub mod tpntree_dynamic;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
 pub use errors::TpnTreeError;
+pub use ops::{TpnTreeOps, TreeNode};
 
 /// [`Coordinates`] is required for a type to be used inside a [`tpntree::SpatialTree`].
 ///
 /// Be sure to return a slice of a length equal to const generic type parameter N.
+/// Spatial methods of the tree check the length and error with [`TpnTreeError::DimensionMismatch`] otherwise.
 pub trait Coordinates<const N: usize> {
     fn coordinates(&self) -> &[f64];
+
+    /// Returns the coordinates if there are exactly N of them.
+    ///
+    /// ```
+    /// # use tpntree::{Coordinates, TpnTreeError};
+    /// let data = vec![1.0, 2.0];
+    ///
+    /// assert!(Coordinates::<2>::try_coordinates(&data).is_ok());
+    /// assert_eq!(
+    ///     Coordinates::<3>::try_coordinates(&data),
+    ///     Err(TpnTreeError::DimensionMismatch { expected: 3, got: 2 })
+    /// );
+    /// ```
+    fn try_coordinates(&self) -> Result<&[f64], TpnTreeError> {
+        let coordinates = self.coordinates();
+        if coordinates.len() == N {
+            Ok(coordinates)
+        } else {
+            Err(TpnTreeError::DimensionMismatch {
+                expected: N,
+                got: coordinates.len(),
+            })
+        }
+    }
 }
 
 impl<const N: usize> Coordinates<N> for [f64; N] {
@@ -26,15 +80,83 @@ impl<const N: usize> Coordinates<N> for [f64; N] {
 impl<const N: usize> Coordinates<N> for Vec<f64> {
     /// Blanket implementation for vectors.
     ///
-    /// Panics if the length of the vec is different from N.
+    /// The length of the vec is not checked here, see [`Coordinates::try_coordinates`].
+    fn coordinates(&self) -> &[f64] {
+        self
+    }
+}
+
+/// [`CoordinatesDyn`] is required for a type to be used inside a [`tpntree_dynamic::SpatialTreeDyn`].
+///
+/// The dimension is only known at runtime, spatial methods of the tree compare the length of the slice to the dimension of the tree.
+pub trait CoordinatesDyn {
+    fn coordinates(&self) -> &[f64];
+}
+
+impl<const N: usize> CoordinatesDyn for [f64; N] {
+    /// Implementation for arrays of any length.
     fn coordinates(&self) -> &[f64] {
-        assert_eq!(
-            self.len(),
-            N,
-            "Expected vec of length {}, got vec of length {}.",
-            N,
-            self.len()
-        );
         self
     }
 }
+
+impl CoordinatesDyn for Vec<f64> {
+    /// Implementation for vectors.
+    fn coordinates(&self) -> &[f64] {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::{tpntree, tpntree_dynamic};
+
+    fn assert_send_sync<T: Send + Sync>(_: &T) {}
+
+    #[test]
+    fn trees_and_iterators_are_send_and_sync() {
+        let mut tree = tpntree::Tree3D::builder().leaf_capacity(1).build();
+        tree.insert([0.5, 0.5, 0.5]).unwrap();
+        let dynamic = tpntree_dynamic::TpnTree::<Vec<f64>>::root(1.0, 3);
+
+        assert_send_sync(&tree);
+        assert_send_sync(&tree.iter_depth_first());
+        assert_send_sync(&tree.iter_breadth_first());
+        assert_send_sync(&tree.iter_levels());
+        assert_send_sync(&tree.iter_depth_first_in_region([0.0; 3], [1.0; 3]));
+        assert_send_sync(&tree.iter_lod([0.0; 3], |_| 1));
+        assert_send_sync(&dynamic);
+        assert_send_sync(&dynamic.iter_depth_first());
+        assert_send_sync(&tree.snapshot());
+        assert_send_sync(&tpntree::PersistentTree::from_tree(&tree));
+        assert_send_sync(&tpntree::IndexedTree::<[f64; 3], 3>::root(1.0));
+        assert_send_sync(&tpntree::ArenaTree::<u8, 3>::root(1.0));
+        assert_send_sync(&tree.to_linear());
+    }
+
+    #[test]
+    fn shared_trees_are_queried_from_threads() {
+        let mut tree = tpntree::Tree3D::builder().leaf_capacity(2).build();
+        for i in 0..20 {
+            let i = f64::from(i) / 20.0;
+            tree.insert([i, -i, i * i]).unwrap();
+        }
+        let shared = Arc::new(tree);
+
+        let counts = (0..4)
+            .map(|thread| {
+                let snapshot = tpntree::TreeSnapshot::from(Arc::clone(&shared));
+                std::thread::spawn(move || {
+                    let radius = 0.25 * f64::from(thread + 1);
+                    snapshot.within_radius(&[0.0; 3], radius).len()
+                })
+            })
+            .map(|handle| handle.join().unwrap())
+            .collect::<Vec<_>>();
+
+        assert!(counts.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert_eq!(counts[3], shared.within_radius(&[0.0; 3], 1.0).len());
+    }
+}
@@ -0,0 +1,294 @@
+use crate::{tpntree, tpntree_dynamic, TpnTreeError};
+
+impl<T, const N: usize> tpntree::TpnTree<T, N> {
+    /// Rebuilds the tree with the same structure, transforming the data of every tree with `f`.
+    ///
+    /// The function sees the trees in depth first order, each tree before its children, the children in index order.
+    /// The configuration of the tree is kept.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::TpnTree;
+    /// let mut tree = TpnTree::<Vec<f64>, 2>::root(1.0);
+    /// tree.divide().expect("Couldn't divide.");
+    /// *tree.get_child_mut(0).unwrap().data_mut() = Some(vec![1.0, 2.0]);
+    ///
+    /// let counts = tree.map(|data| data.map(|samples| samples.len()));
+    ///
+    /// assert_eq!(counts.get_child(0).and_then(|child| child.data()), Some(&2));
+    /// assert_eq!(counts.data(), None);
+    /// ```
+    pub fn map<U>(self, mut f: impl FnMut(Option<T>) -> Option<U>) -> tpntree::TpnTree<U, N> {
+        self.map_with(&mut f)
+    }
+
+    /// Builds a tree with the same structure from the transformed data of every tree, leaving the tree untouched.
+    ///
+    /// See [`tpntree::TpnTree::map`] for the order `f` is called in.
+    pub fn map_ref<U>(&self, mut f: impl FnMut(Option<&T>) -> Option<U>) -> tpntree::TpnTree<U, N> {
+        self.map_ref_with(&mut f)
+    }
+
+    /// Builds a tree with the same structure combining the data of both trees tree by tree.
+    ///
+    /// Errors with [`TpnTreeError::StructureMismatch`] if the trees are not [structurally equal](tpntree::TpnTree::structurally_equal).
+    /// The function sees the trees in the same order as in [`tpntree::TpnTree::map`], the configuration of `self` is kept.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::TpnTree;
+    /// let mut before = TpnTree::<f64, 2>::root(1.0);
+    /// before.divide().expect("Couldn't divide.");
+    /// *before.get_child_mut(0).unwrap().data_mut() = Some(1.0);
+    ///
+    /// let mut after = before.clone();
+    /// *after.get_child_mut(0).unwrap().data_mut() = Some(1.5);
+    ///
+    /// let change = before
+    ///     .zip_with(&after, |a, b| a.zip(b).map(|(a, b)| b - a))
+    ///     .expect("Same structure.");
+    ///
+    /// assert_eq!(change.get_child(0).and_then(|child| child.data()), Some(&0.5));
+    /// ```
+    pub fn zip_with<U, V>(
+        &self,
+        other: &tpntree::TpnTree<U, N>,
+        mut f: impl FnMut(Option<&T>, Option<&U>) -> Option<V>,
+    ) -> Result<tpntree::TpnTree<V, N>, TpnTreeError> {
+        if self.structurally_equal(other) {
+            Ok(self.zip_with_unchecked(other, &mut f))
+        } else {
+            Err(TpnTreeError::StructureMismatch)
+        }
+    }
+
+    fn map_with<U, F: FnMut(Option<T>) -> Option<U>>(self, f: &mut F) -> tpntree::TpnTree<U, N> {
+        let data = f(self.data);
+        tpntree::TpnTree {
+            coordinates: self.coordinates,
+            span: self.span,
+            level: self.level,
+            data,
+            children: self
+                .children
+                .into_iter()
+                .map(|child| child.map_with(f))
+                .collect(),
+            split_axes: self.split_axes,
+            branching: self.branching,
+            split_point: self.split_point,
+            config: self.config,
+        }
+    }
+
+    fn map_ref_with<U, F: FnMut(Option<&T>) -> Option<U>>(
+        &self,
+        f: &mut F,
+    ) -> tpntree::TpnTree<U, N> {
+        let data = f(self.data.as_ref());
+        tpntree::TpnTree {
+            coordinates: self.coordinates,
+            span: self.span,
+            level: self.level,
+            data,
+            children: self
+                .children
+                .iter()
+                .map(|child| child.map_ref_with(f))
+                .collect(),
+            split_axes: self.split_axes,
+            branching: self.branching,
+            split_point: self.split_point,
+            config: self.config.clone(),
+        }
+    }
+
+    // assumes both trees to be structurally equal
+    fn zip_with_unchecked<U, V, F: FnMut(Option<&T>, Option<&U>) -> Option<V>>(
+        &self,
+        other: &tpntree::TpnTree<U, N>,
+        f: &mut F,
+    ) -> tpntree::TpnTree<V, N> {
+        let data = f(self.data.as_ref(), other.data.as_ref());
+        tpntree::TpnTree {
+            coordinates: self.coordinates,
+            span: self.span,
+            level: self.level,
+            data,
+            children: self
+                .children
+                .iter()
+                .zip(other.children.iter())
+                .map(|(child, other_child)| child.zip_with_unchecked(other_child, f))
+                .collect(),
+            split_axes: self.split_axes,
+            branching: self.branching,
+            split_point: self.split_point,
+            config: self.config.clone(),
+        }
+    }
+}
+
+impl<T> tpntree_dynamic::TpnTree<T> {
+    /// Rebuilds the tree with the same structure, transforming the data of every tree with `f`.
+    ///
+    /// The function sees the trees in depth first order, each tree before its children, the children in index order.
+    pub fn map<U>(self, mut f: impl FnMut(Option<T>) -> Option<U>) -> tpntree_dynamic::TpnTree<U> {
+        self.map_with(&mut f)
+    }
+
+    /// Builds a tree with the same structure from the transformed data of every tree, leaving the tree untouched.
+    ///
+    /// See [`tpntree_dynamic::TpnTree::map`] for the order `f` is called in.
+    pub fn map_ref<U>(
+        &self,
+        mut f: impl FnMut(Option<&T>) -> Option<U>,
+    ) -> tpntree_dynamic::TpnTree<U> {
+        self.map_ref_with(&mut f)
+    }
+
+    /// Builds a tree with the same structure combining the data of both trees tree by tree.
+    ///
+    /// Errors with [`TpnTreeError::StructureMismatch`] if the trees are not [structurally equal](tpntree_dynamic::TpnTree::structurally_equal).
+    /// The function sees the trees in the same order as in [`tpntree_dynamic::TpnTree::map`].
+    pub fn zip_with<U, V>(
+        &self,
+        other: &tpntree_dynamic::TpnTree<U>,
+        mut f: impl FnMut(Option<&T>, Option<&U>) -> Option<V>,
+    ) -> Result<tpntree_dynamic::TpnTree<V>, TpnTreeError> {
+        if self.structurally_equal(other) {
+            Ok(self.zip_with_unchecked(other, &mut f))
+        } else {
+            Err(TpnTreeError::StructureMismatch)
+        }
+    }
+
+    fn map_with<U, F: FnMut(Option<T>) -> Option<U>>(
+        self,
+        f: &mut F,
+    ) -> tpntree_dynamic::TpnTree<U> {
+        let data = f(self.data);
+        tpntree_dynamic::TpnTree {
+            coordinates: self.coordinates,
+            span: self.span,
+            level: self.level,
+            data,
+            children: self
+                .children
+                .into_iter()
+                .map(|child| child.map_with(f))
+                .collect(),
+        }
+    }
+
+    fn map_ref_with<U, F: FnMut(Option<&T>) -> Option<U>>(
+        &self,
+        f: &mut F,
+    ) -> tpntree_dynamic::TpnTree<U> {
+        let data = f(self.data.as_ref());
+        tpntree_dynamic::TpnTree {
+            coordinates: self.coordinates.clone(),
+            span: self.span.clone(),
+            level: self.level,
+            data,
+            children: self
+                .children
+                .iter()
+                .map(|child| child.map_ref_with(f))
+                .collect(),
+        }
+    }
+
+    // assumes both trees to be structurally equal
+    fn zip_with_unchecked<U, V, F: FnMut(Option<&T>, Option<&U>) -> Option<V>>(
+        &self,
+        other: &tpntree_dynamic::TpnTree<U>,
+        f: &mut F,
+    ) -> tpntree_dynamic::TpnTree<V> {
+        let data = f(self.data.as_ref(), other.data.as_ref());
+        tpntree_dynamic::TpnTree {
+            coordinates: self.coordinates.clone(),
+            span: self.span.clone(),
+            level: self.level,
+            data,
+            children: self
+                .children
+                .iter()
+                .zip(other.children.iter())
+                .map(|(child, other_child)| child.zip_with_unchecked(other_child, f))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{tpntree, tpntree_dynamic, TpnTreeError};
+
+    #[test]
+    fn map_keeps_structure_and_order() {
+        let mut tree = tpntree::TpnTree::<usize, 2>::builder().max_depth(3).build();
+        assert!(tree.divide().is_ok());
+        assert!(tree.get_child_mut(2).unwrap().divide().is_ok());
+
+        let mut visited = 0;
+        let mapped = tree.map_ref(|data| {
+            visited += 1;
+            Some(data.copied().unwrap_or(visited))
+        });
+
+        assert!(mapped.structurally_equal(&tree));
+        assert_eq!(mapped.config(), tree.config());
+        assert_eq!(
+            mapped
+                .iter_breadth_first()
+                .map(|tree| tree.data().copied())
+                .take(5)
+                .collect::<Vec<_>>(),
+            vec![Some(1), Some(2), Some(3), Some(4), Some(9)]
+        );
+
+        let strings = mapped.map(|data| data.map(|number| number.to_string()));
+        assert_eq!(strings.data(), Some(&"1".to_string()));
+    }
+
+    #[test]
+    fn zip_requires_equal_structure() {
+        let mut tree = tpntree::TpnTree::<u8, 2>::root(1.0);
+        let mut other = tpntree::TpnTree::<u8, 2>::root(1.0);
+        assert!(tree.divide().is_ok());
+
+        assert_eq!(
+            tree.zip_with(&other, |_, _| Some(())),
+            Err(TpnTreeError::StructureMismatch)
+        );
+
+        assert!(other.divide().is_ok());
+        *tree.data_mut() = Some(1);
+        *other.get_child_mut(1).unwrap().data_mut() = Some(2);
+
+        let zipped = tree
+            .zip_with(&other, |a, b| Some((a.copied(), b.copied())))
+            .unwrap();
+        assert_eq!(zipped.data(), Some(&(Some(1), None)));
+        assert_eq!(zipped.get_child(1).unwrap().data(), Some(&(None, Some(2))));
+
+        let dynamic = tpntree_dynamic::TpnTree::<u8>::root(1.0, 2);
+        assert!(dynamic
+            .zip_with(&tpntree_dynamic::TpnTree::<u8>::root(1.0, 3), |_, _| Some(
+                ()
+            ))
+            .is_err());
+    }
+
+    #[test]
+    fn map_dynamic() {
+        let mut tree = tpntree_dynamic::TpnTree::<u8>::root(1.0, 3);
+        assert!(tree.divide().is_ok());
+        *tree.get_child_mut(7).unwrap().data_mut() = Some(2);
+
+        let doubled = tree.map(|data| data.map(|value| u16::from(value) * 2));
+
+        assert_eq!(doubled.child_count(), 8);
+        assert_eq!(doubled.get_child(7).unwrap().data(), &Some(4));
+        assert_eq!(doubled.data(), &None);
+    }
+}
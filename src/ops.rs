@@ -0,0 +1,251 @@
+use crate::{errors::TpnTreeError, tpntree, tpntree_dynamic};
+
+/// [`TpnTreeOps`] is implemented by both [`tpntree::TpnTree`] and [`tpntree_dynamic::TpnTree`].
+///
+/// It allows to write code working with either kind of tree.
+///
+/// ```
+/// # use tpntree::{TpnTreeOps, TpnTreeError};
+/// fn divide_root<T>(tree: &mut impl TpnTreeOps<T>) -> Result<(), TpnTreeError> {
+///     assert!(tree.is_root());
+///     tree.divide()
+/// }
+///
+/// let mut static_tree = tpntree::tpntree::TpnTree::<(), 2>::root(1.0);
+/// let mut dynamic_tree = tpntree::tpntree_dynamic::TpnTree::<()>::root(1.0, 2);
+///
+/// assert!(divide_root(&mut static_tree).is_ok());
+/// assert!(divide_root(&mut dynamic_tree).is_ok());
+/// assert_eq!(static_tree.child_count(), dynamic_tree.child_count());
+/// ```
+pub trait TpnTreeOps<T> {
+    /// Divides the tree into subregions creating new trees as children.
+    ///
+    /// Errors if the tree has been divided before.
+    fn divide(&mut self) -> Result<(), TpnTreeError>;
+
+    /// Returns the count of direct children.
+    fn child_count(&self) -> usize;
+
+    /// Returns the level of the tree.
+    fn level(&self) -> usize;
+
+    /// Returns wheter the tree is a root.
+    fn is_root(&self) -> bool;
+
+    /// Returns wheter the tree is a leaf.
+    fn is_leaf(&self) -> bool;
+}
+
+/// [`TreeNode`] gives access to the structure and content of both [`tpntree::TpnTree`] and [`tpntree_dynamic::TpnTree`].
+///
+/// Algorithms like queries, visitors or serializers can be written once against this trait.
+///
+/// ```
+/// # use tpntree::TreeNode;
+/// fn count_nodes<T>(tree: &impl TreeNode<T>) -> usize {
+///     1 + tree.children().iter().map(count_nodes).sum::<usize>()
+/// }
+///
+/// let mut tree = tpntree::tpntree_dynamic::TpnTree::<()>::root(1.0, 2);
+/// tree.divide().expect("Couldn't divide.");
+///
+/// assert_eq!(count_nodes(&tree), 5);
+/// ```
+pub trait TreeNode<T>: TpnTreeOps<T> + Sized {
+    /// Returns the direct children as a slice.
+    fn children(&self) -> &[Self];
+
+    /// Returns the direct children as a mutable slice.
+    fn children_mut(&mut self) -> &mut [Self];
+
+    /// Returns the data by reference of the tree.
+    fn data(&self) -> Option<&T>;
+
+    /// Returns the data by mutable reference of the tree.
+    fn data_mut(&mut self) -> &mut Option<T>;
+
+    /// Returns the coordinates of the center of the tree.
+    fn coordinates(&self) -> &[f64];
+
+    /// Returns the span of the tree.
+    fn span(&self) -> &[f64];
+
+    /// Returns the number of dimensions of the tree.
+    fn dimensions(&self) -> usize {
+        self.coordinates().len()
+    }
+}
+
+impl<T, const N: usize> TpnTreeOps<T> for tpntree::TpnTree<T, N> {
+    fn divide(&mut self) -> Result<(), TpnTreeError> {
+        self.divide()
+    }
+
+    fn child_count(&self) -> usize {
+        self.child_count()
+    }
+
+    fn level(&self) -> usize {
+        self.level()
+    }
+
+    fn is_root(&self) -> bool {
+        self.is_root()
+    }
+
+    fn is_leaf(&self) -> bool {
+        self.is_leaf()
+    }
+}
+
+impl<T, const N: usize> TreeNode<T> for tpntree::TpnTree<T, N> {
+    fn children(&self) -> &[Self] {
+        &self.children
+    }
+
+    fn children_mut(&mut self) -> &mut [Self] {
+        &mut self.children
+    }
+
+    fn data(&self) -> Option<&T> {
+        self.data.as_ref()
+    }
+
+    fn data_mut(&mut self) -> &mut Option<T> {
+        &mut self.data
+    }
+
+    fn coordinates(&self) -> &[f64] {
+        &self.coordinates
+    }
+
+    fn span(&self) -> &[f64] {
+        &self.span
+    }
+}
+
+impl<T> TpnTreeOps<T> for tpntree_dynamic::TpnTree<T> {
+    fn divide(&mut self) -> Result<(), TpnTreeError> {
+        self.divide()
+    }
+
+    fn child_count(&self) -> usize {
+        self.child_count()
+    }
+
+    fn level(&self) -> usize {
+        self.level()
+    }
+
+    fn is_root(&self) -> bool {
+        self.is_root()
+    }
+
+    fn is_leaf(&self) -> bool {
+        self.is_leaf()
+    }
+}
+
+impl<T> TreeNode<T> for tpntree_dynamic::TpnTree<T> {
+    fn children(&self) -> &[Self] {
+        &self.children
+    }
+
+    fn children_mut(&mut self) -> &mut [Self] {
+        &mut self.children
+    }
+
+    fn data(&self) -> Option<&T> {
+        self.data.as_ref()
+    }
+
+    fn data_mut(&mut self) -> &mut Option<T> {
+        &mut self.data
+    }
+
+    fn coordinates(&self) -> &[f64] {
+        &self.coordinates
+    }
+
+    fn span(&self) -> &[f64] {
+        &self.span
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use super::{TpnTreeOps, TreeNode};
+    use crate::{tpntree, tpntree_dynamic};
+
+    fn sum_leaf_data(tree: &impl TreeNode<f64>) -> f64 {
+        if tree.is_leaf() {
+            tree.data().copied().unwrap_or(0.0)
+        } else {
+            tree.children().iter().map(sum_leaf_data).sum()
+        }
+    }
+
+    fn number_leaves(tree: &mut impl TreeNode<f64>) {
+        for (i, child) in tree.children_mut().iter_mut().enumerate() {
+            *child.data_mut() = Some(i as f64);
+        }
+    }
+
+    fn divide_twice<T>(tree: &mut impl TpnTreeOps<T>) {
+        assert!(tree.is_leaf());
+        assert!(tree.divide().is_ok());
+        assert!(!tree.is_leaf());
+        assert!(tree.divide().is_err());
+    }
+
+    #[test]
+    fn divide_static_and_dynamic_generically() {
+        let mut static_tree = tpntree::TpnTree::<(), 3>::root(1.0);
+        let mut dynamic_tree = tpntree_dynamic::TpnTree::<()>::root(1.0, 3);
+
+        divide_twice(&mut static_tree);
+        divide_twice(&mut dynamic_tree);
+
+        assert_eq!(static_tree.child_count(), 8);
+        assert_eq!(dynamic_tree.child_count(), 8);
+    }
+
+    #[test]
+    fn sum_leaf_data_generically() {
+        let mut static_tree = tpntree::TpnTree::<f64, 2>::root(1.0);
+        let mut dynamic_tree = tpntree_dynamic::TpnTree::<f64>::root(1.0, 2);
+
+        assert!(static_tree.divide().is_ok());
+        assert!(dynamic_tree.divide().is_ok());
+        number_leaves(&mut static_tree);
+        number_leaves(&mut dynamic_tree);
+
+        assert_eq!(sum_leaf_data(&static_tree), 6.0);
+        assert_eq!(sum_leaf_data(&dynamic_tree), 6.0);
+    }
+
+    #[test]
+    fn coordinates_and_span_as_slices() {
+        let static_tree = tpntree::TpnTree::<(), 2>::new([1.0, 2.0], [0.5, 0.25], 0);
+        let dynamic_tree =
+            tpntree_dynamic::TpnTree::<()>::new(vec![1.0, 2.0], vec![0.5, 0.25], 0).unwrap();
+
+        assert_eq!(
+            TreeNode::coordinates(&static_tree),
+            TreeNode::coordinates(&dynamic_tree)
+        );
+        assert_eq!(TreeNode::span(&static_tree), TreeNode::span(&dynamic_tree));
+        assert_eq!(static_tree.dimensions(), 2);
+    }
+
+    #[test]
+    fn children_are_not_roots() {
+        let mut dynamic_tree = tpntree_dynamic::TpnTree::<()>::root(1.0, 2);
+
+        assert!(dynamic_tree.is_root());
+        assert!(dynamic_tree.divide().is_ok());
+        assert!(dynamic_tree.iter_children().all(|c| !c.is_root()));
+    }
+}
@@ -0,0 +1,118 @@
+use alloc::string::String;
+use core::{
+    fmt::{Display, Formatter, Result},
+    marker::PhantomData,
+};
+
+use crate::TreeNode;
+
+// displays the tree as outline, the summary is appended for trees holding data
+struct Pretty<'a, T, Tree, F> {
+    tree: &'a Tree,
+    summary: F,
+    data: PhantomData<fn(&T)>,
+}
+
+impl<'a, T, Tree: TreeNode<T>, F: Fn(&T) -> String> Pretty<'a, T, Tree, F> {
+    fn write_tree(&self, tree: &Tree, f: &mut Formatter<'_>) -> Result {
+        write!(
+            f,
+            "{:indent$}level {} center {:?} span {:?}",
+            "",
+            tree.level(),
+            tree.coordinates(),
+            tree.span(),
+            indent = 2 * (tree.level() - self.tree.level())
+        )?;
+        if let Some(data) = tree.data() {
+            write!(f, ": {}", (self.summary)(data))?;
+        }
+        writeln!(f)?;
+        for child in tree.children() {
+            self.write_tree(child, f)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a, T, Tree: TreeNode<T>, F: Fn(&T) -> String> Display for Pretty<'a, T, Tree, F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        self.write_tree(self.tree, f)
+    }
+}
+
+macro_rules! impl_pretty {
+    ( $( $n:ident )? ) => {
+        impl<T $(, const $n: usize)?> $crate::get_tree_type!( $( $n )?) {
+            /// Displays the tree as an indented outline with one line per tree, showing level, center and span.
+            ///
+            /// ```
+            /// # use tpntree::tpntree::TpnTree;
+            /// let mut root = TpnTree::<(), 1>::root(1.0);
+            /// root.divide().expect("Couldn't divide.");
+            ///
+            /// assert_eq!(
+            ///     root.pretty().to_string(),
+            ///     "level 0 center [0.0] span [1.0]\n  level 1 center [0.5] span [0.5]\n  level 1 center [-0.5] span [0.5]\n"
+            /// );
+            /// ```
+            pub fn pretty(&self) -> impl Display + '_ {
+                self.pretty_with(|_| String::from("data"))
+            }
+
+            /// Displays the tree like [`Self::pretty`], summarizing the data of each tree with `summary`.
+            pub fn pretty_with<'a>(&'a self, summary: impl Fn(&T) -> String + 'a) -> impl Display + 'a {
+                Pretty {
+                    tree: self,
+                    summary,
+                    data: PhantomData,
+                }
+            }
+        }
+    };
+}
+
+impl_pretty!(N);
+impl_pretty!();
+
+#[cfg(test)]
+mod tests {
+    use crate::{tpntree, tpntree_dynamic};
+
+    #[test]
+    fn pretty_marks_data() {
+        let mut tree = tpntree::TpnTree::<u8, 1>::root(1.0);
+
+        *tree.data_mut() = Some(1);
+
+        assert_eq!(
+            tree.pretty().to_string(),
+            "level 0 center [0.0] span [1.0]: data\n"
+        );
+    }
+
+    #[test]
+    fn pretty_with_summary_indents_by_level() {
+        let mut tree = tpntree_dynamic::TpnTree::<Vec<u8>>::root(2.0, 1);
+
+        assert!(tree.divide().is_ok());
+        assert!(tree.get_child_mut(1).unwrap().divide().is_ok());
+        *tree
+            .get_child_mut(1)
+            .unwrap()
+            .get_child_mut(0)
+            .unwrap()
+            .data_mut() = Some(vec![1, 2]);
+
+        let pretty = tree
+            .get_child(1)
+            .unwrap()
+            .pretty_with(|data| format!("{} items", data.len()))
+            .to_string();
+
+        assert_eq!(
+            pretty,
+            "level 1 center [-1.0] span [1.0]\n  level 2 center [-0.5] span [0.5]: 2 items\n  level 2 center [-1.5] span [0.5]\n"
+        );
+    }
+}
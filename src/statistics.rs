@@ -0,0 +1,231 @@
+use alloc::{vec, vec::Vec};
+#[cfg(feature = "std")]
+use core::hash::Hash;
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use num_traits::Float;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+use crate::TreeNode;
+
+// values of the direct children, missing data counts as zero
+fn children_values<T: Into<f64> + Copy, Tree: TreeNode<T>>(tree: &Tree) -> Vec<f64> {
+    tree.children()
+        .iter()
+        .map(|child| child.data().map_or(0.0, |&data| data.into()))
+        .collect()
+}
+
+// values of all trees in the subtree holding data, including the tree itself
+fn subtree_values<T: Into<f64> + Copy, Tree: TreeNode<T>>(tree: &Tree) -> Vec<f64> {
+    let mut values = Vec::new();
+    let mut stack = vec![tree];
+    while let Some(tree) = stack.pop() {
+        values.extend(tree.data().map(|&data| data.into()));
+        stack.extend(tree.children());
+    }
+    values
+}
+
+fn sum(values: &[f64]) -> f64 {
+    values.iter().sum()
+}
+
+fn mean(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        0.0
+    } else {
+        sum(values) / values.len() as f64
+    }
+}
+
+// population variance
+fn variance(values: &[f64]) -> f64 {
+    let values_mean = mean(values);
+    let squared_deviations = values
+        .iter()
+        .map(|value| (value - values_mean).powi(2))
+        .collect::<Vec<_>>();
+    mean(&squared_deviations)
+}
+
+// counts the data of all leaves per bucket
+#[cfg(feature = "std")]
+fn histogram<T, K: Eq + Hash, Tree: TreeNode<T>>(
+    tree: &Tree,
+    bucket_fn: impl Fn(&T) -> K,
+) -> HashMap<K, usize> {
+    let mut counts = HashMap::new();
+    let mut stack = vec![tree];
+    while let Some(tree) = stack.pop() {
+        if tree.is_leaf() {
+            if let Some(data) = tree.data() {
+                *counts.entry(bucket_fn(data)).or_insert(0) += 1;
+            }
+        }
+        stack.extend(tree.children());
+    }
+    counts
+}
+
+// counts the trees per depth below the tree, the tree itself is at depth zero
+fn counts_per_level<T, Tree: TreeNode<T>>(tree: &Tree) -> Vec<usize> {
+    let mut counts = Vec::new();
+    let mut level = vec![tree];
+    while !level.is_empty() {
+        counts.push(level.len());
+        level = level.iter().flat_map(|tree| tree.children()).collect();
+    }
+    counts
+}
+
+macro_rules! impl_statistics {
+    ( $( $n:ident )? ) => {
+        /// Statistics over numeric data.
+        ///
+        /// The plain methods look at the direct children, where missing data counts as zero, and are zero without children.
+        /// The `subtree_` methods look at all trees of the subtree holding data, including the tree itself, and are zero if there is none.
+        impl<T: Into<f64> + Copy $(, const $n: usize)?> $crate::get_tree_type!( $( $n )?) {
+            /// Calculate the sum of the data of the direct children.
+            pub fn sum(&self) -> f64 {
+                sum(&children_values(self))
+            }
+
+            /// Calculate the mean of the data of the direct children.
+            pub fn mean(&self) -> f64 {
+                mean(&children_values(self))
+            }
+
+            /// Calculate the population variance of the data of the direct children.
+            pub fn variance(&self) -> f64 {
+                variance(&children_values(self))
+            }
+
+            /// Calculate the sum of the data in the subtree.
+            pub fn subtree_sum(&self) -> f64 {
+                sum(&subtree_values(self))
+            }
+
+            /// Calculate the mean of the data in the subtree.
+            pub fn subtree_mean(&self) -> f64 {
+                mean(&subtree_values(self))
+            }
+
+            /// Calculate the population variance of the data in the subtree.
+            pub fn subtree_variance(&self) -> f64 {
+                variance(&subtree_values(self))
+            }
+        }
+    };
+}
+
+impl_statistics!(N);
+impl_statistics!();
+
+macro_rules! impl_counts {
+    ( $( $n:ident )? ) => {
+        impl<T $(, const $n: usize)?> $crate::get_tree_type!( $( $n )?) {
+            /// Counts the data of all leaves per bucket returned by `bucket_fn`, leaves without data are not counted.
+            ///
+            /// ```
+            /// # use tpntree::tpntree::TpnTree;
+            /// let mut tree = TpnTree::<u8, 1>::root(1.0);
+            /// tree.divide().expect("Couldn't divide.");
+            /// *tree.get_child_mut(0).unwrap().data_mut() = Some(3);
+            /// *tree.get_child_mut(1).unwrap().data_mut() = Some(5);
+            ///
+            /// let histogram = tree.histogram(|data| data % 2 == 1);
+            ///
+            /// assert_eq!(histogram.get(&true), Some(&2));
+            /// assert_eq!(histogram.get(&false), None);
+            /// ```
+            #[cfg(feature = "std")]
+            pub fn histogram<K: Eq + Hash>(&self, bucket_fn: impl Fn(&T) -> K) -> HashMap<K, usize> {
+                histogram(self, bucket_fn)
+            }
+
+            /// Counts the trees per depth below the tree, starting with the tree itself at index zero.
+            pub fn counts_per_level(&self) -> Vec<usize> {
+                counts_per_level(self)
+            }
+        }
+    };
+}
+
+impl_counts!(N);
+impl_counts!();
+
+#[cfg(test)]
+mod tests {
+    use crate::{tpntree, tpntree_dynamic};
+
+    #[test]
+    fn calculate_variance_alone() {
+        let tree = tpntree::TpnTree::<f64, 2>::root(1.0);
+
+        assert!(tree.variance() < f64::EPSILON);
+        assert!(tree.subtree_variance() < f64::EPSILON);
+    }
+
+    #[test]
+    fn calculate_children_statistics() {
+        let mut tree = tpntree::TpnTree::<f64, 2>::root(1.0);
+
+        assert!(tree.divide().is_ok());
+
+        for (i, c) in tree.iter_children_mut().enumerate() {
+            c.data = Some(i as f64)
+        }
+
+        assert!((tree.sum() - 6.0).abs() < f64::EPSILON);
+        assert!((tree.mean() - 1.5).abs() < f64::EPSILON);
+        // population variance of 0,1,2,3
+        assert!((tree.variance() - 1.25).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn calculate_subtree_statistics_for_integers() {
+        let mut tree = tpntree_dynamic::TpnTree::<u8>::root(1.0, 1);
+
+        assert!(tree.divide().is_ok());
+        assert!(tree.get_child_mut(0).unwrap().divide().is_ok());
+
+        *tree.data_mut() = Some(4);
+        *tree
+            .get_child_mut(0)
+            .unwrap()
+            .get_child_mut(1)
+            .unwrap()
+            .data_mut() = Some(2);
+
+        assert!((tree.subtree_sum() - 6.0).abs() < f64::EPSILON);
+        assert!((tree.subtree_mean() - 3.0).abs() < f64::EPSILON);
+        assert!((tree.subtree_variance() - 1.0).abs() < f64::EPSILON);
+        // the children have no data
+        assert!((tree.mean() - 0.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn count_leaves_and_levels() {
+        let mut tree = tpntree_dynamic::TpnTree::<usize>::root(1.0, 2);
+
+        assert!(tree.divide().is_ok());
+        assert!(tree.get_child_mut(2).unwrap().divide().is_ok());
+        for (index, child) in tree.iter_children_mut().enumerate() {
+            child.data = Some(index);
+        }
+        // data of trees that are not leaves is not counted
+        tree.data = Some(0);
+
+        #[cfg(feature = "std")]
+        {
+            let histogram = tree.histogram(|&index| index < 2);
+            assert_eq!(histogram.get(&true), Some(&2));
+            assert_eq!(histogram.get(&false), Some(&1));
+        }
+
+        assert_eq!(tree.counts_per_level(), vec![1, 4, 4]);
+        assert_eq!(tree.get_child(2).unwrap().counts_per_level(), vec![1, 4]);
+    }
+}
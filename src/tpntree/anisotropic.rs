@@ -0,0 +1,187 @@
+use alloc::vec::Vec;
+
+use super::{off_center, TpnTree};
+use crate::errors::TpnTreeError;
+
+impl<T, const N: usize> TpnTree<T, N> {
+    /// Divides the tree along the given axes only, creating 2^k children for k distinct axes.
+    ///
+    /// Use this for data that is thin along some axes, e.g. terrain that is much wider than it is high.
+    /// Bit `i` of a child index selects the lower half along the i-th split axis in ascending order,
+    /// so dividing along all axes is the same as [`TpnTree::divide`].
+    ///
+    /// Errors with [`TpnTreeError::NoSuchAxis`] for an axis not below N and otherwise like [`TpnTree::divide`].
+    /// The linear, arena and persistent representations assume trees divided along all axes.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::TpnTree;
+    /// let mut root = TpnTree::<(), 3>::root(1.0);
+    ///
+    /// root.divide_axes(&[0, 2]).expect("Couldn't divide.");
+    ///
+    /// assert_eq!(root.child_count(), 4);
+    /// assert_eq!(root.split_axes(), vec![0, 2]);
+    /// assert_eq!(root.get_child(2).unwrap().coordinates(), [0.5, 0.0, -0.5]);
+    /// assert_eq!(root.get_child(2).unwrap().span(), [0.5, 1.0, 0.5]);
+    /// assert_eq!(root.child_index_for_point(&[0.5, 0.9, -0.5]), 2);
+    /// ```
+    pub fn divide_axes(&mut self, axes: &[usize]) -> Result<(), TpnTreeError> {
+        let mut split_axes = 0_usize;
+        for &axis in axes {
+            if axis >= N {
+                return Err(TpnTreeError::NoSuchAxis { axis });
+            }
+            split_axes |= 1 << axis;
+        }
+        let all = split_axes.count_ones() as usize == N;
+        self.divide_along((!all).then_some(split_axes), 2, None)
+    }
+
+    /// Returns the axes the children split the tree along in ascending order, or nothing for a leaf.
+    pub fn split_axes(&self) -> Vec<usize> {
+        if self.is_leaf() {
+            return Vec::new();
+        }
+        self.split_axes_iter().collect()
+    }
+
+    // the axes the tree is or would be split along in ascending order
+    pub(crate) fn split_axes_iter(&self) -> impl DoubleEndedIterator<Item = usize> {
+        let split_axes = self.split_axes.unwrap_or(usize::MAX);
+        (0..N).filter(move |axis| split_axes & 1 << axis != 0)
+    }
+
+    // turns the tree into a leaf, forgetting the axes it was split along
+    pub(crate) fn clear_children(&mut self) {
+        self.children.clear();
+        self.split_axes = None;
+        self.branching = 2;
+        self.split_point = None;
+    }
+
+    // the count of children the tree has or would have when divided along its split axes
+    pub(crate) fn split_child_count(&self) -> usize {
+        self.branching
+            .pow(self.split_axes.map_or(N as u32, |axes| axes.count_ones()))
+    }
+
+    // the index of the child covering the coordinates within the axes the tree is split along
+    pub(crate) fn split_child_index(&self, coordinates: &[f64]) -> usize {
+        if self.branching != 2 {
+            return self.branching_child_index(coordinates);
+        }
+        if let Some(split_point) = self.split_point {
+            return off_center::child_index_at(&split_point, coordinates);
+        }
+        match self.split_axes {
+            Some(axes) => compact_index(self.child_index_generic(coordinates), axes),
+            None => self.centered_child_index(coordinates),
+        }
+    }
+
+    // the region of the child at the index within the axes the tree is split along
+    pub(crate) fn split_child_region(&self, index: usize) -> ([f64; N], [f64; N]) {
+        if self.branching != 2 {
+            return self.branching_child_region(index);
+        }
+        if let Some(split_point) = self.split_point {
+            return self.off_center_child_region(&split_point, index);
+        }
+        let Some(axes) = self.split_axes else {
+            return self.centered_child_region(index);
+        };
+        let mut coordinates = self.coordinates;
+        let mut span = self.span;
+        for (bit, axis) in (0..N).filter(|axis| axes & 1 << axis != 0).enumerate() {
+            span[axis] /= 2.0;
+            if index & 1 << bit == 0 {
+                coordinates[axis] += span[axis];
+            } else {
+                coordinates[axis] -= span[axis];
+            }
+        }
+        (coordinates, span)
+    }
+}
+
+// gathers the bits of the index at the split axes into the low bits
+pub(crate) fn compact_index(index: usize, axes: usize) -> usize {
+    (0..usize::BITS as usize)
+        .filter(|axis| axes & 1 << axis != 0)
+        .enumerate()
+        .fold(0, |compact, (bit, axis)| {
+            compact | ((index >> axis) & 1) << bit
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tpntree::{SpatialTree, TpnTree};
+    use crate::TpnTreeError;
+
+    #[test]
+    fn thin_data_divides_along_wide_axes() {
+        let mut tree = SpatialTree::<[f64; 3], 3>::new([0.0; 3], [8.0, 8.0, 1.0], 0);
+        // split only the axes still wider than the leaves are high
+        let wide = |tree: &SpatialTree<[f64; 3], 3>| {
+            (0..2)
+                .filter(|&axis| tree.span()[axis] > tree.span()[2])
+                .collect::<Vec<_>>()
+        };
+        let points = (0..64)
+            .map(|i| {
+                let t = i as f64;
+                [(t * 0.37).sin() * 7.9, (t * 0.71).cos() * 7.9, 0.0]
+            })
+            .collect::<Vec<_>>();
+
+        for point in points.iter() {
+            let mut leaf = &mut tree;
+            while !leaf.is_leaf() {
+                let index = leaf.child_index_for_point(point);
+                leaf = leaf.get_child_mut(index).unwrap();
+            }
+            let axes = wide(leaf);
+            if !axes.is_empty() {
+                leaf.divide_axes(&axes).unwrap();
+            }
+        }
+        for point in points.iter() {
+            tree.insert_by_coordinates(*point, &|_| false).unwrap();
+        }
+
+        assert_eq!(tree.check_spatial_invariants(), Ok(()));
+        assert!(tree
+            .iter_depth_first()
+            .all(|tree| tree.is_leaf() || tree.child_count() == 4));
+        assert!(tree.iter_depth_first().all(|tree| tree.span()[2] == 1.0));
+        for point in points.iter() {
+            let leaf = tree.find_at_level(point, usize::MAX).unwrap();
+            assert!(leaf.data().unwrap().contains(point));
+        }
+
+        #[cfg(feature = "persist")]
+        {
+            let mut bytes = Vec::new();
+            tree.save_to(&mut bytes).unwrap();
+            assert_eq!(SpatialTree::load_from(bytes.as_slice()).unwrap(), tree);
+        }
+    }
+
+    #[test]
+    fn divide_axes_validates() {
+        let mut tree = TpnTree::<(), 2>::root(1.0);
+
+        assert_eq!(
+            tree.divide_axes(&[2]),
+            Err(TpnTreeError::NoSuchAxis { axis: 2 })
+        );
+        assert!(tree.divide_axes(&[1, 0, 1]).is_ok());
+        assert_eq!(tree, {
+            let mut full = TpnTree::root(1.0);
+            full.divide().unwrap();
+            full
+        });
+        assert_eq!(tree.divide_axes(&[0]), Err(TpnTreeError::CanNotDivide));
+    }
+}
@@ -0,0 +1,214 @@
+use super::{Region, TpnTree};
+use crate::errors::TpnTreeError;
+use alloc::vec::Vec;
+
+/// Identifies a tree stored in an [`ArenaTree`].
+///
+/// Ids stay valid until the arena is cleared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeId(usize);
+
+/// A tree allocating all of its trees from one growable arena instead of allocating the children of every division separately.
+///
+/// The children of a tree are stored consecutively.
+/// Trees in an arena are always divided like with [`TpnTree::divide`], at their center into 2^N children,
+/// so it can not hold trees divided with [`TpnTree::divide_axes`], [`TpnTree::divide_with_branching`] or [`TpnTree::divide_at`].
+/// All trees are freed at once on drop, [`ArenaTree::clear`] keeps the memory for reuse, e.g. when rebuilding a tree every frame.
+/// Methods taking a [`NodeId`] panic if the id does not belong to the arena.
+///
+/// ```
+/// # use tpntree::tpntree::ArenaTree;
+/// let mut arena = ArenaTree::<u32, 2>::with_capacity([0.0; 2], [1.0; 2], 1 + 4);
+///
+/// let root = arena.root_id();
+/// arena.divide(root).expect("Couldn't divide.");
+/// let leaf = arena.find_leaf(&[0.5, -0.5]);
+/// *arena.data_mut(leaf) = Some(1);
+///
+/// assert_eq!(arena.get_child(root, 2), Some(leaf));
+/// assert_eq!(arena.node_count(), 5);
+///
+/// arena.clear();
+/// assert!(arena.is_leaf(arena.root_id()));
+/// assert!(arena.capacity() >= 5);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArenaTree<T, const N: usize> {
+    regions: Vec<Region<N>>,
+    // index of the first of the 2^N children, NONE for leaves
+    first_child: Vec<usize>,
+    data: Vec<Option<T>>,
+}
+
+impl<T, const N: usize> ArenaTree<T, N> {
+    const NONE: usize = usize::MAX;
+    const CHILD_COUNT: usize = 1 << N;
+
+    /// Creates an arena holding a root at level zero with room for `capacity` trees.
+    pub fn with_capacity(coordinates: [f64; N], span: [f64; N], capacity: usize) -> Self {
+        let mut arena = Self {
+            regions: Vec::with_capacity(capacity),
+            first_child: Vec::with_capacity(capacity),
+            data: Vec::with_capacity(capacity),
+        };
+        arena.push(Region::new(coordinates, span, 0));
+        arena
+    }
+
+    /// Creates an arena holding a root with equal span in all dimension at the center of the space, see [`TpnTree::root`].
+    pub fn root(span: f64) -> Self {
+        Self::with_capacity([0.0; N], [span; N], 1)
+    }
+
+    /// Returns the id of the root.
+    pub fn root_id(&self) -> NodeId {
+        NodeId(0)
+    }
+
+    /// Returns the count of trees in the arena.
+    pub fn node_count(&self) -> usize {
+        self.regions.len()
+    }
+
+    /// Returns the count of trees the arena can hold without allocating.
+    pub fn capacity(&self) -> usize {
+        self.regions.capacity()
+    }
+
+    /// Removes all trees but the root and its data, keeping the allocated memory.
+    pub fn clear(&mut self) {
+        let root = self.regions[0];
+        self.regions.clear();
+        self.first_child.clear();
+        self.data.clear();
+        self.push(root);
+    }
+
+    /// Returns the region of the tree.
+    pub fn region(&self, id: NodeId) -> Region<N> {
+        self.regions[id.0]
+    }
+
+    /// Returns the data of the tree.
+    pub fn data(&self, id: NodeId) -> Option<&T> {
+        self.data[id.0].as_ref()
+    }
+
+    /// Returns the data of the tree by mutable reference.
+    pub fn data_mut(&mut self, id: NodeId) -> &mut Option<T> {
+        &mut self.data[id.0]
+    }
+
+    /// Checks if the tree has no children.
+    pub fn is_leaf(&self, id: NodeId) -> bool {
+        self.first_child[id.0] == Self::NONE
+    }
+
+    /// Returns the id of the child at the index, if the tree is divided.
+    pub fn get_child(&self, id: NodeId, index: usize) -> Option<NodeId> {
+        if self.is_leaf(id) || index >= Self::CHILD_COUNT {
+            None
+        } else {
+            Some(NodeId(self.first_child[id.0] + index))
+        }
+    }
+
+    /// Divides the tree into 2^N children allocated from the arena, see [`TpnTree::divide`].
+    ///
+    /// Errors with [`TpnTreeError::CanNotDivide`] if the tree is divided already.
+    pub fn divide(&mut self, id: NodeId) -> Result<(), TpnTreeError> {
+        if !self.is_leaf(id) {
+            return Err(TpnTreeError::CanNotDivide);
+        }
+        let region = self.regions[id.0];
+        self.first_child[id.0] = self.regions.len();
+        for child in region.iter_virtual_children() {
+            self.push(child);
+        }
+        Ok(())
+    }
+
+    /// Returns the id of the leaf covering the point, descending half-open like insertion into a [`SpatialTree`](super::SpatialTree).
+    ///
+    /// Points outside of the root end up in the closest leaf along each axis.
+    pub fn find_leaf(&self, point: &[f64; N]) -> NodeId {
+        let mut id = self.root_id();
+        while !self.is_leaf(id) {
+            let index = self.regions[id.0].child_index_for_point(point);
+            id = NodeId(self.first_child[id.0] + index);
+        }
+        id
+    }
+
+    /// Copies the trees of the arena into a [`TpnTree`].
+    pub fn to_tree(&self) -> TpnTree<T, N>
+    where
+        T: Clone,
+    {
+        self.to_tree_at(self.root_id())
+    }
+
+    fn to_tree_at(&self, id: NodeId) -> TpnTree<T, N>
+    where
+        T: Clone,
+    {
+        let region = self.region(id);
+        let mut tree = TpnTree::new(region.coordinates(), region.span(), region.level());
+        tree.children = (0..Self::CHILD_COUNT)
+            .filter_map(|index| self.get_child(id, index))
+            .map(|child| self.to_tree_at(child))
+            .collect();
+        tree.data = self.data(id).cloned();
+        tree
+    }
+
+    fn push(&mut self, region: Region<N>) {
+        self.regions.push(region);
+        self.first_child.push(Self::NONE);
+        self.data.push(None);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tpntree::{ArenaTree, SplitLayout, TpnTree};
+    use crate::TpnTreeError;
+
+    #[test]
+    fn arena_matches_tree() {
+        let mut arena = ArenaTree::<usize, 3>::root(1.0);
+        let mut tree = TpnTree::<usize, 3>::root(1.0);
+
+        for point in [[0.5, 0.5, 0.5], [0.7, 0.6, 0.1], [-0.3, 0.2, -0.9]] {
+            let leaf = arena.find_leaf(&point);
+            arena.divide(leaf).unwrap();
+            *arena.data_mut(arena.find_leaf(&point)) = Some(arena.node_count());
+
+            find_leaf_mut(&mut tree, &point).divide().unwrap();
+            *find_leaf_mut(&mut tree, &point).data_mut() = Some(arena.node_count());
+        }
+
+        assert_eq!(arena.to_tree(), tree);
+        assert!(arena
+            .to_tree()
+            .iter_depth_first()
+            .all(|tree| tree.split_layout() == SplitLayout::default()));
+        assert_eq!(
+            arena.divide(arena.root_id()),
+            Err(TpnTreeError::CanNotDivide)
+        );
+        assert_eq!(arena.get_child(arena.root_id(), 8), None);
+    }
+
+    fn find_leaf_mut<'a>(
+        tree: &'a mut TpnTree<usize, 3>,
+        point: &[f64; 3],
+    ) -> &'a mut TpnTree<usize, 3> {
+        if tree.is_leaf() {
+            tree
+        } else {
+            let index = tree.child_index_for_point(point);
+            find_leaf_mut(&mut tree.children[index], point)
+        }
+    }
+}
@@ -0,0 +1,137 @@
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use num_traits::Float;
+
+use super::TpnTree;
+use crate::errors::TpnTreeError;
+
+impl<T, const N: usize> TpnTree<T, N> {
+    /// Divides the tree into `branching` equal parts along every axis, creating branching^N children.
+    ///
+    /// Use this for refinement schemes with other ratios than 2:1 per step, e.g. 4:1.
+    /// Digit `i` of the child index in base `branching` selects the part along axis `i`, counted from the part at the max face downwards.
+    /// So dividing with a branching of 2 is the same as [`TpnTree::divide`].
+    /// Children are half-open like with [`TpnTree::divide`], a point on a face between two parts belongs to the part above it.
+    ///
+    /// Errors with [`TpnTreeError::InvalidBranching`] if the branching is below 2 or the children would not be countable,
+    /// and otherwise like [`TpnTree::divide`].
+    /// [`NodeKey`](super::NodeKey), [`Orthant`](super::Orthant) and the linear, arena and persistent representations assume a branching of 2.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::TpnTree;
+    /// let mut root = TpnTree::<(), 2>::root(1.5);
+    ///
+    /// root.divide_with_branching(3).expect("Couldn't divide.");
+    ///
+    /// assert_eq!(root.child_count(), 9);
+    /// assert_eq!(root.branching(), 3);
+    /// // the middle part along the first axis and the lowest part along the second
+    /// assert_eq!(root.get_child(1 + 2 * 3).unwrap().coordinates(), [0.0, -1.0]);
+    /// assert_eq!(root.child_index_for_point(&[0.2, -1.2]), 7);
+    /// ```
+    pub fn divide_with_branching(&mut self, branching: usize) -> Result<(), TpnTreeError> {
+        self.divide_along(None, branching, None)
+    }
+
+    /// Returns the count of parts the tree is divided into along each split axis, 2 for trees not divided with [`TpnTree::divide_with_branching`].
+    pub fn branching(&self) -> usize {
+        self.branching
+    }
+
+    // the index of the child covering the coordinates in base branching over the split axes
+    pub(crate) fn branching_child_index(&self, coordinates: &[f64]) -> usize {
+        let last = self.branching - 1;
+        self.split_axes_iter().rev().fold(0, |index, axis| {
+            let min = self.coordinates[axis] - self.span[axis];
+            let width = 2.0 * self.span[axis] / self.branching as f64;
+            let from_min = ((coordinates[axis] - min) / width).floor().max(0.0) as usize;
+            index * self.branching + last - from_min.min(last)
+        })
+    }
+
+    // the region of the child at the index in base branching over the split axes
+    pub(crate) fn branching_child_region(&self, mut index: usize) -> ([f64; N], [f64; N]) {
+        let mut coordinates = self.coordinates;
+        let mut span = self.span;
+        for axis in self.split_axes_iter() {
+            let part = index % self.branching;
+            index /= self.branching;
+            span[axis] = self.span[axis] / self.branching as f64;
+            let max = self.coordinates[axis] + self.span[axis];
+            coordinates[axis] = max - (2 * part + 1) as f64 * span[axis];
+        }
+        (coordinates, span)
+    }
+}
+
+// the count of children for the count of split axes and branching
+pub(crate) fn child_count(split_count: usize, branching: usize) -> Result<usize, TpnTreeError> {
+    Some(branching)
+        .filter(|branching| *branching >= 2)
+        .and_then(|branching| branching.checked_pow(split_count as u32))
+        .ok_or(TpnTreeError::InvalidBranching { branching })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tpntree::{SpatialTree, TpnTree};
+    use crate::TpnTreeError;
+
+    #[test]
+    fn four_to_one_refinement_finds_points() {
+        let mut tree = SpatialTree::<[f64; 2], 2>::root(1.0);
+        tree.divide_with_branching(4).unwrap();
+        tree.get_child_mut(5)
+            .unwrap()
+            .divide_with_branching(4)
+            .unwrap();
+        let points = (0..100)
+            .map(|i| {
+                let t = i as f64;
+                [(t * 0.37).sin(), (t * 0.71).cos()]
+            })
+            .collect::<Vec<_>>();
+
+        for point in points.iter() {
+            tree.insert_by_coordinates(*point, &|_| false).unwrap();
+        }
+
+        assert_eq!(tree.check_spatial_invariants(), Ok(()));
+        assert_eq!(tree.iter_depth_first().count(), 1 + 16 + 16);
+        for point in points.iter() {
+            let leaf = tree.find_at_level(point, usize::MAX).unwrap();
+            assert!(leaf.data().unwrap().contains(point));
+        }
+        // faces between parts belong to the part above
+        assert_eq!(tree.child_index_for_point(&[0.5, 0.0]), 4);
+
+        #[cfg(feature = "persist")]
+        {
+            let mut bytes = Vec::new();
+            tree.save_to(&mut bytes).unwrap();
+            assert_eq!(SpatialTree::load_from(bytes.as_slice()).unwrap(), tree);
+        }
+    }
+
+    #[test]
+    fn branching_validates() {
+        let mut tree = TpnTree::<(), 3>::root(1.0);
+
+        assert_eq!(
+            tree.divide_with_branching(1),
+            Err(TpnTreeError::InvalidBranching { branching: 1 })
+        );
+        assert_eq!(
+            tree.divide_with_branching(usize::MAX),
+            Err(TpnTreeError::InvalidBranching {
+                branching: usize::MAX
+            })
+        );
+        assert!(tree.is_leaf());
+
+        tree.divide_with_branching(2).unwrap();
+        let mut binary = TpnTree::root(1.0);
+        binary.divide().unwrap();
+        assert_eq!(tree, binary);
+    }
+}
@@ -0,0 +1,425 @@
+use alloc::{sync::Arc, vec::Vec};
+use core::marker::PhantomData;
+
+use super::{Spillover, TpnTree, WorldTransform};
+
+/// Settings respected by the methods of a tree, shared by all trees descending from the root.
+///
+/// Use [`TpnTree::builder`] to create a tree with a configuration or [`TpnTree::set_config`] to configure an existing one.
+/// The configuration is reference counted, children created by [`TpnTree::divide`] share it with their parent.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TreeConfig {
+    max_depth: Option<usize>,
+    leaf_capacity: Option<usize>,
+    spillover: Option<Spillover>,
+    looseness: Option<f64>,
+    duplicate_policy: Option<DuplicatePolicy>,
+    duplicate_tolerance: Option<f64>,
+    periodic_axes: Option<Vec<bool>>,
+    epsilon: Option<f64>,
+    world_transform: Option<WorldTransform>,
+}
+
+/// What [`SpatialTree::insert`](super::SpatialTree::insert) does with an item at the coordinates of an item already in the tree.
+///
+/// Items are duplicates if their distance is at most the duplicate tolerance of the [`TreeConfig`], i.e. equal without tolerance.
+///
+/// ```
+/// # use tpntree::tpntree::{DuplicatePolicy, Tree3D};
+/// let mut tree = Tree3D::builder()
+///     .duplicate_policy(DuplicatePolicy::Reject)
+///     .duplicate_tolerance(0.01)
+///     .build();
+///
+/// assert!(tree.insert([0.5, 0.5, 0.5]).is_ok());
+/// assert!(tree.insert([0.505, 0.5, 0.5]).is_err());
+/// assert_eq!(tree.data().map(Vec::len), Some(1));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicatePolicy {
+    /// Keeps all items, like without a policy.
+    KeepAll,
+    /// Errors with [`TpnTreeError::Duplicate`](crate::TpnTreeError::Duplicate) and keeps the item in the tree.
+    Reject,
+    /// Removes the item in the tree and inserts the new one.
+    Replace,
+}
+
+impl TreeConfig {
+    /// The level below which leaves do not divide on insertion if no max depth is set.
+    ///
+    /// Items at the same coordinates can not be separated by dividing, they share a leaf at this level at the latest.
+    pub const DEFAULT_MAX_DEPTH: usize = 32;
+
+    /// Returns the level below which trees can not be divided.
+    pub fn max_depth(&self) -> Option<usize> {
+        self.max_depth
+    }
+
+    /// Returns the number of items a leaf holds before it divides on insertion.
+    ///
+    /// Leaves at the max depth, or at [`TreeConfig::DEFAULT_MAX_DEPTH`] without one, do not divide.
+    pub fn leaf_capacity(&self) -> Option<usize> {
+        self.leaf_capacity
+    }
+
+    /// Returns what happens on insertion into a leaf holding `leaf_capacity` items.
+    pub fn spillover(&self) -> Option<Spillover> {
+        self.spillover
+    }
+
+    /// Returns the looseness factor of loose trees.
+    pub fn looseness(&self) -> Option<f64> {
+        self.looseness
+    }
+
+    /// Sets the level below which trees can not be divided.
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// Returns what happens on insertion of duplicate items.
+    pub fn duplicate_policy(&self) -> Option<DuplicatePolicy> {
+        self.duplicate_policy
+    }
+
+    /// Returns the distance up to which items are duplicates.
+    pub fn duplicate_tolerance(&self) -> Option<f64> {
+        self.duplicate_tolerance
+    }
+
+    /// Returns for each axis whether proximity queries wrap around it.
+    pub fn periodic_axes(&self) -> Option<&[bool]> {
+        self.periodic_axes.as_deref()
+    }
+
+    /// Returns the distance beyond its faces up to which the root still spans coordinates.
+    pub fn epsilon(&self) -> Option<f64> {
+        self.epsilon
+    }
+
+    /// Returns the transform placing the tree in world space.
+    pub fn world_transform(&self) -> Option<&WorldTransform> {
+        self.world_transform.as_ref()
+    }
+
+    /// Sets the number of items a leaf holds before it divides on insertion.
+    pub fn with_leaf_capacity(mut self, leaf_capacity: usize) -> Self {
+        self.leaf_capacity = Some(leaf_capacity);
+        self
+    }
+
+    /// Sets what happens on insertion into a leaf holding `leaf_capacity` items.
+    pub fn with_spillover(mut self, spillover: Spillover) -> Self {
+        self.spillover = Some(spillover);
+        self
+    }
+
+    /// Sets the looseness factor of loose trees.
+    pub fn with_looseness(mut self, looseness: f64) -> Self {
+        self.looseness = Some(looseness);
+        self
+    }
+
+    /// Sets what happens on insertion of duplicate items.
+    pub fn with_duplicate_policy(mut self, duplicate_policy: DuplicatePolicy) -> Self {
+        self.duplicate_policy = Some(duplicate_policy);
+        self
+    }
+
+    /// Sets the distance up to which items are duplicates.
+    pub fn with_duplicate_tolerance(mut self, duplicate_tolerance: f64) -> Self {
+        self.duplicate_tolerance = Some(duplicate_tolerance);
+        self
+    }
+
+    /// Sets for each axis whether proximity queries wrap around it.
+    ///
+    /// Along a periodic axis the box of the tree a query is called on behaves like a torus,
+    /// see [`TpnTree::wrap`].
+    pub fn with_periodic_axes(mut self, periodic_axes: Vec<bool>) -> Self {
+        self.periodic_axes = Some(periodic_axes);
+        self
+    }
+
+    /// Sets the distance beyond its faces up to which the root still spans coordinates.
+    ///
+    /// Coordinates that end up just outside the root due to accumulated floating-point error are accepted instead of rejected with
+    /// [`TpnTreeError::DoesNotSpan`](crate::TpnTreeError::DoesNotSpan).
+    /// They descend into the children closest to them, as descent only compares coordinates with the centers of the trees.
+    pub fn with_epsilon(mut self, epsilon: f64) -> Self {
+        self.epsilon = Some(epsilon);
+        self
+    }
+
+    /// Sets the transform placing the tree in world space.
+    ///
+    /// The tree itself stays in its own space, methods taking world space points map them with [`TpnTree::world_to_tree`].
+    /// The transform has to be of the same dimension as the tree.
+    pub fn with_world_transform(mut self, world_transform: WorldTransform) -> Self {
+        self.world_transform = Some(world_transform);
+        self
+    }
+}
+
+/// Builds a [`TpnTree`] with a [`TreeConfig`].
+///
+/// The tree is centered at the origin with a span of 1.0 along every axis, unless specified otherwise.
+///
+/// ```
+/// # use tpntree::tpntree::TpnTree;
+/// let tree = TpnTree::<(), 2>::builder()
+///     .center([1.0, 1.0])
+///     .span([2.0, 2.0])
+///     .max_depth(8)
+///     .leaf_capacity(16)
+///     .build();
+///
+/// assert_eq!(tree.coordinates(), [1.0, 1.0]);
+/// assert_eq!(tree.config().and_then(|config| config.max_depth()), Some(8));
+/// ```
+#[derive(Debug, Clone)]
+pub struct TpnTreeBuilder<T, const N: usize> {
+    coordinates: [f64; N],
+    span: [f64; N],
+    snap_to_binary: bool,
+    config: TreeConfig,
+    data: PhantomData<T>,
+}
+
+impl<T, const N: usize> TpnTreeBuilder<T, N> {
+    /// Sets the coordinates of the center of the root.
+    pub fn center(mut self, coordinates: [f64; N]) -> Self {
+        self.coordinates = coordinates;
+        self
+    }
+
+    /// Sets the span of the root.
+    pub fn span(mut self, span: [f64; N]) -> Self {
+        self.span = span;
+        self
+    }
+
+    /// Grows the root to the smallest binary aligned region covering it, so divisions are exact, see [`TpnTree::is_binary_aligned`].
+    pub fn snap_to_binary(mut self) -> Self {
+        self.snap_to_binary = true;
+        self
+    }
+
+    /// Sets the level below which trees can not be divided.
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.config = self.config.with_max_depth(max_depth);
+        self
+    }
+
+    /// Sets the number of items a leaf holds before it divides on insertion.
+    pub fn leaf_capacity(mut self, leaf_capacity: usize) -> Self {
+        self.config = self.config.with_leaf_capacity(leaf_capacity);
+        self
+    }
+
+    /// Sets what happens on insertion into a leaf holding `leaf_capacity` items.
+    pub fn spillover(mut self, spillover: Spillover) -> Self {
+        self.config = self.config.with_spillover(spillover);
+        self
+    }
+
+    /// Sets the looseness factor of loose trees.
+    pub fn looseness(mut self, looseness: f64) -> Self {
+        self.config = self.config.with_looseness(looseness);
+        self
+    }
+
+    /// Sets what happens on insertion of duplicate items.
+    pub fn duplicate_policy(mut self, duplicate_policy: DuplicatePolicy) -> Self {
+        self.config = self.config.with_duplicate_policy(duplicate_policy);
+        self
+    }
+
+    /// Sets the distance up to which items are duplicates.
+    pub fn duplicate_tolerance(mut self, duplicate_tolerance: f64) -> Self {
+        self.config = self.config.with_duplicate_tolerance(duplicate_tolerance);
+        self
+    }
+
+    /// Sets for each axis whether proximity queries wrap around it.
+    pub fn periodic_axes(mut self, periodic_axes: [bool; N]) -> Self {
+        self.config = self.config.with_periodic_axes(periodic_axes.to_vec());
+        self
+    }
+
+    /// Sets the distance beyond its faces up to which the root still spans coordinates.
+    pub fn epsilon(mut self, epsilon: f64) -> Self {
+        self.config = self.config.with_epsilon(epsilon);
+        self
+    }
+
+    /// Sets the transform placing the tree in world space.
+    pub fn world_transform(mut self, world_transform: WorldTransform) -> Self {
+        self.config = self.config.with_world_transform(world_transform);
+        self
+    }
+
+    /// Creates the configured root tree.
+    pub fn build(self) -> TpnTree<T, N> {
+        let (coordinates, span) = if self.snap_to_binary {
+            super::snap::binary_region(&self.coordinates, &self.span)
+        } else {
+            (self.coordinates, self.span)
+        };
+        let mut tree = TpnTree::new(coordinates, span, 0);
+        tree.config = Some(Arc::new(self.config));
+        tree
+    }
+}
+
+impl<T, const N: usize> TpnTree<T, N> {
+    /// Starts building a root tree with a [`TreeConfig`].
+    pub fn builder() -> TpnTreeBuilder<T, N> {
+        TpnTreeBuilder {
+            coordinates: [0.0; N],
+            span: [1.0; N],
+            snap_to_binary: false,
+            config: TreeConfig::default(),
+            data: PhantomData,
+        }
+    }
+
+    /// Returns the configuration of the tree, if it descends from a root built with [`TpnTree::builder`].
+    pub fn config(&self) -> Option<&TreeConfig> {
+        self.config.as_deref()
+    }
+
+    /// Sets the configuration of the tree and all its descendants.
+    ///
+    /// Trees divided afterwards share the configuration as well.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::{TpnTree, TreeConfig};
+    /// let mut tree = TpnTree::<(), 2>::root(1.0);
+    /// tree.divide().expect("Couldn't divide.");
+    ///
+    /// tree.set_config(TreeConfig::default().with_max_depth(1));
+    ///
+    /// assert!(tree.get_child_mut(0).unwrap().divide().is_err());
+    /// ```
+    pub fn set_config(&mut self, config: TreeConfig) {
+        self.share_config(Some(&Arc::new(config)));
+    }
+
+    // sets the config of the tree and all its descendants
+    pub(crate) fn share_config(&mut self, config: Option<&Arc<TreeConfig>>) {
+        self.config = config.cloned();
+        for child in &mut self.children {
+            child.share_config(config);
+        }
+    }
+
+    // checks if the config allows the tree to divide on capacity, coincident items would divide forever without a limit
+    pub(crate) fn below_max_depth(&self) -> bool {
+        let max_depth = self
+            .config()
+            .and_then(|config| config.max_depth())
+            .unwrap_or(TreeConfig::DEFAULT_MAX_DEPTH);
+        self.level < max_depth
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tpntree::{LooseTree, TpnTree, Tree3D, TreeConfig};
+    use crate::TpnTreeError;
+
+    #[test]
+    fn build_with_defaults() {
+        let tree = TpnTree::<(), 3>::builder().build();
+
+        assert_eq!(tree.coordinates(), [0.0; 3]);
+        assert_eq!(tree.span(), [1.0; 3]);
+        assert!(tree.is_root());
+        assert_eq!(
+            tree.config().and_then(|config| config.leaf_capacity()),
+            None
+        );
+    }
+
+    #[test]
+    fn children_share_config() {
+        let mut tree = TpnTree::<(), 2>::builder().max_depth(1).build();
+
+        assert!(tree.divide().is_ok());
+        assert!(tree
+            .iter_children()
+            .all(|child| child.config() == tree.config()));
+        assert_eq!(
+            tree.get_child_mut(0).unwrap().divide(),
+            Err(TpnTreeError::MaxDepthExceeded { max_level: 1 })
+        );
+    }
+
+    #[test]
+    fn set_config_reaches_descendants() {
+        let mut tree = TpnTree::<(), 2>::root(1.0);
+
+        assert!(tree.divide().is_ok());
+        assert!(tree.get_child_mut(3).unwrap().divide().is_ok());
+
+        tree.set_config(TreeConfig::default().with_leaf_capacity(4));
+
+        assert_eq!(tree.iter_depth_first().count(), 9);
+        assert!(tree
+            .iter_depth_first()
+            .all(|tree| tree.config().and_then(|config| config.leaf_capacity()) == Some(4)));
+    }
+
+    #[test]
+    fn insert_respects_leaf_capacity_and_max_depth() {
+        let mut tree = Tree3D::builder().leaf_capacity(2).max_depth(1).build();
+
+        for data in [
+            [0.5, 0.5, 0.5],
+            [0.6, 0.6, 0.6],
+            [0.7, 0.7, 0.7],
+            [-0.5, -0.5, -0.5],
+        ] {
+            assert!(tree.insert(data).is_ok());
+        }
+
+        assert_eq!(tree.child_count(), 8);
+        assert_eq!(
+            tree.find_by_coordinates(&[0.5, 0.5, 0.5])
+                .ok()
+                .and_then(|leaf| leaf.data())
+                .map(|vec| vec.len()),
+            Some(3)
+        );
+    }
+
+    #[test]
+    fn insert_loose_uses_looseness() {
+        let mut tree = LooseTree::<u8, 2>::builder()
+            .looseness(2.0)
+            .leaf_capacity(1)
+            .build();
+
+        assert!(tree.insert_loose(0, [0.5, 0.5], [0.4, 0.4]).is_ok());
+        assert!(tree.insert_loose(1, [-0.5, -0.5], [0.4, 0.4]).is_ok());
+
+        assert!(tree.data().is_none());
+        assert_eq!(tree.find_intersecting(&[0.0, 0.0], &[1.0, 1.0]).len(), 2);
+    }
+
+    #[test]
+    fn snap_root_to_binary() {
+        let tree = TpnTree::<(), 2>::builder()
+            .center([0.3, -1.0])
+            .span([0.1, 3.0])
+            .snap_to_binary()
+            .build();
+
+        assert_eq!(tree.coordinates(), [0.25, 0.0]);
+        assert_eq!(tree.span(), [0.25, 4.0]);
+        assert!(tree.is_binary_aligned());
+    }
+}
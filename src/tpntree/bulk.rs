@@ -0,0 +1,245 @@
+use alloc::vec::Vec;
+use core::iter::FromIterator;
+
+use super::{SpatialTree, TpnTree};
+use crate::{errors::TpnTreeError, Coordinates};
+
+// the division policy of trees collected from an iterator
+const COLLECT_LEAF_CAPACITY: usize = 8;
+const COLLECT_MAX_DEPTH: usize = 16;
+
+impl<T: Coordinates<N>, const N: usize> SpatialTree<T, N> {
+    /// Inserts all data in bulk, dividing leaves once after all data has been placed.
+    ///
+    /// First every item is added to the leaf containing it without checking the `division_condition`.
+    /// Then every leaf fulfilling the `division_condition` is divided and its data moved into its children, which are checked in turn.
+    /// Unlike [`SpatialTree::insert_by_coordinates`], the condition thus sees all data a leaf ends up with, not the data before an insertion.
+    ///
+    /// Errors if the tree does not span some data or it has other than N coordinates.
+    /// The data inserted before the failing item stays in the tree, leaves are divided nonetheless.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::Tree3D;
+    /// let mut tree = Tree3D::root(1.0);
+    ///
+    /// let points = (0..100).map(|i| {
+    ///     let x = i as f64 / 100.0;
+    ///     [x, -x, x * x]
+    /// });
+    /// tree.extend_from_iter(points, &|tree| tree.data().is_some_and(|vec| vec.len() > 8))
+    ///     .expect("Couldn't insert.");
+    ///
+    /// assert!(tree
+    ///     .iter_depth_first()
+    ///     .filter_map(|tree| tree.data())
+    ///     .all(|vec| vec.len() <= 8));
+    /// ```
+    pub fn extend_from_iter(
+        &mut self,
+        iter: impl IntoIterator<Item = T>,
+        division_condition: &dyn Fn(&Self) -> bool,
+    ) -> Result<(), TpnTreeError> {
+        let placed = iter.into_iter().try_for_each(|data| {
+            self.find_by_coordinates_mut(&data)?
+                .data
+                .get_or_insert(Vec::new())
+                .push(data);
+            Ok(())
+        });
+
+        self.divide_leaves(division_condition)?;
+        placed
+    }
+
+    // divides leaves fulfilling the condition and moves their data into the children, recursively
+    fn divide_leaves(
+        &mut self,
+        division_condition: &dyn Fn(&Self) -> bool,
+    ) -> Result<(), TpnTreeError> {
+        if self.is_leaf() {
+            if !division_condition(self) {
+                return Ok(());
+            }
+            self.divide()?;
+
+            for data in self.data.take().unwrap_or_default() {
+                let index = self.child_index_for(data.coordinates());
+                self.children[index]
+                    .data
+                    .get_or_insert(Vec::new())
+                    .push(data);
+            }
+        }
+
+        self.children
+            .iter_mut()
+            .try_for_each(|child| child.divide_leaves(division_condition))
+    }
+
+    // checks if the leaf holds more data than the configured capacity and may still divide
+    fn exceeds_capacity(&self) -> bool {
+        self.config()
+            .and_then(|config| config.leaf_capacity())
+            .is_some_and(|capacity| self.data.as_ref().is_some_and(|data| data.len() > capacity))
+            && self.below_max_depth()
+    }
+}
+
+/// Inserts all data in bulk according to the [`TreeConfig`](super::TreeConfig) of the tree.
+///
+/// Leaves holding more than `leaf_capacity` items divide unless they are at `max_depth`, just like with [`SpatialTree::insert`].
+///
+/// # Panics
+///
+/// Panics if the tree does not span some data or it has other than N coordinates.
+/// Use [`SpatialTree::extend_from_iter`] to handle such data.
+impl<T: Coordinates<N>, const N: usize> Extend<T> for SpatialTree<T, N> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        if let Err(error) = self.extend_from_iter(iter, &|tree: &Self| tree.exceeds_capacity()) {
+            panic!("{}", error);
+        }
+    }
+}
+
+/// Builds a tree covering the tightest box around all data, dividing leaves holding more than 8 items down to level 16.
+///
+/// The policy is kept in the [`TreeConfig`](super::TreeConfig) of the tree, so later insertions follow it as well.
+/// Without data the tree is [`TpnTree::default`].
+///
+/// # Panics
+///
+/// Panics if some data has other than N coordinates or a coordinate is NaN.
+///
+/// ```
+/// # use tpntree::tpntree::Tree3D;
+/// let points = (0..100).map(|i| [i as f64, 0.0, -(i as f64)]);
+///
+/// let tree = points.collect::<Tree3D>();
+///
+/// assert_eq!(tree.iter_data().count(), 100);
+/// assert_eq!(tree.data_bounds(), Some(([0.0, 0.0, -99.0], [99.0, 0.0, 0.0])));
+/// ```
+impl<T: Coordinates<N>, const N: usize> FromIterator<T> for SpatialTree<T, N> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let items = iter.into_iter().collect::<Vec<_>>();
+        let mut bounds: Option<([f64; N], [f64; N])> = None;
+        for item in &items {
+            let coordinates = item
+                .try_coordinates()
+                .unwrap_or_else(|error| panic!("{}", error));
+            let (min, max) = bounds.get_or_insert(([f64::INFINITY; N], [f64::NEG_INFINITY; N]));
+            for axis in 0..N {
+                min[axis] = min[axis].min(coordinates[axis]);
+                max[axis] = max[axis].max(coordinates[axis]);
+            }
+        }
+        let Some((min, max)) = bounds else {
+            return Self::default();
+        };
+
+        let region =
+            TpnTree::<(), N>::from_bounds(min, max).unwrap_or_else(|error| panic!("{}", error));
+        let (center, mut span) = (region.coordinates(), region.span());
+        // the center and span may round such that the tree misses the outermost data
+        for axis in 0..N {
+            while center[axis] + span[axis] < max[axis] || center[axis] - span[axis] > min[axis] {
+                // the next larger float, as the span is not negative
+                span[axis] = f64::from_bits(span[axis].to_bits() + 1);
+            }
+        }
+        let mut tree = Self::builder()
+            .center(center)
+            .span(span)
+            .leaf_capacity(COLLECT_LEAF_CAPACITY)
+            .max_depth(COLLECT_MAX_DEPTH)
+            .build();
+        tree.extend(items);
+        tree
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tpntree::{SpatialTree, Tree3D};
+    use crate::TpnTreeError;
+
+    fn points() -> Vec<[f64; 3]> {
+        (0..200)
+            .map(|i| {
+                let t = i as f64 * 0.37;
+                [t.sin(), (t * 1.3).cos(), (t * 0.7).sin() * 0.5]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn extend_matches_single_insertions() {
+        let mut single = Tree3D::builder().leaf_capacity(4).max_depth(6).build();
+        let mut bulk = single.clone();
+
+        for data in points() {
+            assert!(single.insert(data).is_ok());
+        }
+        bulk.extend(points());
+
+        assert!(bulk.structurally_equal(&single));
+        assert_eq!(
+            bulk.iter_depth_first()
+                .filter_map(|tree| tree.data())
+                .map(|vec| vec.len())
+                .sum::<usize>(),
+            200
+        );
+    }
+
+    #[test]
+    fn extend_from_iter_stops_at_data_outside() {
+        let mut tree = Tree3D::root(1.0);
+
+        let result = tree.extend_from_iter(
+            vec![[0.5, 0.5, 0.5], [2.0, 0.0, 0.0], [-0.5, -0.5, -0.5]],
+            &|tree| tree.is_root(),
+        );
+
+        assert!(matches!(result, Err(TpnTreeError::DoesNotSpan { .. })));
+        assert_eq!(tree.child_count(), 8);
+        assert_eq!(
+            tree.iter_depth_first()
+                .filter_map(|tree| tree.data())
+                .map(|vec| vec.len())
+                .sum::<usize>(),
+            1
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn extend_panics_on_data_outside() {
+        let mut tree = Tree3D::root(1.0);
+
+        tree.extend(vec![[0.0, 0.0, 2.0]]);
+    }
+
+    #[test]
+    fn collect_covers_all_data() {
+        let tree = points().into_iter().collect::<Tree3D>();
+
+        assert_eq!(tree.check_spatial_invariants(), Ok(()));
+        assert_eq!(tree.iter_data().count(), 200);
+        let (min, max) = tree.data_bounds().unwrap();
+        assert!((0..3).all(|axis| {
+            min[axis] >= tree.min_corner()[axis]
+                && max[axis] <= tree.max_corner()[axis]
+                && max[axis] - min[axis] >= 2.0 * tree.span()[axis] - 1e-12
+        }));
+        assert!(tree
+            .iter_depth_first()
+            .filter_map(|tree| tree.data())
+            .all(|vec| vec.len() <= 8));
+
+        let empty = Vec::<[f64; 2]>::new()
+            .into_iter()
+            .collect::<SpatialTree<_, 2>>();
+        assert_eq!(empty, SpatialTree::default());
+    }
+}
@@ -0,0 +1,161 @@
+use super::{SpatialTree, TreeConfig};
+use crate::{errors::TpnTreeError, Coordinates};
+use alloc::vec::Vec;
+
+/// Decides what [`SpatialTree::insert`] does with data for a leaf which holds `leaf_capacity` items, see [`TreeConfig::with_spillover`].
+///
+/// Without a spillover policy full leaves divide and keep data beyond their capacity once they are at the max depth.
+///
+/// ```
+/// # use tpntree::tpntree::{Spillover, Tree3D};
+/// # use tpntree::TpnTreeError;
+/// let mut tree = Tree3D::builder()
+///     .leaf_capacity(1)
+///     .spillover(Spillover::Reject)
+///     .build();
+///
+/// assert!(tree.insert([0.5, 0.5, 0.5]).is_ok());
+/// assert_eq!(tree.insert([0.5, 0.5, 0.5]), Err(TpnTreeError::CapacityExceeded));
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Spillover {
+    /// Divide the leaf and move its data into the children.
+    ///
+    /// Leaves at the max depth of the [`TreeConfig`] do not divide and reject the data with [`TpnTreeError::MaxDepthExceeded`].
+    Split,
+    /// Keep the data in the leaf beyond its capacity.
+    Overflow,
+    /// Reject the data with [`TpnTreeError::CapacityExceeded`].
+    Reject,
+}
+
+impl<T: Coordinates<N>, const N: usize> SpatialTree<T, N> {
+    // inserts into the leaf closest to the data, applying the spillover policy if the leaf is at capacity
+    // the data of a leaf is allocated for exactly the capacity, data moved into children on a split is not checked again
+    pub(crate) fn insert_with_spillover(
+        &mut self,
+        data: T,
+        spillover: Spillover,
+    ) -> Result<(), TpnTreeError> {
+        let capacity = self.config().and_then(|config| config.leaf_capacity());
+        let max_level = self
+            .config()
+            .and_then(|config| config.max_depth())
+            .unwrap_or(TreeConfig::DEFAULT_MAX_DEPTH);
+        let leaf = self.find_by_coordinates_mut(&data)?;
+
+        let is_full = capacity
+            .is_some_and(|capacity| leaf.data.as_ref().is_some_and(|vec| vec.len() >= capacity));
+
+        match spillover {
+            _ if !is_full => {}
+            Spillover::Overflow => {}
+            Spillover::Reject => return Err(TpnTreeError::CapacityExceeded),
+            Spillover::Split if leaf.level >= max_level => {
+                return Err(TpnTreeError::MaxDepthExceeded { max_level })
+            }
+            Spillover::Split => {
+                leaf.divide()?;
+                for data in leaf.data.take().unwrap_or_default() {
+                    let index = leaf.child_index_for(data.coordinates());
+                    leaf.children[index]
+                        .data
+                        .get_or_insert_with(|| Vec::with_capacity(capacity.unwrap_or(0)))
+                        .push(data);
+                }
+                return leaf.insert_with_spillover(data, spillover);
+            }
+        }
+
+        leaf.data
+            .get_or_insert_with(|| Vec::with_capacity(capacity.unwrap_or(0)))
+            .push(data);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tpntree::{Spillover, Tree3D};
+    use crate::TpnTreeError;
+
+    #[test]
+    fn split_full_leaves() {
+        let mut tree = Tree3D::builder()
+            .leaf_capacity(1)
+            .max_depth(4)
+            .spillover(Spillover::Split)
+            .build();
+
+        assert!(tree.insert([0.5, 0.5, 0.5]).is_ok());
+        assert!(tree.insert([-0.5, -0.5, -0.5]).is_ok());
+
+        assert_eq!(tree.child_count(), 8);
+        assert!(tree.data().is_none());
+        assert_eq!(
+            tree.find_by_coordinates(&[-0.5, -0.5, -0.5])
+                .ok()
+                .and_then(|tree| tree.data()),
+            Some(&vec![[-0.5, -0.5, -0.5]])
+        );
+    }
+
+    #[test]
+    fn coinciding_data_is_rejected_at_max_level() {
+        let mut tree = Tree3D::builder()
+            .leaf_capacity(1)
+            .max_depth(2)
+            .spillover(Spillover::Split)
+            .build();
+
+        assert!(tree.insert([0.1, 0.1, 0.1]).is_ok());
+        assert_eq!(
+            tree.insert([0.1, 0.1, 0.1]),
+            Err(TpnTreeError::MaxDepthExceeded { max_level: 2 })
+        );
+
+        let leaf = tree.find_by_coordinates(&[0.1, 0.1, 0.1]).unwrap();
+        assert_eq!(leaf.level(), 2);
+        assert_eq!(leaf.data().map(|vec| vec.len()), Some(1));
+    }
+
+    #[test]
+    fn overflow_keeps_data() {
+        let mut tree = Tree3D::builder()
+            .leaf_capacity(1)
+            .spillover(Spillover::Overflow)
+            .build();
+
+        for _ in 0..3 {
+            assert!(tree.insert([0.1, 0.1, 0.1]).is_ok());
+        }
+
+        assert!(tree.is_leaf());
+        assert_eq!(tree.data().map(|vec| vec.len()), Some(3));
+    }
+
+    #[test]
+    fn leaves_are_allocated_for_the_capacity() {
+        let mut tree = Tree3D::builder()
+            .leaf_capacity(4)
+            .spillover(Spillover::Reject)
+            .build();
+
+        assert!(tree.insert([0.1, 0.1, 0.1]).is_ok());
+
+        assert_eq!(tree.data().map(|vec| vec.capacity()), Some(4));
+    }
+
+    #[test]
+    fn insert_outside() {
+        let mut tree = Tree3D::builder()
+            .leaf_capacity(1)
+            .spillover(Spillover::Overflow)
+            .build();
+
+        assert!(matches!(
+            tree.insert([1.5, 0.1, 0.1]),
+            Err(TpnTreeError::DoesNotSpan { .. })
+        ));
+    }
+}
@@ -0,0 +1,150 @@
+use alloc::vec::Vec;
+
+use super::{Bounded, LooseTree};
+
+impl<T, const N: usize> LooseTree<T, N> {
+    /// Returns all pairs of stored objects whose bounding boxes intersect.
+    ///
+    /// Objects are compared with the objects of the same tree and of its descendants,
+    /// and with the objects of sibling subtrees whose loose bounds overlap.
+    /// Subtrees whose loose bounds an object does not intersect are skipped.
+    /// Each pair is listed once.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::LooseTree;
+    /// let mut tree = LooseTree::<&str, 2>::builder().looseness(2.0).build();
+    /// let division_condition = |tree: &LooseTree<&str, 2>| tree.is_root();
+    /// for (data, center) in [("a", [0.5, 0.5]), ("b", [0.6, 0.6]), ("c", [-0.5, -0.5])] {
+    ///     tree.insert_with_extent(data, center, [0.1, 0.1], &division_condition)
+    ///         .expect("Couldn't insert.");
+    /// }
+    ///
+    /// let pairs = tree.self_overlapping_pairs();
+    ///
+    /// assert_eq!(pairs.len(), 1);
+    /// assert_eq!((pairs[0].0.data, pairs[0].1.data), ("a", "b"));
+    /// ```
+    pub fn self_overlapping_pairs(&self) -> Vec<(&Bounded<T, N>, &Bounded<T, N>)> {
+        let mut pairs = Vec::new();
+        self.self_overlapping_recursive(&mut pairs);
+        pairs
+    }
+
+    // pairs within the subtree
+    fn self_overlapping_recursive<'a>(
+        &'a self,
+        pairs: &mut Vec<(&'a Bounded<T, N>, &'a Bounded<T, N>)>,
+    ) {
+        let own = self.data.iter().flatten().collect::<Vec<_>>();
+        for (i, bounded) in own.iter().enumerate() {
+            pairs.extend(
+                own[..i]
+                    .iter()
+                    .filter(|other| other.intersects(&bounded.center, &bounded.half_size))
+                    .map(|other| (*other, *bounded)),
+            );
+        }
+
+        for (i, child) in self.children.iter().enumerate() {
+            child.overlapping_with(&own, pairs);
+            child.self_overlapping_recursive(pairs);
+            for sibling in &self.children[..i] {
+                sibling.overlapping_across(child, pairs);
+            }
+        }
+    }
+
+    // pairs of the objects with the objects of the subtree
+    fn overlapping_with<'a>(
+        &'a self,
+        objects: &[&'a Bounded<T, N>],
+        pairs: &mut Vec<(&'a Bounded<T, N>, &'a Bounded<T, N>)>,
+    ) {
+        let loose_span = self.loose_span();
+        let objects = objects
+            .iter()
+            .copied()
+            .filter(|bounded| bounded.intersects(&self.coordinates, &loose_span))
+            .collect::<Vec<_>>();
+        if objects.is_empty() {
+            return;
+        }
+
+        for bounded in self.data.iter().flatten() {
+            pairs.extend(
+                objects
+                    .iter()
+                    .filter(|other| other.intersects(&bounded.center, &bounded.half_size))
+                    .map(|other| (*other, bounded)),
+            );
+        }
+
+        for child in &self.children {
+            child.overlapping_with(&objects, pairs);
+        }
+    }
+
+    // pairs of one object of each subtree
+    fn overlapping_across<'a>(
+        &'a self,
+        other: &'a Self,
+        pairs: &mut Vec<(&'a Bounded<T, N>, &'a Bounded<T, N>)>,
+    ) {
+        let (span, other_span) = (self.loose_span(), other.loose_span());
+        if !(0..N)
+            .all(|i| (self.coordinates[i] - other.coordinates[i]).abs() <= span[i] + other_span[i])
+        {
+            return;
+        }
+
+        let own = self.data.iter().flatten().collect::<Vec<_>>();
+        other.overlapping_with(&own, pairs);
+        for child in &self.children {
+            child.overlapping_across(other, pairs);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tpntree::LooseTree;
+
+    #[test]
+    fn pairs_match_brute_force() {
+        let mut tree = LooseTree::<usize, 2>::builder().looseness(2.0).build();
+        let division_condition = |tree: &LooseTree<usize, 2>| tree.level() < 3;
+        let objects = (0..60)
+            .map(|i| {
+                let t = i as f64;
+                let size = 0.02 + 0.1 * (t * 0.31).sin().abs();
+                ([0.8 * (t * 0.9).sin(), 0.8 * (t * 1.7).cos()], [size, size])
+            })
+            .collect::<Vec<_>>();
+        for (i, (center, half_size)) in objects.iter().enumerate() {
+            tree.insert_with_extent(i, *center, *half_size, &division_condition)
+                .unwrap();
+        }
+
+        let mut pairs = tree
+            .self_overlapping_pairs()
+            .iter()
+            .map(|(a, b)| (a.data.min(b.data), a.data.max(b.data)))
+            .collect::<Vec<_>>();
+        pairs.sort_unstable();
+
+        let mut expected = Vec::new();
+        for (i, (center, half_size)) in objects.iter().enumerate() {
+            for (j, (other_center, other_half_size)) in objects.iter().enumerate().skip(i + 1) {
+                if (0..2).all(|axis| {
+                    (center[axis] - other_center[axis]).abs()
+                        <= half_size[axis] + other_half_size[axis]
+                }) {
+                    expected.push((i, j));
+                }
+            }
+        }
+
+        assert!(!expected.is_empty());
+        assert_eq!(pairs, expected);
+    }
+}
@@ -0,0 +1,147 @@
+use alloc::{collections::BTreeMap, vec::Vec};
+
+use super::TpnTree;
+
+/// The child indices leading from a tree down to one of its descendants, see [`TpnTree::get_descendant`].
+pub type NodePath = Vec<usize>;
+
+impl<T, const N: usize> TpnTree<T, N> {
+    /// Returns the descendant reached by following the child indices of the path, or the tree itself for an empty path.
+    pub fn get_descendant(&self, path: &[usize]) -> Option<&Self> {
+        path.iter()
+            .try_fold(self, |tree, &index| tree.get_child(index))
+    }
+
+    /// Returns the descendant reached by following the child indices of the path by mutable reference, or the tree itself for an empty path.
+    pub fn get_descendant_mut(&mut self, path: &[usize]) -> Option<&mut Self> {
+        path.iter()
+            .try_fold(self, |tree, &index| tree.get_child_mut(index))
+    }
+
+    /// Groups the occupied leaves into components of leaves connected by shared faces.
+    ///
+    /// Leaves of different size are connected if they share a part of a face, like in [`TpnTree::dual_graph`].
+    /// Components are ordered by their first leaf, leaves within a component in depth first order.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::TpnTree;
+    /// let mut root = TpnTree::<bool, 2>::root(1.0);
+    /// root.divide().expect("Couldn't divide.");
+    /// // two diagonal quadrants only touch at a corner
+    /// *root.get_child_mut(0).unwrap().data_mut() = Some(true);
+    /// *root.get_child_mut(3).unwrap().data_mut() = Some(true);
+    ///
+    /// let components = root.connected_components(|leaf| leaf.data() == Some(&true));
+    ///
+    /// assert_eq!(components.len(), 2);
+    /// ```
+    pub fn connected_components(&self, is_occupied: impl Fn(&Self) -> bool) -> Vec<Vec<NodePath>> {
+        let mut leaves = Vec::new();
+        self.collect_leaf_paths(&mut Vec::new(), &mut leaves);
+        let indices = leaves
+            .iter()
+            .enumerate()
+            .map(|(index, &(leaf, _))| (leaf as *const Self, index))
+            .collect::<BTreeMap<_, _>>();
+        let occupied = leaves
+            .iter()
+            .map(|(leaf, _)| is_occupied(leaf))
+            .collect::<Vec<_>>();
+
+        // the dual graph numbers the leaves in the order of iter_depth_first
+        let graph_leaves = self
+            .iter_depth_first()
+            .filter(|tree| tree.is_leaf())
+            .map(|leaf| indices[&(leaf as *const Self)])
+            .collect::<Vec<_>>();
+
+        let mut parents = (0..leaves.len()).collect::<Vec<_>>();
+        for [a, b] in self.dual_graph().edges {
+            let (a, b) = (graph_leaves[a], graph_leaves[b]);
+            if occupied[a] && occupied[b] {
+                let (a, b) = (find_root(&mut parents, a), find_root(&mut parents, b));
+                parents[a.max(b)] = a.min(b);
+            }
+        }
+
+        let mut components = Vec::<Vec<NodePath>>::new();
+        let mut component_of_root = BTreeMap::new();
+        for (index, (_, path)) in leaves.into_iter().enumerate() {
+            if !occupied[index] {
+                continue;
+            }
+            let root = find_root(&mut parents, index);
+            let component = *component_of_root.entry(root).or_insert_with(|| {
+                components.push(Vec::new());
+                components.len() - 1
+            });
+            components[component].push(path);
+        }
+        components
+    }
+
+    // leaves with their paths, children in the order of their index
+    fn collect_leaf_paths<'a>(
+        &'a self,
+        path: &mut NodePath,
+        leaves: &mut Vec<(&'a Self, NodePath)>,
+    ) {
+        if self.is_leaf() {
+            leaves.push((self, path.clone()));
+        }
+        for (index, child) in self.children.iter().enumerate() {
+            path.push(index);
+            child.collect_leaf_paths(path, leaves);
+            path.pop();
+        }
+    }
+}
+
+fn find_root(parents: &mut [usize], mut index: usize) -> usize {
+    while parents[index] != index {
+        parents[index] = parents[parents[index]];
+        index = parents[index];
+    }
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tpntree::TpnTree;
+
+    #[test]
+    fn components_connect_leaves_of_different_size() {
+        let mut root = TpnTree::<bool, 2>::root(1.0);
+        root.divide_to_depth(1).expect("Couldn't divide.");
+        root.get_child_mut(0)
+            .unwrap()
+            .divide()
+            .expect("Couldn't divide.");
+
+        // a small leaf of child 0 touching child 1, and child 2 on its own
+        let occupied = [vec![0, 1], vec![1], vec![2]];
+        for path in &occupied {
+            let mut tree = &mut root;
+            for &index in path {
+                tree = tree.get_child_mut(index).unwrap();
+            }
+            *tree.data_mut() = Some(true);
+        }
+
+        let components = root.connected_components(|leaf| leaf.data() == Some(&true));
+
+        assert_eq!(components, vec![vec![vec![0, 1], vec![1]], vec![vec![2]]]);
+        assert!(components
+            .iter()
+            .flatten()
+            .all(|path| root.get_descendant(path).unwrap().data() == Some(&true)));
+    }
+
+    #[test]
+    fn components_of_empty_tree() {
+        let root = TpnTree::<bool, 3>::root(1.0);
+
+        assert!(root.connected_components(|_| false).is_empty());
+        assert_eq!(root.connected_components(|_| true), vec![vec![vec![]]]);
+    }
+}
@@ -0,0 +1,257 @@
+use alloc::vec::Vec;
+use std::sync::{Mutex, PoisonError};
+
+use super::{SpatialTree, TpnTree};
+use crate::{errors::TpnTreeError, Coordinates};
+
+/// A spatial tree accepting insertions from multiple threads at once.
+///
+/// The tree is divided up front to a fixed split depth and every subtree at that depth is guarded by its own lock.
+/// Insertions only lock the subtree containing their data, so threads inserting into different regions do not block each other.
+/// Below the split depth the subtrees divide as usual.
+///
+/// The tree is [`Send`] and [`Sync`] whenever the data is [`Send`], so it can be shared by reference between threads.
+///
+/// ```
+/// # use tpntree::tpntree::{ConcurrentSpatialTree, Tree3D};
+/// let tree = ConcurrentSpatialTree::new(Tree3D::builder().leaf_capacity(8).build(), 1)
+///     .expect("Couldn't divide.");
+///
+/// std::thread::scope(|scope| {
+///     for thread in 0..4 {
+///         let tree = &tree;
+///         scope.spawn(move || {
+///             for i in 0..100 {
+///                 let t = (thread * 100 + i) as f64 * 0.1;
+///                 tree.insert([t.sin(), t.cos(), (t * 0.3).sin()]).expect("Couldn't insert.");
+///             }
+///         });
+///     }
+/// });
+///
+/// let tree = tree.into_inner();
+/// assert_eq!(tree.iter_depth_first().filter_map(|tree| tree.data()).map(|vec| vec.len()).sum::<usize>(), 400);
+/// ```
+#[derive(Debug)]
+pub struct ConcurrentSpatialTree<T, const N: usize> {
+    // the tree above the split depth, with empty leaves where the subtrees belong
+    skeleton: SpatialTree<T, N>,
+    // the skeleton holding the index of its subtree at every tree at the split depth
+    slots: TpnTree<usize, N>,
+    // subtrees at the split depth in depth first order
+    subtrees: Vec<Mutex<SpatialTree<T, N>>>,
+    split_depth: usize,
+}
+
+impl<T: Coordinates<N>, const N: usize> ConcurrentSpatialTree<T, N> {
+    /// Prepares the tree for concurrent insertions by dividing it `split_depth` levels deep.
+    ///
+    /// Every tree at the split depth becomes an independently locked subtree, 2^(N * split_depth) of them unless the tree was divided in other ways than [`TpnTree::divide`] before.
+    /// Errors if the [`TreeConfig`](super::TreeConfig) of the tree does not allow dividing that deep.
+    pub fn new(mut tree: SpatialTree<T, N>, split_depth: usize) -> Result<Self, TpnTreeError> {
+        let level = tree.level + split_depth;
+        tree.divide_to_depth(level)?;
+
+        let mut subtrees = Vec::new();
+        detach_subtrees(&mut tree, level, &mut subtrees);
+        let mut slots = tree.map_ref(|_| None);
+        number_slots(&mut slots, level, &mut 0);
+
+        Ok(Self {
+            skeleton: tree,
+            slots,
+            subtrees: subtrees.into_iter().map(Mutex::new).collect(),
+            split_depth,
+        })
+    }
+
+    /// Inserts data in the tree with its center closest to the data given the constrains of the `division_condition`.
+    ///
+    /// Locks only the subtree containing the data.
+    /// Behaves like [`SpatialTree::insert_by_coordinates`] below the split depth and errors in the same cases.
+    pub fn insert_by_coordinates(
+        &self,
+        data: T,
+        division_condition: &dyn Fn(&SpatialTree<T, N>) -> bool,
+    ) -> Result<(), TpnTreeError> {
+        let index = self.subtree_index(&data)?;
+        self.subtrees[index]
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert_by_coordinates(data, division_condition)
+    }
+
+    /// Inserts data in the tree, dividing leaves according to the [`TreeConfig`](super::TreeConfig) of the tree.
+    ///
+    /// Locks only the subtree containing the data.
+    /// Behaves like [`SpatialTree::insert`] below the split depth and errors in the same cases.
+    pub fn insert(&self, data: T) -> Result<(), TpnTreeError> {
+        let index = self.subtree_index(&data)?;
+        self.subtrees[index]
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(data)
+    }
+
+    // finds the subtree containing the data, trees may have any count of children above the split depth
+    fn subtree_index(&self, data: &T) -> Result<usize, TpnTreeError> {
+        let coordinates = data.try_coordinates()?;
+        if !self.skeleton.spans_coordinates(coordinates) {
+            return Err(self.skeleton.does_not_span(coordinates));
+        }
+
+        let mut tree = &self.slots;
+        for _ in 0..self.split_depth {
+            tree = &tree.children[tree.child_index_for(coordinates)];
+        }
+        Ok(tree.data.expect("Trees at the split depth are numbered."))
+    }
+
+    /// Reassembles the tree once all insertions are done.
+    pub fn into_inner(self) -> SpatialTree<T, N> {
+        let mut tree = self.skeleton;
+        let mut subtrees = self
+            .subtrees
+            .into_iter()
+            .map(|subtree| subtree.into_inner().unwrap_or_else(PoisonError::into_inner));
+        let level = tree.level + self.split_depth;
+        attach_subtrees(&mut tree, level, &mut subtrees);
+        tree
+    }
+}
+
+// replaces all trees at the level by empty leaves, collecting them in depth first order
+fn detach_subtrees<T, const N: usize>(
+    tree: &mut TpnTree<T, N>,
+    level: usize,
+    subtrees: &mut Vec<TpnTree<T, N>>,
+) {
+    if tree.level == level {
+        let mut empty = TpnTree::new(tree.coordinates, tree.span, tree.level);
+        empty.config = tree.config.clone();
+        subtrees.push(core::mem::replace(tree, empty));
+    } else {
+        for child in &mut tree.children {
+            detach_subtrees(child, level, subtrees);
+        }
+    }
+}
+
+// numbers the trees at the level in depth first order, like the detached subtrees
+fn number_slots<const N: usize>(tree: &mut TpnTree<usize, N>, level: usize, next: &mut usize) {
+    if tree.level == level {
+        tree.data = Some(*next);
+        *next += 1;
+    } else {
+        for child in &mut tree.children {
+            number_slots(child, level, next);
+        }
+    }
+}
+
+// puts the subtrees back in place of the trees at the level, in depth first order
+fn attach_subtrees<T, const N: usize>(
+    tree: &mut TpnTree<T, N>,
+    level: usize,
+    subtrees: &mut impl Iterator<Item = TpnTree<T, N>>,
+) {
+    if tree.level == level {
+        if let Some(subtree) = subtrees.next() {
+            *tree = subtree;
+        }
+    } else {
+        for child in &mut tree.children {
+            attach_subtrees(child, level, subtrees);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tpntree::{ConcurrentSpatialTree, Tree3D};
+    use crate::TpnTreeError;
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    fn points(thread: usize) -> Vec<[f64; 3]> {
+        (0..500)
+            .map(|i| {
+                let t = (thread * 500 + i) as f64 * 0.37;
+                [t.sin(), (t * 1.3).cos(), (t * 0.7).sin()]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn is_send_and_sync() {
+        assert_send_sync::<ConcurrentSpatialTree<[f64; 3], 3>>();
+    }
+
+    #[test]
+    fn parallel_insertions_match_serial_ones() {
+        let mut serial = Tree3D::builder().leaf_capacity(4).max_depth(8).build();
+        let concurrent = ConcurrentSpatialTree::new(serial.clone(), 2).unwrap();
+
+        std::thread::scope(|scope| {
+            for thread in 0..8 {
+                let concurrent = &concurrent;
+                scope.spawn(move || {
+                    for point in points(thread) {
+                        assert!(concurrent.insert(point).is_ok());
+                    }
+                });
+            }
+        });
+        for thread in 0..8 {
+            for point in points(thread) {
+                assert!(serial.insert(point).is_ok());
+            }
+        }
+
+        let concurrent = concurrent.into_inner();
+        assert!(concurrent.structurally_equal(&serial));
+        for point in points(3) {
+            let leaf = concurrent.find_by_coordinates(&point).unwrap();
+            assert!(leaf.data().is_some_and(|vec| vec.contains(&point)));
+        }
+    }
+
+    #[test]
+    fn subtrees_follow_any_split() {
+        let mut tree = Tree3D::builder().leaf_capacity(4).max_depth(8).build();
+        assert!(tree.divide_with_branching(3).is_ok());
+        assert!(tree.get_child_mut(4).unwrap().divide_axes(&[2]).is_ok());
+        let concurrent = ConcurrentSpatialTree::new(tree, 2).unwrap();
+        assert_eq!(concurrent.subtrees.len(), 26 * 8 + 2);
+
+        for point in points(0) {
+            assert!(concurrent.insert(point).is_ok());
+        }
+
+        let tree = concurrent.into_inner();
+        assert_eq!(tree.get_child(4).unwrap().split_axes(), vec![2]);
+        assert_eq!(tree.check_spatial_invariants(), Ok(()));
+        for point in points(0) {
+            let leaf = tree.find_by_coordinates(&point).unwrap();
+            assert!(leaf.data().is_some_and(|vec| vec.contains(&point)));
+        }
+    }
+
+    #[test]
+    fn keeps_existing_data_and_reports_errors() {
+        let mut tree = Tree3D::root(1.0);
+        assert!(tree
+            .insert_by_coordinates([0.5, 0.5, 0.5], &|_| false)
+            .is_ok());
+
+        let concurrent = ConcurrentSpatialTree::new(tree, 1).unwrap();
+        assert!(matches!(
+            concurrent.insert([0.0, 0.0, 1.5]),
+            Err(TpnTreeError::DoesNotSpan { .. })
+        ));
+
+        let tree = concurrent.into_inner();
+        assert_eq!(tree.child_count(), 8);
+        assert_eq!(tree.data(), Some(&vec![[0.5, 0.5, 0.5]]));
+    }
+}
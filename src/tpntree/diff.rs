@@ -0,0 +1,272 @@
+use super::{NodeKey, SplitLayout, TpnTree};
+use crate::errors::TpnTreeError;
+use alloc::vec::Vec;
+
+/// A single change of a [`TreeDiff`], locating the changed tree by its key.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TreeChange<T, const N: usize> {
+    /// The leaf was divided with the layout into children without data.
+    Divided(NodeKey<N>, SplitLayout<N>),
+    /// All children of the tree were removed.
+    Merged(NodeKey<N>),
+    /// The data of the tree was replaced.
+    Data(NodeKey<N>, Option<T>),
+}
+
+/// The changes turning one tree into another, see [`TpnTree::diff`].
+///
+/// Changes are ordered such that replaying them in order only ever touches existing trees.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TreeDiff<T, const N: usize> {
+    pub changes: Vec<TreeChange<T, N>>,
+}
+
+impl<T, const N: usize> TreeDiff<T, N> {
+    /// Checks if there are no changes.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl<T: Clone + PartialEq, const N: usize> TpnTree<T, N> {
+    /// Lists the divisions, merges and data changes turning this tree into the other one.
+    ///
+    /// Errors with [`TpnTreeError::RegionMismatch`] if the trees do not cover the same region,
+    /// with [`TpnTreeError::MaxDepthExceeded`] if a change lies too deep for a [`NodeKey`],
+    /// or with [`TpnTreeError::InvalidBranching`] if the children of a tree divided with [`TpnTree::divide_with_branching`] need to be compared,
+    /// as a [`NodeKey`] can not address all of them.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::TpnTree;
+    /// let before = TpnTree::<u8, 2>::root(1.0);
+    /// let mut after = before.clone();
+    /// after.divide().expect("Couldn't divide.");
+    /// *after.get_child_mut(1).unwrap().data_mut() = Some(4);
+    ///
+    /// let diff = before.diff(&after).expect("Same region.");
+    /// assert_eq!(diff.changes.len(), 2);
+    ///
+    /// let mut replayed = before.clone();
+    /// replayed.apply(diff).expect("Couldn't apply.");
+    /// assert_eq!(replayed, after);
+    /// ```
+    pub fn diff(&self, other: &Self) -> Result<TreeDiff<T, N>, TpnTreeError> {
+        if self.coordinates != other.coordinates || self.span != other.span {
+            return Err(TpnTreeError::RegionMismatch {
+                expected: (self.coordinates.to_vec(), self.span.to_vec()),
+                got: (other.coordinates.to_vec(), other.span.to_vec()),
+            });
+        }
+
+        let mut changes = Vec::new();
+        self.diff_recursive(other, NodeKey::root(), &mut changes)?;
+        Ok(TreeDiff { changes })
+    }
+
+    /// Replays the changes of a diff, see [`TpnTree::diff`].
+    ///
+    /// Errors with [`TpnTreeError::StructureMismatch`] if a change refers to a missing tree,
+    /// or with the error of [`TpnTree::divide_with_layout`] if a tree can not be divided.
+    /// Changes before the failing one stay applied.
+    pub fn apply(&mut self, diff: TreeDiff<T, N>) -> Result<(), TpnTreeError> {
+        for change in diff.changes {
+            match change {
+                TreeChange::Divided(key, layout) => {
+                    self.find_changed(key)?.divide_with_layout(layout)?
+                }
+                TreeChange::Merged(key) => self.find_changed(key)?.clear_children(),
+                TreeChange::Data(key, data) => self.find_changed(key)?.data = data,
+            }
+        }
+        Ok(())
+    }
+
+    fn find_changed(&mut self, key: NodeKey<N>) -> Result<&mut Self, TpnTreeError> {
+        self.find_by_key_mut(key)
+            .ok_or(TpnTreeError::StructureMismatch)
+    }
+
+    fn diff_recursive(
+        &self,
+        other: &Self,
+        key: NodeKey<N>,
+        changes: &mut Vec<TreeChange<T, N>>,
+    ) -> Result<(), TpnTreeError> {
+        if self.data != other.data {
+            changes.push(TreeChange::Data(key, other.data.clone()));
+        }
+
+        match (self.is_leaf(), other.is_leaf()) {
+            (true, true) => {}
+            (false, true) => changes.push(TreeChange::Merged(key)),
+            (true, false) => other.diff_divided(key, changes)?,
+            // trees split differently are merged and divided again
+            (false, false) if self.split_layout() != other.split_layout() => {
+                changes.push(TreeChange::Merged(key));
+                other.diff_divided(key, changes)?;
+            }
+            (false, false) => {
+                self.check_keys_for_children()?;
+                for (index, (child, other_child)) in
+                    self.children.iter().zip(&other.children).enumerate()
+                {
+                    child.diff_recursive(other_child, child_key(key, index)?, changes)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // lists the changes creating the tree below a freshly divided leaf
+    fn diff_added(
+        &self,
+        key: NodeKey<N>,
+        changes: &mut Vec<TreeChange<T, N>>,
+    ) -> Result<(), TpnTreeError> {
+        if self.data.is_some() {
+            changes.push(TreeChange::Data(key, self.data.clone()));
+        }
+        if !self.is_leaf() {
+            self.diff_divided(key, changes)?;
+        }
+        Ok(())
+    }
+
+    // lists the division of a leaf into the children of this tree and the changes creating them
+    fn diff_divided(
+        &self,
+        key: NodeKey<N>,
+        changes: &mut Vec<TreeChange<T, N>>,
+    ) -> Result<(), TpnTreeError> {
+        self.check_keys_for_children()?;
+        changes.push(TreeChange::Divided(key, self.split_layout()));
+        for (index, child) in self.children.iter().enumerate() {
+            child.diff_added(child_key(key, index)?, changes)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T, const N: usize> TpnTree<T, N> {
+    // keys hold child indices below 2^N, which trees divided into more parts per axis exceed
+    fn check_keys_for_children(&self) -> Result<(), TpnTreeError> {
+        match self.branching {
+            2 => Ok(()),
+            branching => Err(TpnTreeError::InvalidBranching { branching }),
+        }
+    }
+}
+
+fn child_key<const N: usize>(key: NodeKey<N>, index: usize) -> Result<NodeKey<N>, TpnTreeError> {
+    key.child(index).ok_or(TpnTreeError::MaxDepthExceeded {
+        max_level: NodeKey::<N>::MAX_LEVEL,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tpntree::{NodeKey, TpnTree, TreeChange, TreeDiff};
+    use crate::TpnTreeError;
+
+    #[test]
+    fn diff_replays_divisions_merges_and_data() {
+        let mut before = TpnTree::<u32, 3>::root(1.0);
+        before.divide_to_depth(2).unwrap();
+        *before.get_child_mut(2).unwrap().data_mut() = Some(1);
+
+        let mut after = before.clone();
+        after.get_child_mut(0).unwrap().children.clear();
+        let child = after.get_child_mut(7).unwrap().get_child_mut(3).unwrap();
+        child.divide().unwrap();
+        child.get_child_mut(5).unwrap().divide().unwrap();
+        *child
+            .get_child_mut(5)
+            .unwrap()
+            .get_child_mut(1)
+            .unwrap()
+            .data_mut() = Some(2);
+        *after.get_child_mut(2).unwrap().data_mut() = None;
+
+        let diff = before.diff(&after).unwrap();
+        assert!(diff
+            .changes
+            .contains(&TreeChange::Merged(NodeKey::from_path(&[0]).unwrap())));
+        assert!(diff
+            .changes
+            .contains(&TreeChange::Data(NodeKey::from_path(&[2]).unwrap(), None)));
+
+        let mut replayed = before.clone();
+        replayed.apply(diff).unwrap();
+        assert_eq!(replayed, after);
+        assert!(after.diff(&replayed).unwrap().is_empty());
+    }
+
+    #[test]
+    fn diff_replays_anisotropic_and_off_center_divisions() {
+        let mut before = TpnTree::<u32, 3>::root(1.0);
+        assert!(before.divide().is_ok());
+
+        let mut after = before.clone();
+        assert!(after.get_child_mut(2).unwrap().divide_axes(&[0, 1]).is_ok());
+        *after
+            .get_child_mut(2)
+            .unwrap()
+            .get_child_mut(3)
+            .unwrap()
+            .data_mut() = Some(1);
+        let mut resplit = TpnTree::<u32, 3>::root(1.0);
+        assert!(resplit.divide_axes(&[2]).is_ok());
+        let mut off_center = TpnTree::<u32, 3>::root(1.0);
+        assert!(off_center.divide_at([0.5, 0.5, 0.5]).is_ok());
+        for after in [after, resplit, off_center] {
+            let diff = before.diff(&after).unwrap();
+            let mut replayed = before.clone();
+            replayed.apply(diff).unwrap();
+
+            assert_eq!(replayed, after);
+            for (a, b) in replayed.iter_depth_first().zip(after.iter_depth_first()) {
+                assert_eq!(a.split_layout(), b.split_layout());
+            }
+        }
+    }
+
+    #[test]
+    fn diff_rejects_branching_children() {
+        let before = TpnTree::<u32, 2>::root(1.0);
+        let mut after = before.clone();
+        assert!(after.divide_with_branching(3).is_ok());
+
+        assert_eq!(
+            before.diff(&after),
+            Err(TpnTreeError::InvalidBranching { branching: 3 })
+        );
+        assert_eq!(
+            after.diff(&after),
+            Err(TpnTreeError::InvalidBranching { branching: 3 })
+        );
+        assert_eq!(
+            after.diff(&before).map(|diff| diff.changes),
+            Ok(vec![TreeChange::Merged(NodeKey::root())])
+        );
+    }
+
+    #[test]
+    fn apply_reports_missing_trees() {
+        let mut before = TpnTree::<u32, 2>::root(1.0);
+        let mut after = before.clone();
+        after.divide().unwrap();
+        after.get_child_mut(0).unwrap().divide().unwrap();
+
+        let diff = before.diff(&after).unwrap();
+        before.divide().unwrap();
+
+        assert_eq!(before.apply(diff), Err(TpnTreeError::CanNotDivide));
+        let missing = TreeDiff {
+            changes: vec![TreeChange::Merged(NodeKey::from_path(&[1]).unwrap())],
+        };
+        assert_eq!(
+            TpnTree::<u32, 2>::root(1.0).apply(missing),
+            Err(TpnTreeError::StructureMismatch)
+        );
+    }
+}
@@ -0,0 +1,143 @@
+use alloc::{collections::BTreeMap, vec::Vec};
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use num_traits::Float;
+
+use super::SpatialTree;
+use crate::Coordinates;
+
+impl<T: Coordinates<N>, const N: usize> SpatialTree<T, N> {
+    /// Returns the centroid of the data in every occupied cell at the level `level`, like a voxel grid filter.
+    ///
+    /// The cells are those a tree divided down to `level` would have, independent of how far the tree is actually divided.
+    /// Trees at or below `level` form a single cell.
+    /// Cells are returned in depth first order of the trees holding them.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::TpnTree;
+    /// let mut tree = TpnTree::<Vec<[f64; 2]>, 2>::root(1.0);
+    /// for data in [[0.5, 0.5], [0.7, 0.9], [-0.5, -0.5]] {
+    ///     tree.insert_by_coordinates(data, &|_| false).expect("Couldn't insert.");
+    /// }
+    ///
+    /// let mut centroids = tree.downsample(1);
+    /// centroids.sort_by(|a, b| a[0].total_cmp(&b[0]));
+    ///
+    /// assert_eq!(centroids, vec![[-0.5, -0.5], [0.6, 0.7]]);
+    /// ```
+    pub fn downsample(&self, level: usize) -> Vec<[f64; N]> {
+        self.voxel_cells(level)
+            .into_iter()
+            .map(|cell| {
+                let mut centroid = [0.0; N];
+                for data in &cell {
+                    for (sum, coordinate) in centroid.iter_mut().zip(data.coordinates()) {
+                        *sum += coordinate;
+                    }
+                }
+                centroid.map(|sum| sum / cell.len() as f64)
+            })
+            .collect()
+    }
+
+    /// Returns the first data of every occupied cell at the level `level`, see [`SpatialTree::downsample`].
+    ///
+    /// Unlike the centroid the representative is one of the original items.
+    pub fn downsample_first(&self, level: usize) -> Vec<&T> {
+        self.voxel_cells(level)
+            .into_iter()
+            .map(|cell| cell[0])
+            .collect()
+    }
+
+    fn voxel_cells(&self, level: usize) -> Vec<Vec<&T>> {
+        let mut cells = Vec::new();
+        self.collect_voxel_cells(level, &mut cells);
+        cells
+    }
+
+    fn collect_voxel_cells<'a>(&'a self, level: usize, cells: &mut Vec<Vec<&'a T>>) {
+        if self.level >= level {
+            let cell = self
+                .iter_depth_first()
+                .flat_map(|tree| tree.data.iter().flatten())
+                .collect::<Vec<_>>();
+            if !cell.is_empty() {
+                cells.push(cell);
+            }
+        } else if self.is_leaf() {
+            // the leaf is coarser than the cells, so its data is binned half-open like insertion would
+            let per_axis = 2.0_f64.powi((level - self.level) as i32);
+            let min_corner = self.min_corner();
+            let mut binned = BTreeMap::<Vec<u64>, Vec<&T>>::new();
+            for data in self.data.iter().flatten() {
+                let key = data
+                    .coordinates()
+                    .iter()
+                    .zip(min_corner)
+                    .zip(self.span)
+                    .map(|((coordinate, min), span)| {
+                        ((coordinate - min) / (2.0 * span) * per_axis).clamp(0.0, per_axis - 1.0)
+                            as u64
+                    })
+                    .collect();
+                binned.entry(key).or_default().push(data);
+            }
+            cells.extend(binned.into_values());
+        } else {
+            for child in &self.children {
+                child.collect_voxel_cells(level, cells);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tpntree::Tree3D;
+
+    fn grid_tree(division_condition: &dyn Fn(&Tree3D) -> bool) -> Tree3D {
+        let mut tree = Tree3D::root(1.0);
+        for x in 0..8 {
+            for y in 0..8 {
+                for z in 0..8 {
+                    let data = [x, y, z].map(|c| -0.9375 + 0.25 * c as f64);
+                    tree.insert_by_coordinates(data, division_condition)
+                        .expect("Couldn't insert.");
+                }
+            }
+        }
+        tree
+    }
+
+    #[test]
+    fn downsample_is_independent_of_division() {
+        let undivided = grid_tree(&|_| false);
+        let divided = grid_tree(&|tree| tree.data().map_or(0, Vec::len) >= 4);
+
+        for level in 0..4 {
+            let mut a = undivided.downsample(level);
+            let mut b = divided.downsample(level);
+            a.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            b.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+            assert_eq!(a.len(), 8_usize.pow(level as u32));
+            assert_eq!(a, b);
+        }
+    }
+
+    #[test]
+    fn downsample_first_picks_items_of_distinct_cells() {
+        let tree = grid_tree(&|_| false);
+
+        let representatives = tree.downsample_first(1);
+
+        assert_eq!(representatives.len(), 8);
+        assert!(representatives
+            .iter()
+            .all(|data| tree.downsample(1).iter().any(|centroid| centroid
+                .iter()
+                .zip(data.iter())
+                .all(|(c, d)| (c - d).abs() < 0.5))));
+    }
+}
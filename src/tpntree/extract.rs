@@ -0,0 +1,101 @@
+use alloc::vec::Vec;
+
+use super::SpatialTree;
+use crate::Coordinates;
+
+impl<T: Coordinates<N>, const N: usize> SpatialTree<T, N> {
+    /// Returns a standalone tree covering the box given by center and half extent, holding clones of all items within the box.
+    ///
+    /// The items lie within the box including its faces.
+    /// The new tree is a root sharing the configuration of this tree, its leaves divide like on [`SpatialTree::insert`].
+    ///
+    /// ```
+    /// # use tpntree::tpntree::Tree3D;
+    /// let mut tree = Tree3D::builder().leaf_capacity(1).build();
+    /// tree.insert([0.5, 0.5, 0.5]).expect("Couldn't insert.");
+    /// tree.insert([-0.5, 0.5, 0.5]).expect("Couldn't insert.");
+    ///
+    /// let region = tree.extract_region([0.5; 3], [0.5; 3]);
+    ///
+    /// assert_eq!(region.coordinates(), [0.5; 3]);
+    /// assert_eq!(region.iter_data().collect::<Vec<_>>(), vec![&[0.5, 0.5, 0.5]]);
+    /// assert_eq!(tree.iter_data().count(), 2);
+    /// ```
+    pub fn extract_region(&self, center: [f64; N], half_extent: [f64; N]) -> Self
+    where
+        T: Clone,
+    {
+        let items = self
+            .iter_depth_first_in_region(center, half_extent)
+            .filter_map(|tree| tree.data())
+            .flatten()
+            .filter(|&item| within(item, &center, &half_extent))
+            .cloned()
+            .collect::<Vec<_>>();
+        self.region_tree(center, half_extent, items)
+    }
+
+    /// Like [`SpatialTree::extract_region`], but moves the items out of this tree instead of cloning them.
+    ///
+    /// Trees of this tree left without items collapse like with [`SpatialTree::drain_filter`].
+    pub fn extract_region_into(&mut self, center: [f64; N], half_extent: [f64; N]) -> Self {
+        let items = self.drain_filter(|item| within(item, &center, &half_extent));
+        self.region_tree(center, half_extent, items)
+    }
+
+    // a root covering the box with the config of this tree holding the items
+    fn region_tree(&self, center: [f64; N], half_extent: [f64; N], items: Vec<T>) -> Self {
+        let mut tree = Self::new(center, half_extent, 0);
+        tree.share_config(self.config.as_ref());
+        tree.extend(items);
+        tree
+    }
+}
+
+// checks if the item lies within the box including its faces
+fn within<T: Coordinates<N>, const N: usize>(
+    item: &T,
+    center: &[f64; N],
+    half_extent: &[f64; N],
+) -> bool {
+    item.coordinates()
+        .iter()
+        .zip(center)
+        .zip(half_extent)
+        .all(|((coordinate, center), half_extent)| (coordinate - center).abs() <= *half_extent)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tpntree::Tree3D;
+
+    #[test]
+    fn extracted_regions_hold_the_items_within() {
+        let mut tree = Tree3D::builder().leaf_capacity(3).max_depth(6).build();
+        let points = (0..100)
+            .map(|i| {
+                let t = i as f64;
+                [(t * 0.3).sin(), (t * 0.7).cos(), (t * 1.1).sin()]
+            })
+            .collect::<Vec<_>>();
+        tree.extend(points.clone());
+        let (center, half_extent) = ([0.2, -0.1, 0.0], [0.4, 0.5, 1.0]);
+        let inside = |point: &[f64; 3]| {
+            (0..3).all(|axis| (point[axis] - center[axis]).abs() <= half_extent[axis])
+        };
+
+        let copy = tree.extract_region(center, half_extent);
+        assert_eq!(copy.check_spatial_invariants(), Ok(()));
+        assert_eq!(copy.config(), tree.config());
+        assert_eq!(
+            copy.iter_data().count(),
+            points.iter().filter(|point| inside(point)).count()
+        );
+        assert_eq!(tree.iter_data().count(), 100);
+
+        let moved = tree.extract_region_into(center, half_extent);
+        assert_eq!(moved, copy);
+        assert!(tree.iter_data().all(|point| !inside(point)));
+        assert_eq!(tree.iter_data().count() + moved.iter_data().count(), 100);
+    }
+}
@@ -0,0 +1,113 @@
+use geo_types::{coord, Point, Rect};
+
+use super::{SpatialTree, TpnTree};
+use crate::{errors::TpnTreeError, Coordinates};
+
+impl<T> From<&TpnTree<T, 2>> for Rect<f64> {
+    /// Converts the area covered by the tree into a geo rectangle.
+    fn from(tree: &TpnTree<T, 2>) -> Self {
+        let [x, y] = tree.coordinates();
+        let [span_x, span_y] = tree.span();
+        Rect::new(
+            coord! { x: x - span_x, y: y - span_y },
+            coord! { x: x + span_x, y: y + span_y },
+        )
+    }
+}
+
+impl<T> From<Rect<f64>> for TpnTree<T, 2> {
+    /// Creates a new root TpnTree covering the geo rectangle.
+    fn from(rect: Rect<f64>) -> Self {
+        let center = rect.center();
+        TpnTree::new(
+            [center.x, center.y],
+            [rect.width() / 2.0, rect.height() / 2.0],
+            0,
+        )
+    }
+}
+
+impl<T> TpnTree<T, 2> {
+    /// Creates a new root TpnTree covering the geo rectangle.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::TpnTree;
+    /// use geo_types::{coord, Rect};
+    ///
+    /// let rect = Rect::new(coord! { x: 0.0, y: 0.0 }, coord! { x: 4.0, y: 2.0 });
+    /// let root = TpnTree::<(), 2>::from_geo_rect(rect);
+    ///
+    /// assert_eq!(root.coordinates(), [2.0, 1.0]);
+    /// assert_eq!(root.span(), [2.0, 1.0]);
+    /// assert_eq!(root.geo_rect(), rect);
+    /// ```
+    pub fn from_geo_rect(rect: Rect<f64>) -> Self {
+        rect.into()
+    }
+
+    /// Returns the area covered by the TpnTree as geo rectangle.
+    pub fn geo_rect(&self) -> Rect<f64> {
+        self.into()
+    }
+}
+
+impl<T: Coordinates<2> + From<Point<f64>>> SpatialTree<T, 2> {
+    /// Inserts the geo point converted into the data type of the tree, see [`SpatialTree::insert_by_coordinates`].
+    ///
+    /// ```
+    /// # use tpntree::tpntree::SpatialTree;
+    /// use geo_types::Point;
+    ///
+    /// let mut tree = SpatialTree::<[f64; 2], 2>::root(1.0);
+    /// let division_condition = |tree: &SpatialTree<[f64; 2], 2>| tree.data().is_some();
+    ///
+    /// assert!(tree.insert_geo_point(Point::new(0.5, 0.5), &division_condition).is_ok());
+    /// assert!(tree.insert_geo_point(Point::new(-0.5, -0.5), &division_condition).is_ok());
+    /// assert_eq!(tree.child_count(), 4);
+    /// ```
+    pub fn insert_geo_point(
+        &mut self,
+        point: Point<f64>,
+        division_condition: &dyn Fn(&Self) -> bool,
+    ) -> Result<(), TpnTreeError> {
+        self.insert_by_coordinates(T::from(point), division_condition)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo_types::{coord, Point, Rect};
+
+    use crate::tpntree::{SpatialTree, TpnTree};
+
+    #[test]
+    fn convert_between_trees_and_rects() {
+        let rect = Rect::new(coord! { x: -1.0, y: 0.0 }, coord! { x: 3.0, y: 1.0 });
+        let tree = TpnTree::<(), 2>::from(rect);
+
+        assert_eq!(tree.coordinates(), [1.0, 0.5]);
+        assert_eq!(tree.span(), [2.0, 0.5]);
+        assert_eq!(Rect::from(&tree), rect);
+    }
+
+    #[test]
+    fn insert_and_find_geo_points() {
+        let mut tree = SpatialTree::<[f64; 2], 2>::root(1.0);
+        let division_condition = |tree: &SpatialTree<[f64; 2], 2>| tree.data().is_some();
+
+        assert!(tree
+            .insert_geo_point(Point::new(0.5, 0.5), &division_condition)
+            .is_ok());
+        assert!(tree
+            .insert_geo_point(Point::new(-0.5, 0.5), &division_condition)
+            .is_ok());
+        assert!(tree
+            .insert_geo_point(Point::new(2.0, 0.0), &division_condition)
+            .is_err());
+
+        let leaf = tree
+            .find_by_coordinates(&[-0.5, 0.5])
+            .expect("Point was inserted.");
+        assert_eq!(leaf.data(), Some(&vec![[-0.5, 0.5]]));
+    }
+}
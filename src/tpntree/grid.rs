@@ -0,0 +1,159 @@
+use alloc::vec::Vec;
+use core::ops::Range;
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use num_traits::Float;
+
+use super::TpnTree;
+use crate::errors::TpnTreeError;
+
+impl<const N: usize> TpnTree<f64, N> {
+    /// Builds a tree from a dense grid of values, dividing every region whose variance exceeds `tolerance`.
+    ///
+    /// The values are given in row-major order, i.e. the index along the last axis changes fastest.
+    /// The tree covers the box from the origin to `shape` with the cell at index `i` centered at `i + 0.5` along every axis.
+    /// Every tree holds the mean of the cells it covers, trees covering no cells hold no data.
+    /// Errors with [`TpnTreeError::DimensionMismatch`] if there is not one value per cell.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::TpnTree;
+    /// let values = [0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0, 1.0];
+    ///
+    /// let tree = TpnTree::<f64, 3>::from_grid([2, 2, 2], &values, 0.0).expect("Couldn't build from grid.");
+    ///
+    /// assert_eq!(tree.child_count(), 8);
+    /// assert_eq!(tree.to_grid([2, 2, 2]), values.to_vec());
+    /// ```
+    pub fn from_grid(
+        shape: [usize; N],
+        values: &[f64],
+        tolerance: f64,
+    ) -> Result<Self, TpnTreeError> {
+        let count = shape.iter().product::<usize>();
+        if values.len() != count {
+            return Err(TpnTreeError::DimensionMismatch {
+                expected: count,
+                got: values.len(),
+            });
+        }
+
+        let mut tree = Self::from_bounds([0.0; N], shape.map(|size| size as f64))?;
+        tree.fill_from_grid(&shape, values, tolerance)?;
+        Ok(tree)
+    }
+
+    /// Samples the data of the leaves onto a dense grid with `resolution` cells along each axis covering the tree.
+    ///
+    /// Each cell takes the value of the leaf covering its center, cells of leaves without data are zero.
+    /// The values are returned in row-major order, see [`TpnTree::from_grid`].
+    pub fn to_grid(&self, resolution: [usize; N]) -> Vec<f64> {
+        let min = self.min_corner();
+        let max = self.max_corner();
+        let count = resolution.iter().product::<usize>();
+
+        (0..count)
+            .map(|cell| {
+                let index = grid_index(&resolution, cell);
+                let mut center = [0.0; N];
+                for axis in 0..N {
+                    let width = (max[axis] - min[axis]) / resolution[axis] as f64;
+                    center[axis] = min[axis] + (index[axis] as f64 + 0.5) * width;
+                }
+                self.find_at_level(&center, usize::MAX)
+                    .and_then(|leaf| leaf.data().copied())
+                    .unwrap_or(0.0)
+            })
+            .collect()
+    }
+
+    fn fill_from_grid(
+        &mut self,
+        shape: &[usize; N],
+        values: &[f64],
+        tolerance: f64,
+    ) -> Result<(), TpnTreeError> {
+        let ranges = self.cell_ranges();
+        let extent = ranges.clone().map(|range| range.len());
+        let count = extent.iter().product::<usize>();
+        if count == 0 {
+            return Ok(());
+        }
+
+        let cells = || {
+            (0..count).map(|cell| {
+                let local = grid_index(&extent, cell);
+                let offset = (0..N).fold(0, |offset, axis| {
+                    offset * shape[axis] + ranges[axis].start + local[axis]
+                });
+                values[offset]
+            })
+        };
+        let mean = cells().sum::<f64>() / count as f64;
+        let variance = cells().map(|value| (value - mean).powi(2)).sum::<f64>() / count as f64;
+        self.data = Some(mean);
+
+        if count > 1 && variance > tolerance {
+            self.divide()?;
+            for child in self.children.iter_mut() {
+                child.fill_from_grid(shape, values, tolerance)?;
+            }
+        }
+        Ok(())
+    }
+
+    // cells with their center inside the tree along each axis, half-open like insertion
+    fn cell_ranges(&self) -> [Range<usize>; N] {
+        let min = self.min_corner();
+        let max = self.max_corner();
+        core::array::from_fn(|axis| {
+            let start = (min[axis] - 0.5).ceil().max(0.0) as usize;
+            let end = (max[axis] - 0.5).ceil().max(0.0) as usize;
+            start..end.max(start)
+        })
+    }
+}
+
+// the multi-dimensional index of the cell at the row-major offset
+fn grid_index<const N: usize>(shape: &[usize; N], mut offset: usize) -> [usize; N] {
+    let mut index = [0; N];
+    for axis in (0..N).rev() {
+        index[axis] = offset % shape[axis];
+        offset /= shape[axis];
+    }
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tpntree::TpnTree;
+    use crate::TpnTreeError;
+
+    #[test]
+    fn grid_round_trips_without_tolerance() {
+        let shape = [3, 4, 2];
+        let values = (0..24).map(|i| (i * 7 % 11) as f64).collect::<Vec<_>>();
+
+        let tree = TpnTree::from_grid(shape, &values, 0.0).unwrap();
+
+        assert_eq!(tree.to_grid(shape), values);
+    }
+
+    #[test]
+    fn grid_is_compressed_within_tolerance() {
+        let tree = TpnTree::from_grid([2, 2], &[1.0, 1.1, 0.9, 1.0], 0.1).unwrap();
+
+        assert!(tree.is_leaf());
+        assert_eq!(tree.to_grid([4, 1]), vec![1.0; 4]);
+    }
+
+    #[test]
+    fn grid_requires_all_values() {
+        assert_eq!(
+            TpnTree::<f64, 2>::from_grid([2, 2], &[0.0; 3], 0.0),
+            Err(TpnTreeError::DimensionMismatch {
+                expected: 4,
+                got: 3
+            })
+        );
+    }
+}
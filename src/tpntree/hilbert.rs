@@ -0,0 +1,193 @@
+use alloc::vec::Vec;
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use num_traits::Float;
+
+use super::TpnTree;
+
+impl<T, const N: usize> TpnTree<T, N> {
+    /// Returns all leaves together with their index along the Hilbert curve, ordered by it.
+    ///
+    /// Indices are those of the cells of a tree divided as deep as its deepest leaf, larger leaves take the index of one of the cells they cover.
+    /// Consecutive cells along the Hilbert curve share a face, which gives good locality e.g. for bulk export or database storage of leaves.
+    ///
+    /// Panics if N times the depth of the deepest leaf below the tree exceeds 128 bits.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::TpnTree;
+    /// let mut root = TpnTree::<(), 2>::root(1.0);
+    /// root.divide().expect("Couldn't divide.");
+    ///
+    /// let indices = root
+    ///     .leaf_hilbert_indices()
+    ///     .into_iter()
+    ///     .map(|(index, leaf)| (index, leaf.coordinates()))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(
+    ///     indices,
+    ///     vec![
+    ///         (0, [-0.5, -0.5]),
+    ///         (1, [-0.5, 0.5]),
+    ///         (2, [0.5, 0.5]),
+    ///         (3, [0.5, -0.5])
+    ///     ]
+    /// );
+    /// ```
+    pub fn leaf_hilbert_indices(&self) -> Vec<(u128, &Self)> {
+        let leaves = self
+            .iter_depth_first()
+            .filter(|tree| tree.is_leaf())
+            .collect::<Vec<_>>();
+        let depth = leaves
+            .iter()
+            .map(|leaf| leaf.level - self.level)
+            .max()
+            .unwrap_or(0);
+        assert!(
+            N * depth <= 128,
+            "Expected at most 128 bits for Hilbert indices, got {}.",
+            N * depth
+        );
+
+        let min_corner = self.min_corner();
+        let mut indexed = leaves
+            .into_iter()
+            .map(|leaf| {
+                let leaf_min_corner = leaf.min_corner();
+                let mut cell = [0_u64; N];
+                for i in 0..N {
+                    // the cell of the leaf at its own level, moved to the deepest level
+                    let position = (leaf_min_corner[i] - min_corner[i]) / (2.0 * leaf.span[i]);
+                    cell[i] = (position.round() as u64) << (depth - (leaf.level - self.level));
+                }
+                (hilbert_index(cell, depth), leaf)
+            })
+            .collect::<Vec<_>>();
+        indexed.sort_by_key(|&(index, _)| index);
+        indexed
+    }
+
+    /// Iterates the leaves in the order of the Hilbert curve, see [`TpnTree::leaf_hilbert_indices`].
+    pub fn iter_hilbert_order(&self) -> impl Iterator<Item = &Self> {
+        self.leaf_hilbert_indices()
+            .into_iter()
+            .map(|(_, leaf)| leaf)
+    }
+}
+
+// Skilling's transform of cell coordinates with the given bits per axis into the transposed Hilbert index,
+// which is then interleaved into a single number, most significant bits first
+fn hilbert_index<const N: usize>(mut cell: [u64; N], bits: usize) -> u128 {
+    if bits == 0 || N == 0 {
+        return 0;
+    }
+
+    let mut q = 1_u64 << (bits - 1);
+    while q > 1 {
+        let p = q - 1;
+        for i in 0..N {
+            if cell[i] & q != 0 {
+                cell[0] ^= p;
+            } else {
+                let t = (cell[0] ^ cell[i]) & p;
+                cell[0] ^= t;
+                cell[i] ^= t;
+            }
+        }
+        q >>= 1;
+    }
+
+    for i in 1..N {
+        cell[i] ^= cell[i - 1];
+    }
+    let mut t = 0;
+    let mut q = 1_u64 << (bits - 1);
+    while q > 1 {
+        if cell[N - 1] & q != 0 {
+            t ^= q - 1;
+        }
+        q >>= 1;
+    }
+    for coordinate in cell.iter_mut() {
+        *coordinate ^= t;
+    }
+
+    let mut index = 0_u128;
+    for bit in (0..bits).rev() {
+        for coordinate in cell {
+            index = (index << 1) | u128::from((coordinate >> bit) & 1);
+        }
+    }
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tpntree::TpnTree;
+
+    fn assert_face_adjacent_order<const N: usize>(tree: &TpnTree<(), N>) {
+        let leaves = tree.iter_hilbert_order().collect::<Vec<_>>();
+        let side = 2.0 * leaves[0].span()[0];
+
+        for pair in leaves.windows(2) {
+            let distance = pair[0]
+                .coordinates()
+                .iter()
+                .zip(pair[1].coordinates())
+                .map(|(a, b)| (a - b).abs())
+                .sum::<f64>();
+            assert_eq!(distance, side);
+        }
+    }
+
+    #[test]
+    fn hilbert_order_steps_to_neighbors() {
+        let mut square = TpnTree::<(), 2>::root(1.0);
+        square.divide_to_depth(4).unwrap();
+        assert_face_adjacent_order(&square);
+
+        let mut cube = TpnTree::<(), 3>::root(1.0);
+        cube.divide_to_depth(3).unwrap();
+        assert_face_adjacent_order(&cube);
+
+        let indices = cube
+            .leaf_hilbert_indices()
+            .into_iter()
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+        assert_eq!(indices, (0..512).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn hilbert_order_keeps_large_leaves_contiguous() {
+        let mut uniform = TpnTree::<(), 2>::root(1.0);
+        uniform.divide_to_depth(2).unwrap();
+        let mut adaptive = TpnTree::<(), 2>::root(1.0);
+        adaptive.divide().unwrap();
+        adaptive.get_child_mut(1).unwrap().divide().unwrap();
+
+        // the order of the uniform leaves within each large leaf, mapped to the large leaf
+        let mut expected = uniform
+            .iter_hilbert_order()
+            .map(|leaf| {
+                let coordinates = leaf.coordinates();
+                let index = adaptive.child_index_for_point(&coordinates);
+                if index == 1 {
+                    coordinates
+                } else {
+                    adaptive.get_child(index).unwrap().coordinates()
+                }
+            })
+            .collect::<Vec<_>>();
+        expected.dedup();
+
+        assert_eq!(
+            adaptive
+                .iter_hilbert_order()
+                .map(|leaf| leaf.coordinates())
+                .collect::<Vec<_>>(),
+            expected
+        );
+    }
+}
@@ -0,0 +1,154 @@
+use alloc::{vec, vec::Vec};
+use core::ops::Range;
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use num_traits::Float;
+
+use super::TpnTree;
+use crate::errors::TpnTreeError;
+
+impl TpnTree<f64, 2> {
+    /// Builds a quadtree from a grayscale image, dividing every region whose pixel variance exceeds `tolerance`.
+    ///
+    /// The pixels are given row by row, the tree covers the rectangle from (0/0) to (width/height) with a pixel of column x and row y centered at (x + 0.5/y + 0.5).
+    /// Every tree holds the mean of the pixels it covers, trees covering no pixels hold no data.
+    /// Errors with [`TpnTreeError::DimensionMismatch`] if there are not `width * height` pixels.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::TpnTree;
+    /// let pixels: [u8; 16] = [
+    ///     0, 0, 9, 9,
+    ///     0, 0, 9, 9,
+    ///     0, 0, 0, 0,
+    ///     0, 0, 0, 5,
+    /// ];
+    ///
+    /// let tree = TpnTree::from_image(4, 4, &pixels, 0.0).expect("Couldn't build from image.");
+    ///
+    /// // one quadrant is split into single pixels
+    /// assert_eq!(tree.iter_depth_first().filter(|tree| tree.is_leaf()).count(), 3 + 4);
+    /// assert_eq!(tree.to_image(), pixels.map(f64::from).to_vec());
+    /// ```
+    pub fn from_image<P: Into<f64> + Copy>(
+        width: usize,
+        height: usize,
+        pixels: &[P],
+        tolerance: f64,
+    ) -> Result<Self, TpnTreeError> {
+        if pixels.len() != width * height {
+            return Err(TpnTreeError::DimensionMismatch {
+                expected: width * height,
+                got: pixels.len(),
+            });
+        }
+        let pixels = pixels
+            .iter()
+            .map(|&pixel| pixel.into())
+            .collect::<Vec<f64>>();
+
+        let mut tree = Self::from_bounds([0.0; 2], [width as f64, height as f64])?;
+        tree.fill_from_image(width, &pixels, tolerance)?;
+        Ok(tree)
+    }
+
+    /// Reconstructs the image covered by the tree from the data of its leaves, see [`TpnTree::from_image`].
+    ///
+    /// Pixels are returned row by row, pixels of leaves without data are zero.
+    pub fn to_image(&self) -> Vec<f64> {
+        let [columns, rows] = self.pixel_ranges();
+        let width = columns.len();
+        let mut pixels = vec![0.0; width * rows.len()];
+
+        for leaf in self.iter_depth_first().filter(|tree| tree.is_leaf()) {
+            if let Some(&value) = leaf.data() {
+                let [leaf_columns, leaf_rows] = leaf.pixel_ranges();
+                for row in leaf_rows {
+                    let start = (row - rows.start) * width;
+                    pixels[start + leaf_columns.start - columns.start
+                        ..start + leaf_columns.end - columns.start]
+                        .fill(value);
+                }
+            }
+        }
+        pixels
+    }
+
+    fn fill_from_image(
+        &mut self,
+        width: usize,
+        pixels: &[f64],
+        tolerance: f64,
+    ) -> Result<(), TpnTreeError> {
+        let [columns, rows] = self.pixel_ranges();
+        let count = columns.len() * rows.len();
+        if count == 0 {
+            return Ok(());
+        }
+
+        let values = || {
+            rows.clone()
+                .flat_map(|row| pixels[row * width..][columns.clone()].iter())
+        };
+        let mean = values().sum::<f64>() / count as f64;
+        let variance = values().map(|value| (value - mean).powi(2)).sum::<f64>() / count as f64;
+        self.data = Some(mean);
+
+        if count > 1 && variance > tolerance {
+            self.divide()?;
+            for child in self.children.iter_mut() {
+                child.fill_from_image(width, pixels, tolerance)?;
+            }
+        }
+        Ok(())
+    }
+
+    // columns and rows with their pixel center inside the tree, half-open like insertion
+    fn pixel_ranges(&self) -> [Range<usize>; 2] {
+        let min = self.min_corner();
+        let max = self.max_corner();
+        [0, 1].map(|axis| {
+            let start = (min[axis] - 0.5).ceil().max(0.0) as usize;
+            let end = (max[axis] - 0.5).ceil().max(0.0) as usize;
+            start..end.max(start)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tpntree::TpnTree;
+    use crate::TpnTreeError;
+
+    #[test]
+    fn image_round_trips_without_tolerance() {
+        let (width, height) = (5, 3);
+        let pixels = (0..width * height)
+            .map(|i| (i * 7 % 11) as f64)
+            .collect::<Vec<_>>();
+
+        let tree = TpnTree::from_image(width, height, &pixels, 0.0).unwrap();
+
+        assert_eq!(tree.to_image(), pixels);
+    }
+
+    #[test]
+    fn image_is_compressed_within_tolerance() {
+        let pixels = [1.0, 1.1, 0.9, 1.0];
+
+        let tree = TpnTree::from_image(2, 2, &pixels, 0.1).unwrap();
+
+        assert!(tree.is_leaf());
+        assert_eq!(tree.to_image(), vec![1.0; 4]);
+    }
+
+    #[test]
+    fn image_requires_all_pixels() {
+        assert_eq!(
+            TpnTree::from_image(2, 2, &[0.0; 3], 0.0),
+            Err(TpnTreeError::DimensionMismatch {
+                expected: 4,
+                got: 3
+            })
+        );
+    }
+}
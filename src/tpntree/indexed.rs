@@ -0,0 +1,469 @@
+use alloc::vec::Vec;
+use core::iter::once;
+
+use super::TpnTree;
+use crate::{errors::TpnTreeError, Coordinates};
+
+/// Identifies an item stored in an [`IndexedTree`].
+///
+/// Ids stay valid while the tree divides, as only the ids move between the trees.
+/// The storage of removed items is reused by later insertions, but ids of removed items never refer to the later items.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ItemId {
+    index: usize,
+    // counts the removals from the slot at the index, telling apart its successive items
+    generation: usize,
+}
+
+/// A spatial tree storing its items in one central vector, with leaves holding ids of them.
+///
+/// Compared to a [`SpatialTree`](super::SpatialTree) the items never move once inserted, which keeps them close in memory and makes ids of them stable.
+/// The tree keeps track of the path to the leaf holding each id, so items are found, removed and relocated in O(depth).
+///
+/// ```
+/// # use tpntree::tpntree::{IndexedTree, TpnTree};
+/// let mut tree = IndexedTree::new(TpnTree::builder().leaf_capacity(1).build());
+///
+/// let first = tree.insert([0.5, 0.5]).expect("Couldn't insert.");
+/// let second = tree.insert([-0.5, -0.5]).expect("Couldn't insert.");
+///
+/// assert_eq!(tree.tree().child_count(), 4);
+/// assert_eq!(tree.get(first), Some(&[0.5, 0.5]));
+///
+/// tree.relocate(second, [0.5, -0.5]).expect("Couldn't relocate.");
+/// assert_eq!(tree.remove(first), Some([0.5, 0.5]));
+/// assert_eq!(tree.len(), 1);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexedTree<T, const N: usize> {
+    tree: TpnTree<Vec<ItemId>, N>,
+    // removed items leave a hole until the slot is reused
+    items: Vec<Option<T>>,
+    // child indices from the root to the leaf holding each id
+    paths: Vec<Vec<usize>>,
+    // generation of the current item of each slot
+    generations: Vec<usize>,
+    // slots without an item
+    free: Vec<usize>,
+    len: usize,
+}
+
+impl<T: Coordinates<N>, const N: usize> IndexedTree<T, N> {
+    /// Creates an empty indexed tree from a tree holding no ids yet, configured as needed.
+    ///
+    /// Any ids the tree holds already are dropped.
+    pub fn new(mut tree: TpnTree<Vec<ItemId>, N>) -> Self {
+        tree.visit_mut(&mut |tree: &mut TpnTree<Vec<ItemId>, N>| {
+            tree.data = None;
+            super::VisitControl::Continue
+        });
+        Self {
+            tree,
+            items: Vec::new(),
+            paths: Vec::new(),
+            generations: Vec::new(),
+            free: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /// Creates an empty indexed tree with equal span in all dimension at the center of the space.
+    pub fn root(span: f64) -> Self {
+        Self::new(TpnTree::root(span))
+    }
+
+    /// Returns the tree holding the ids.
+    pub fn tree(&self) -> &TpnTree<Vec<ItemId>, N> {
+        &self.tree
+    }
+
+    /// Iterates all items with their ids in ascending order of the ids.
+    pub fn iter(&self) -> impl Iterator<Item = (ItemId, &T)> {
+        self.items
+            .iter()
+            .zip(&self.generations)
+            .enumerate()
+            .filter_map(|(index, (item, &generation))| {
+                item.as_ref()
+                    .map(|item| (ItemId { index, generation }, item))
+            })
+    }
+
+    /// Returns the item with the id.
+    pub fn get(&self, id: ItemId) -> Option<&T> {
+        self.items[self.slot(id)?].as_ref()
+    }
+
+    /// Returns the item with the id by mutable reference.
+    ///
+    /// Changing the coordinates of the item does not move its id in the tree, use [`IndexedTree::relocate`] for that.
+    pub fn get_mut(&mut self, id: ItemId) -> Option<&mut T> {
+        let slot = self.slot(id)?;
+        self.items[slot].as_mut()
+    }
+
+    /// Returns the path of child indices from the root to the leaf holding the id.
+    pub fn path(&self, id: ItemId) -> Option<&[usize]> {
+        self.get(id)?;
+        Some(&self.paths[id.index])
+    }
+
+    /// Returns the count of items.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether there are no items.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Inserts the item and returns its id, dividing leaves given the constraints of the `division_condition`.
+    ///
+    /// Behaves like [`SpatialTree::insert_by_coordinates`](super::SpatialTree::insert_by_coordinates) and errors in the same cases.
+    pub fn insert_by_coordinates(
+        &mut self,
+        data: T,
+        division_condition: &dyn Fn(&TpnTree<Vec<ItemId>, N>) -> bool,
+    ) -> Result<ItemId, TpnTreeError> {
+        self.check_spans(&data)?;
+
+        let id = self.vacant_id();
+        self.items[id.index] = Some(data);
+        if let Err(error) = self.insert_id(id, division_condition) {
+            self.items[id.index] = None;
+            self.free.push(id.index);
+            return Err(error);
+        }
+        self.len += 1;
+        Ok(id)
+    }
+
+    /// Inserts the item and returns its id, dividing leaves according to the [`TreeConfig`](super::TreeConfig) of the tree.
+    ///
+    /// Behaves like [`SpatialTree::insert`](super::SpatialTree::insert).
+    pub fn insert(&mut self, data: T) -> Result<ItemId, TpnTreeError> {
+        self.insert_by_coordinates(data, &|tree| tree.is_at_capacity())
+    }
+
+    /// Removes the item with the id from the tree and returns it.
+    ///
+    /// Leaves are not merged when they become empty.
+    /// The id no longer refers to any item afterwards, even once its storage is reused.
+    pub fn remove(&mut self, id: ItemId) -> Option<T> {
+        let slot = self.slot(id)?;
+        let item = self.items[slot].take()?;
+        self.detach(id);
+        self.paths[slot] = Vec::new();
+        self.generations[slot] = self.generations[slot].wrapping_add(1);
+        self.free.push(slot);
+        self.len -= 1;
+        Some(item)
+    }
+
+    /// Replaces the item with the id by the data and moves the id to the leaf containing the new coordinates.
+    ///
+    /// Dividing leaves according to the [`TreeConfig`](super::TreeConfig) of the tree, like [`IndexedTree::insert`].
+    /// Returns the replaced item.
+    /// Errors with [`TpnTreeError::NoSuchItem`] if there is no item with the id or like [`IndexedTree::insert`] otherwise.
+    /// The item is left untouched on error.
+    pub fn relocate(&mut self, id: ItemId, data: T) -> Result<T, TpnTreeError> {
+        if self.get(id).is_none() {
+            return Err(TpnTreeError::NoSuchItem);
+        }
+        self.check_spans(&data)?;
+
+        self.detach(id);
+        let previous = self.items[id.index].replace(data);
+        if let Err(error) = self.insert_id(id, &|tree| tree.is_at_capacity()) {
+            // inserting without dividing can not fail
+            self.items[id.index] = previous;
+            let _ = self.insert_id(id, &|_| false);
+            return Err(error);
+        }
+        Ok(previous.expect("Checked to exist."))
+    }
+
+    /// Returns the ids and items of the leaf containing the point.
+    ///
+    /// Errors if the tree does not span the point.
+    pub fn leaf_items(&self, point: &[f64; N]) -> Result<Vec<(ItemId, &T)>, TpnTreeError> {
+        if !self.tree.spans_coordinates(point) {
+            return Err(self.tree.does_not_span(point));
+        }
+
+        Ok(self
+            .tree
+            .leaf_at(point)
+            .data
+            .iter()
+            .flatten()
+            .filter_map(|&id| self.get(id).map(|item| (id, item)))
+            .collect())
+    }
+
+    // the slot of the id, unless its item was removed
+    fn slot(&self, id: ItemId) -> Option<usize> {
+        (self.generations.get(id.index) == Some(&id.generation)).then_some(id.index)
+    }
+
+    // the id for the next item, reusing a free slot if there is one
+    fn vacant_id(&mut self) -> ItemId {
+        let index = self.free.pop().unwrap_or_else(|| {
+            self.items.push(None);
+            self.paths.push(Vec::new());
+            self.generations.push(0);
+            self.items.len() - 1
+        });
+        ItemId {
+            index,
+            generation: self.generations[index],
+        }
+    }
+
+    fn check_spans(&self, data: &T) -> Result<(), TpnTreeError> {
+        let coordinates = data.try_coordinates()?;
+        if self.tree.spans_coordinates(coordinates) {
+            Ok(())
+        } else {
+            Err(self.tree.does_not_span(coordinates))
+        }
+    }
+
+    fn insert_id(
+        &mut self,
+        id: ItemId,
+        division_condition: &dyn Fn(&TpnTree<Vec<ItemId>, N>) -> bool,
+    ) -> Result<(), TpnTreeError> {
+        let mut locator = Locator {
+            items: &self.items,
+            paths: &mut self.paths,
+            path: Vec::new(),
+            division_condition,
+        };
+        locator.insert(&mut self.tree, id)
+    }
+
+    // removes the id from the leaf on its path
+    fn detach(&mut self, id: ItemId) {
+        let mut tree = &mut self.tree;
+        for &index in &self.paths[id.index] {
+            tree = &mut tree.children[index];
+        }
+        if let Some(ids) = tree.data.as_mut() {
+            ids.retain(|&other| other != id);
+            if ids.is_empty() {
+                tree.data = None;
+            }
+        }
+    }
+}
+
+// mirrors the insertion into a spatial tree, looking up the coordinates of the ids in the items and recording their paths
+struct Locator<'a, T, const N: usize> {
+    items: &'a [Option<T>],
+    paths: &'a mut [Vec<usize>],
+    path: Vec<usize>,
+    division_condition: &'a dyn Fn(&TpnTree<Vec<ItemId>, N>) -> bool,
+}
+
+impl<T: Coordinates<N>, const N: usize> Locator<'_, T, N> {
+    fn insert(
+        &mut self,
+        tree: &mut TpnTree<Vec<ItemId>, N>,
+        id: ItemId,
+    ) -> Result<(), TpnTreeError> {
+        if tree.is_leaf() {
+            if (self.division_condition)(tree) {
+                tree.divide()?;
+
+                for id in tree
+                    .data
+                    .take()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .chain(once(id))
+                {
+                    self.insert_into_child(tree, id)?;
+                }
+            } else {
+                tree.data.get_or_insert(Vec::new()).push(id);
+                self.paths[id.index].clone_from(&self.path);
+            }
+            Ok(())
+        } else {
+            self.insert_into_child(tree, id)
+        }
+    }
+
+    fn insert_into_child(
+        &mut self,
+        tree: &mut TpnTree<Vec<ItemId>, N>,
+        id: ItemId,
+    ) -> Result<(), TpnTreeError> {
+        let coordinates = self.items[id.index]
+            .as_ref()
+            .map(|item| item.coordinates())
+            .expect("Ids in the tree refer to items.");
+        let index = tree.child_index_for(coordinates);
+
+        self.path.push(index);
+        let result = self.insert(&mut tree.children[index], id);
+        self.path.pop();
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tpntree::{IndexedTree, TpnTree, Tree3D, TreeConfig};
+    use crate::TpnTreeError;
+
+    fn points() -> Vec<[f64; 3]> {
+        (0..50)
+            .map(|i| {
+                let t = i as f64 * 0.43;
+                [t.cos(), t.sin(), (t * 0.2).cos()]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn ids_survive_divisions() {
+        let mut indexed =
+            IndexedTree::new(TpnTree::builder().leaf_capacity(2).max_depth(5).build());
+        let mut spatial = Tree3D::builder().leaf_capacity(2).max_depth(5).build();
+
+        let ids = points()
+            .into_iter()
+            .map(|point| indexed.insert(point).unwrap())
+            .collect::<Vec<_>>();
+        spatial.extend(points());
+
+        assert!(indexed.tree().structurally_equal(&spatial));
+        for (id, point) in ids.iter().zip(&points()) {
+            assert_eq!(indexed.get(*id), Some(point));
+            assert!(indexed
+                .leaf_items(point)
+                .unwrap()
+                .iter()
+                .any(|(found, _)| found == id));
+        }
+    }
+
+    #[test]
+    fn paths_lead_to_leaves_holding_ids() {
+        let mut tree = IndexedTree::new(TpnTree::builder().leaf_capacity(2).max_depth(5).build());
+        let ids = points()
+            .into_iter()
+            .map(|point| tree.insert(point).unwrap())
+            .collect::<Vec<_>>();
+
+        for id in ids {
+            let mut leaf = tree.tree();
+            for &index in tree.path(id).unwrap() {
+                leaf = leaf.get_child(index).unwrap();
+            }
+            assert!(leaf.is_leaf());
+            assert!(leaf.data().unwrap().contains(&id));
+        }
+    }
+
+    #[test]
+    fn remove_and_relocate() {
+        let mut tree = IndexedTree::new(TpnTree::builder().leaf_capacity(1).build());
+
+        let a = tree.insert([0.5, 0.5]).unwrap();
+        let b = tree.insert([-0.5, -0.5]).unwrap();
+
+        assert_eq!(tree.relocate(b, [0.6, -0.6]), Ok([-0.5, -0.5]));
+        assert_eq!(tree.leaf_items(&[-0.5, -0.5]).unwrap(), vec![]);
+        assert_eq!(
+            tree.leaf_items(&[0.6, -0.6]).unwrap(),
+            vec![(b, &[0.6, -0.6])]
+        );
+        assert!(tree.relocate(b, [2.0, 0.0]).is_err());
+        assert_eq!(tree.get(b), Some(&[0.6, -0.6]));
+
+        assert_eq!(tree.remove(a), Some([0.5, 0.5]));
+        assert_eq!(tree.remove(a), None);
+        assert_eq!(tree.relocate(a, [0.0, 0.0]), Err(TpnTreeError::NoSuchItem));
+        assert_eq!(tree.iter().collect::<Vec<_>>(), vec![(b, &[0.6, -0.6])]);
+        assert_eq!(tree.len(), 1);
+    }
+
+    #[test]
+    fn churn_reuses_storage() {
+        let mut tree = IndexedTree::new(TpnTree::builder().leaf_capacity(2).max_depth(5).build());
+        let mut ids = points()
+            .into_iter()
+            .map(|point| tree.insert(point).unwrap())
+            .collect::<Vec<_>>();
+
+        for round in 0..20 {
+            for (id, point) in ids.iter_mut().zip(points()).skip(round % 2).step_by(2) {
+                assert_eq!(tree.remove(*id), Some(point));
+                *id = tree.insert(point).unwrap();
+            }
+        }
+
+        assert_eq!(tree.len(), points().len());
+        assert_eq!(tree.items.len(), points().len());
+        assert_eq!(tree.paths.len(), points().len());
+        for (id, point) in ids.iter().zip(&points()) {
+            assert_eq!(tree.get(*id), Some(point));
+        }
+    }
+
+    #[test]
+    fn ids_of_removed_items_stay_invalid() {
+        let mut tree = IndexedTree::new(TpnTree::builder().leaf_capacity(1).build());
+
+        let a = tree.insert([0.5, 0.5]).unwrap();
+        assert_eq!(tree.remove(a), Some([0.5, 0.5]));
+        assert_eq!(tree.path(a), None);
+
+        let b = tree.insert([-0.5, -0.5]).unwrap();
+        assert_ne!(a, b);
+        assert_eq!(tree.get(a), None);
+        assert_eq!(tree.get_mut(a), None);
+        assert_eq!(tree.path(a), None);
+        assert_eq!(tree.remove(a), None);
+        assert_eq!(tree.relocate(a, [0.0, 0.0]), Err(TpnTreeError::NoSuchItem));
+        assert_eq!(tree.iter().collect::<Vec<_>>(), vec![(b, &[-0.5, -0.5])]);
+        assert_eq!(
+            tree.leaf_items(&[-0.5, -0.5]).unwrap(),
+            vec![(b, &[-0.5, -0.5])]
+        );
+    }
+
+    #[test]
+    fn coincident_items_share_a_leaf() {
+        let mut tree = IndexedTree::new(TpnTree::builder().leaf_capacity(1).build());
+
+        let a = tree.insert([0.3, 0.3]).unwrap();
+        let b = tree.insert([0.3, 0.3]).unwrap();
+
+        assert_eq!(tree.path(b).unwrap().len(), TreeConfig::DEFAULT_MAX_DEPTH);
+        assert_eq!(
+            tree.leaf_items(&[0.3, 0.3]).unwrap(),
+            vec![(a, &[0.3, 0.3]), (b, &[0.3, 0.3])]
+        );
+    }
+
+    #[test]
+    fn failed_insertions_store_nothing() {
+        let mut tree = IndexedTree::<Vec<f64>, 2>::root(1.0);
+
+        assert!(matches!(
+            tree.insert(vec![2.0, 0.0]),
+            Err(TpnTreeError::DoesNotSpan { .. })
+        ));
+        assert!(matches!(
+            tree.insert(vec![0.0]),
+            Err(TpnTreeError::DimensionMismatch { .. })
+        ));
+        assert!(tree.is_empty());
+        assert!(tree.leaf_items(&[0.0, 0.0]).unwrap().is_empty());
+    }
+}
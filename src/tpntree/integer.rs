@@ -0,0 +1,306 @@
+use alloc::vec::Vec;
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use num_traits::Float;
+
+use crate::errors::TpnTreeError;
+
+/// Maps the integer cells of an [`IntTpnTree`] to world space and back.
+///
+/// A cell `c` covers the world space box from `origin + c * scale` to `origin + (c + 1) * scale`.
+///
+/// ```
+/// # use tpntree::tpntree::GridTransform;
+/// let transform = GridTransform::new([10.0, 0.0], 0.5);
+///
+/// assert_eq!(transform.to_world(&[2, -1]), [11.0, -0.5]);
+/// assert_eq!(transform.to_cell(&[11.2, -0.1]), [2, -1]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GridTransform<const N: usize> {
+    /// World space position of the min corner of cell zero.
+    pub origin: [f64; N],
+    /// World space edge length of a cell.
+    pub scale: f64,
+}
+
+impl<const N: usize> GridTransform<N> {
+    /// Creates a new transform.
+    pub fn new(origin: [f64; N], scale: f64) -> Self {
+        Self { origin, scale }
+    }
+
+    /// Returns the world space position of the min corner of the cell.
+    pub fn to_world(&self, cell: &[i64; N]) -> [f64; N] {
+        core::array::from_fn(|axis| self.origin[axis] + cell[axis] as f64 * self.scale)
+    }
+
+    /// Returns the cell covering the world space point, cells are half-open.
+    pub fn to_cell(&self, point: &[f64; N]) -> [i64; N] {
+        core::array::from_fn(|axis| ((point[axis] - self.origin[axis]) / self.scale).floor() as i64)
+    }
+}
+
+impl<const N: usize> Default for GridTransform<N> {
+    fn default() -> Self {
+        Self::new([0.0; N], 1.0)
+    }
+}
+
+/// A tree over integer cells where every tree covers a cube with an edge length of a power of two.
+///
+/// Division halves the edge length exactly, so children always tile their parent and the unit cells at the bottom have no floating-point drift.
+/// Trees are half-open, covering the cells from their min corner up to but excluding their max corner.
+/// Children are ordered like the ones of a [`TpnTree`](super::TpnTree), bit `i` of the index is set if the child lies below the center along axis `i`.
+/// Use a [`GridTransform`] to relate cells to world space.
+///
+/// ```
+/// # use tpntree::tpntree::IntTpnTree;
+/// let mut tree = IntTpnTree::<u8, 3>::root(4);
+///
+/// *tree.divide_to_cell(&[3, -2, 0]).expect("Couldn't divide.").data_mut() = Some(1);
+///
+/// let voxel = tree.find_by_cell(&[3, -2, 0]).expect("Spans the cell.");
+/// assert_eq!((voxel.min(), voxel.size()), ([3, -2, 0], 1));
+/// assert_eq!(voxel.data(), Some(&1));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct IntTpnTree<T, const N: usize> {
+    min: [i64; N],
+    size_log2: u32,
+    level: usize,
+    children: Vec<Self>,
+    data: Option<T>,
+}
+
+impl<T, const N: usize> IntTpnTree<T, N> {
+    /// Creates a new tree covering the cube at the min corner with an edge length of `2^size_log2` cells.
+    ///
+    /// Panics if the edge length does not fit an `i64`.
+    pub fn new(min: [i64; N], size_log2: u32, level: usize) -> Self {
+        assert!(
+            size_log2 < i64::BITS - 1,
+            "Expected a size exponent below {}, got {}.",
+            i64::BITS - 1,
+            size_log2
+        );
+        Self {
+            min,
+            size_log2,
+            level,
+            children: Vec::new(),
+            data: None,
+        }
+    }
+
+    /// Creates a new tree with an edge length of `2^size_log2` cells centered at the origin, or with cell zero as min corner for a single cell.
+    pub fn root(size_log2: u32) -> Self {
+        let half = (1_i64 << size_log2) / 2;
+        Self::new([-half; N], size_log2, 0)
+    }
+
+    /// Returns the min corner, the first cell covered by the tree.
+    pub fn min(&self) -> [i64; N] {
+        self.min
+    }
+
+    /// Returns the max corner, the first cell beyond the tree along every axis.
+    pub fn max(&self) -> [i64; N] {
+        self.min.map(|min| min + self.size())
+    }
+
+    /// Returns the edge length of the tree in cells.
+    pub fn size(&self) -> i64 {
+        1 << self.size_log2
+    }
+
+    /// Returns the binary logarithm of the edge length of the tree.
+    pub fn size_log2(&self) -> u32 {
+        self.size_log2
+    }
+
+    /// Returns the level of the tree.
+    pub fn level(&self) -> usize {
+        self.level
+    }
+
+    /// Get a reference to the data the tree holds.
+    pub fn data(&self) -> Option<&T> {
+        self.data.as_ref()
+    }
+
+    /// Get a mutable reference to the data the tree holds.
+    pub fn data_mut(&mut self) -> &mut Option<T> {
+        &mut self.data
+    }
+
+    /// Checks if the tree has no children.
+    pub fn is_leaf(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    /// Returns the direct children, or an empty slice for a leaf.
+    pub fn children(&self) -> &[Self] {
+        &self.children
+    }
+
+    /// Get a reference to the child at the index if it exists.
+    pub fn get_child(&self, index: usize) -> Option<&Self> {
+        self.children.get(index)
+    }
+
+    /// Get a mutable reference to the child at the index if it exists.
+    pub fn get_child_mut(&mut self, index: usize) -> Option<&mut Self> {
+        self.children.get_mut(index)
+    }
+
+    /// Checks if the tree covers the cell.
+    pub fn contains(&self, cell: &[i64; N]) -> bool {
+        (0..N).all(|axis| self.min[axis] <= cell[axis] && cell[axis] < self.min[axis] + self.size())
+    }
+
+    /// Returns the world space min and max corner of the tree.
+    pub fn world_bounds(&self, transform: &GridTransform<N>) -> ([f64; N], [f64; N]) {
+        (
+            transform.to_world(&self.min),
+            transform.to_world(&self.max()),
+        )
+    }
+
+    /// Divides the tree into 2^N children of half the edge length.
+    ///
+    /// Errors with [`TpnTreeError::CanNotDivide`] if the tree has been divided before,
+    /// or with [`TpnTreeError::MaxDepthExceeded`] if it is a single cell.
+    pub fn divide(&mut self) -> Result<(), TpnTreeError> {
+        if !self.is_leaf() {
+            return Err(TpnTreeError::CanNotDivide);
+        }
+        if self.size_log2 == 0 {
+            return Err(TpnTreeError::MaxDepthExceeded {
+                max_level: self.level,
+            });
+        }
+
+        let half = self.size() / 2;
+        self.children = (0..1 << N)
+            .map(|index: usize| {
+                let min = core::array::from_fn(|axis| {
+                    if index & 1 << axis == 0 {
+                        self.min[axis] + half
+                    } else {
+                        self.min[axis]
+                    }
+                });
+                Self::new(min, self.size_log2 - 1, self.level + 1)
+            })
+            .collect();
+        Ok(())
+    }
+
+    /// Returns the index of the child covering the cell, whether the tree is divided or not.
+    pub fn child_index_for_cell(&self, cell: &[i64; N]) -> usize {
+        let half = self.size() / 2;
+        (0..N).fold(0, |index, axis| {
+            index | ((cell[axis] < self.min[axis] + half) as usize) << axis
+        })
+    }
+
+    /// Returns the deepest tree covering the cell.
+    ///
+    /// Errors if the tree does not cover the cell.
+    pub fn find_by_cell(&self, cell: &[i64; N]) -> Result<&Self, TpnTreeError> {
+        self.check_contains(cell)?;
+        let mut tree = self;
+        while let Some(child) = tree.get_child(tree.child_index_for_cell(cell)) {
+            tree = child;
+        }
+        Ok(tree)
+    }
+
+    /// Returns the deepest tree covering the cell by mutable reference.
+    ///
+    /// Errors if the tree does not cover the cell.
+    pub fn find_by_cell_mut(&mut self, cell: &[i64; N]) -> Result<&mut Self, TpnTreeError> {
+        self.check_contains(cell)?;
+        let mut tree = self;
+        while !tree.is_leaf() {
+            let index = tree.child_index_for_cell(cell);
+            tree = &mut tree.children[index];
+        }
+        Ok(tree)
+    }
+
+    /// Divides the leaves covering the cell down to the single cell and returns it.
+    ///
+    /// Errors if the tree does not cover the cell.
+    pub fn divide_to_cell(&mut self, cell: &[i64; N]) -> Result<&mut Self, TpnTreeError> {
+        let mut tree = self.find_by_cell_mut(cell)?;
+        while tree.size_log2 > 0 {
+            tree.divide()?;
+            let index = tree.child_index_for_cell(cell);
+            tree = &mut tree.children[index];
+        }
+        Ok(tree)
+    }
+
+    fn check_contains(&self, cell: &[i64; N]) -> Result<(), TpnTreeError> {
+        if self.contains(cell) {
+            Ok(())
+        } else {
+            Err(TpnTreeError::DoesNotSpan {
+                point: cell.iter().map(|&coordinate| coordinate as f64).collect(),
+                bounds: (
+                    self.min.iter().map(|&min| min as f64).collect(),
+                    self.max().iter().map(|&max| max as f64).collect(),
+                ),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tpntree::{GridTransform, IntTpnTree};
+    use crate::TpnTreeError;
+
+    #[test]
+    fn children_tile_parent_exactly() {
+        let mut tree = IntTpnTree::<(), 2>::new([-5, 7], 3, 0);
+        tree.divide().unwrap();
+
+        for (index, child) in tree.children().iter().enumerate() {
+            assert_eq!(child.size(), 4);
+            assert_eq!(tree.child_index_for_cell(&child.min()), index);
+            assert_eq!(
+                tree.child_index_for_cell(&child.max().map(|max| max - 1)),
+                index
+            );
+        }
+        assert_eq!(tree.get_child(0).unwrap().min(), [-1, 11]);
+        assert_eq!(tree.get_child(3).unwrap().min(), [-5, 7]);
+        assert_eq!(tree.divide(), Err(TpnTreeError::CanNotDivide));
+    }
+
+    #[test]
+    fn single_cells_do_not_divide() {
+        let mut tree = IntTpnTree::<(), 3>::root(2);
+        let cell = tree.divide_to_cell(&[-2, 1, 0]).unwrap();
+
+        assert_eq!(cell.level(), 2);
+        assert_eq!(
+            cell.divide(),
+            Err(TpnTreeError::MaxDepthExceeded { max_level: 2 })
+        );
+        assert!(tree.find_by_cell(&[2, 0, 0]).is_err());
+    }
+
+    #[test]
+    fn world_bounds_follow_transform() {
+        let tree = IntTpnTree::<(), 2>::root(4);
+        let transform = GridTransform::new([1.0, -1.0], 0.25);
+
+        assert_eq!(tree.world_bounds(&transform), ([-1.0, -3.0], [3.0, 1.0]));
+        assert!(tree.contains(&transform.to_cell(&[2.99, 0.99])));
+        assert!(!tree.contains(&transform.to_cell(&[3.0, 0.0])));
+    }
+}
@@ -0,0 +1,209 @@
+use alloc::vec::Vec;
+use core::fmt::{self, Display};
+
+use super::{NodePath, SpatialTree, TpnTree};
+use crate::Coordinates;
+
+/// A broken invariant found by [`TpnTree::check_invariants`], locating the offending tree by its path from the checked tree.
+#[derive(Debug, Clone, PartialEq)]
+pub enum InvariantViolation {
+    /// A tree has other than zero or b^k children for the k axes it is split along into b parts each.
+    ChildCount { path: NodePath, count: usize },
+    /// A child is not one level below its parent.
+    Level {
+        path: NodePath,
+        expected: usize,
+        got: usize,
+    },
+    /// A child does not cover its part of the parent, i.e. its center is off or its span is not half of the parent span.
+    ChildRegion { path: NodePath },
+    /// An item of a spatial tree lies outside of the tree holding it.
+    ItemOutside { path: NodePath, item: usize },
+}
+
+impl Display for InvariantViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InvariantViolation::ChildCount { path, count } => {
+                write!(f, "The tree at {:?} has {} children.", path, count)
+            }
+            InvariantViolation::Level {
+                path,
+                expected,
+                got,
+            } => write!(
+                f,
+                "The tree at {:?} is at level {}, expected {}.",
+                path, got, expected
+            ),
+            InvariantViolation::ChildRegion { path } => {
+                write!(
+                    f,
+                    "The tree at {:?} does not cover its part of the parent.",
+                    path
+                )
+            }
+            InvariantViolation::ItemOutside { path, item } => write!(
+                f,
+                "The item {} of the tree at {:?} lies outside of the tree.",
+                item, path
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InvariantViolation {}
+
+impl<T, const N: usize> TpnTree<T, N> {
+    /// Verifies the structure of the tree, e.g. after a sequence of mutations in a property based test.
+    ///
+    /// Every tree has zero or 2^N children, each one level below it and covering its part of the parent up to rounding.
+    /// Returns the first violation in depth first order.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::TpnTree;
+    /// let mut root = TpnTree::<(), 2>::root(1.0);
+    /// root.divide().expect("Couldn't divide.");
+    /// assert_eq!(root.check_invariants(), Ok(()));
+    ///
+    /// let child = root.take_child(3).unwrap();
+    /// root.attach_child(3, child).expect("Same region.");
+    /// assert_eq!(root.check_invariants(), Ok(()));
+    /// ```
+    pub fn check_invariants(&self) -> Result<(), InvariantViolation> {
+        self.check_recursive(&mut Vec::new(), &mut |_, _| Ok(()))
+    }
+
+    fn check_recursive(
+        &self,
+        path: &mut NodePath,
+        check_data: &mut impl FnMut(&Self, &NodePath) -> Result<(), InvariantViolation>,
+    ) -> Result<(), InvariantViolation> {
+        check_data(self, path)?;
+
+        if !self.children.is_empty() && self.children.len() != self.split_child_count() {
+            return Err(InvariantViolation::ChildCount {
+                path: path.clone(),
+                count: self.children.len(),
+            });
+        }
+
+        for (index, child) in self.children.iter().enumerate() {
+            path.push(index);
+            if child.level != self.level + 1 {
+                return Err(InvariantViolation::Level {
+                    path: path.clone(),
+                    expected: self.level + 1,
+                    got: child.level,
+                });
+            }
+
+            let (coordinates, span) = self.split_child_region(index);
+            let matches = (0..N).all(|i| {
+                let tolerance = 4.0 * f64::EPSILON * (self.coordinates[i].abs() + self.span[i]);
+                (child.coordinates[i] - coordinates[i]).abs() <= tolerance
+                    && (child.span[i] - span[i]).abs() <= tolerance
+            });
+            if !matches {
+                return Err(InvariantViolation::ChildRegion { path: path.clone() });
+            }
+
+            child.check_recursive(path, check_data)?;
+            path.pop();
+        }
+        Ok(())
+    }
+}
+
+impl<T: Coordinates<N>, const N: usize> SpatialTree<T, N> {
+    /// Verifies the structure of the tree like [`TpnTree::check_invariants`] and that every item lies within the tree holding it.
+    ///
+    /// Items are identified by their index in the data of their tree.
+    pub fn check_spatial_invariants(&self) -> Result<(), InvariantViolation> {
+        self.check_recursive(&mut Vec::new(), &mut |tree, path| match tree
+            .data
+            .iter()
+            .flatten()
+            .position(|data| {
+                !data
+                    .try_coordinates()
+                    .is_ok_and(|coordinates| tree.spans_coordinates(coordinates))
+            }) {
+            Some(item) => Err(InvariantViolation::ItemOutside {
+                path: path.clone(),
+                item,
+            }),
+            None => Ok(()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tpntree::{InvariantViolation, SpatialTree, TpnTree};
+
+    #[test]
+    fn detect_broken_structure() {
+        let mut root = TpnTree::<(), 2>::root(1.0);
+        root.divide().unwrap();
+        root.get_child_mut(1).unwrap().divide().unwrap();
+
+        let mut wrong_level = root.clone();
+        wrong_level.get_child_mut(1).unwrap().children[2].level = 5;
+        assert_eq!(
+            wrong_level.check_invariants(),
+            Err(InvariantViolation::Level {
+                path: vec![1, 2],
+                expected: 2,
+                got: 5
+            })
+        );
+
+        let mut wrong_region = root.clone();
+        wrong_region.children[3].span[0] = 0.25;
+        assert_eq!(
+            wrong_region.check_invariants(),
+            Err(InvariantViolation::ChildRegion { path: vec![3] })
+        );
+
+        let mut wrong_count = root;
+        wrong_count.children.pop();
+        assert_eq!(
+            wrong_count.check_invariants(),
+            Err(InvariantViolation::ChildCount {
+                path: vec![],
+                count: 3
+            })
+        );
+    }
+
+    #[test]
+    fn detect_items_outside_of_their_leaf() {
+        let mut tree = SpatialTree::<[f64; 2], 2>::root(1.0);
+        for point in [[0.5, 0.5], [-0.5, 0.5], [0.1, -0.9]] {
+            tree.insert_by_coordinates(point, &|tree| tree.data().is_some())
+                .unwrap();
+        }
+        assert_eq!(tree.check_spatial_invariants(), Ok(()));
+
+        tree.find_by_coordinates_mut(&[0.5, 0.5])
+            .unwrap()
+            .data_mut()
+            .as_mut()
+            .unwrap()
+            .push([-0.5, -0.5]);
+        assert!(matches!(
+            tree.check_spatial_invariants(),
+            Err(InvariantViolation::ItemOutside { item: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn tolerate_rounding_of_odd_regions() {
+        let mut root = TpnTree::<(), 3>::new([0.1, 0.7, -3.3], [0.3, 1.1, 0.7], 0);
+        root.divide_to_depth(3).unwrap();
+
+        assert_eq!(root.check_invariants(), Ok(()));
+    }
+}
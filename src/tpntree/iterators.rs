@@ -1,11 +1,269 @@
-use crate::{impl_breadth_first_iterator, impl_depth_first_iterator};
+use alloc::{vec, vec::Vec};
+use core::slice;
+
+use super::{SpatialTree, TpnTree};
+use crate::{impl_breadth_first_iterator, impl_depth_first_iterator, impl_levels_iterator};
 
 impl_breadth_first_iterator!(N);
 impl_depth_first_iterator!(N);
+impl_levels_iterator!(N);
+
+impl<T, const N: usize> TpnTree<T, N> {
+    /// Iterate the tree depth first, starting with the root, yielding only trees intersecting the box given by center and half extent.
+    ///
+    /// Trees not intersecting the box are skipped together with their children.
+    /// Touching boxes intersect.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::TpnTree;
+    /// let mut root = TpnTree::<(), 2>::root(1.0);
+    /// root.divide().expect("Couldn't divide.");
+    ///
+    /// assert_eq!(root.iter_depth_first_in_region([0.5, 0.5], [0.1, 0.1]).count(), 2);
+    /// ```
+    pub fn iter_depth_first_in_region(
+        &self,
+        center: [f64; N],
+        half_extent: [f64; N],
+    ) -> RegionIterator<'_, T, N> {
+        RegionIterator {
+            stack: vec![self],
+            center,
+            half_extent,
+        }
+    }
+
+    /// Iterate all leaves overlapping the box given by center and half extent, whether they hold data or not.
+    ///
+    /// The leaves together cover the part of the box inside the tree, so empty space shows up as leaves without data.
+    /// Leaves only touching the box are skipped.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::OccupancyTree;
+    /// let mut tree = OccupancyTree::<2>::root(1.0);
+    /// tree.divide().expect("Couldn't divide.");
+    /// *tree.get_child_mut(0).unwrap().data_mut() = Some(true);
+    /// *tree.get_child_mut(1).unwrap().data_mut() = Some(false);
+    ///
+    /// // occupied, free and unknown space along the upper half
+    /// let cells = tree
+    ///     .iter_leaves_in_region([0.0, 0.5], [1.0, 0.25])
+    ///     .map(|leaf| leaf.data().copied())
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(cells.len(), 2);
+    /// assert!(cells.contains(&Some(true)) && cells.contains(&Some(false)));
+    /// assert_eq!(tree.iter_leaves_in_region([0.0, -0.5], [0.1, 0.1]).count(), 2);
+    /// ```
+    pub fn iter_leaves_in_region(
+        &self,
+        center: [f64; N],
+        half_extent: [f64; N],
+    ) -> LeafRegionIterator<'_, T, N> {
+        LeafRegionIterator {
+            stack: vec![self],
+            center,
+            half_extent,
+        }
+    }
+
+    /// Iterate the cut of the tree whose detail decreases with the distance to the viewpoint, as used for level of detail rendering.
+    ///
+    /// A tree is yielded instead of its children if it is a leaf or its level reaches the max level returned by `detail_fn` for its distance to the viewpoint.
+    /// The yielded trees do not overlap and together cover the whole tree.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::TpnTree;
+    /// let mut root = TpnTree::<(), 2>::root(1.0);
+    /// root.divide_to_depth(3).expect("Couldn't divide.");
+    ///
+    /// let cut = root
+    ///     .iter_lod([1.0, 1.0], |distance| if distance < 0.5 { 3 } else { 1 })
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert!(cut.iter().any(|tree| tree.level() == 3));
+    /// assert!(cut.iter().any(|tree| tree.level() == 1));
+    /// assert_eq!(cut.iter().map(|tree| tree.volume()).sum::<f64>(), root.volume());
+    /// ```
+    pub fn iter_lod<F: Fn(f64) -> usize>(
+        &self,
+        viewpoint: [f64; N],
+        detail_fn: F,
+    ) -> LodIterator<'_, T, N, F> {
+        LodIterator {
+            stack: vec![self],
+            viewpoint,
+            detail_fn,
+        }
+    }
+
+    fn intersects_box(&self, center: &[f64; N], half_extent: &[f64; N]) -> bool {
+        (0..N).all(|i| (self.coordinates[i] - center[i]).abs() <= self.span[i] + half_extent[i])
+    }
+
+    fn overlaps_box(&self, center: &[f64; N], half_extent: &[f64; N]) -> bool {
+        (0..N).all(|i| (self.coordinates[i] - center[i]).abs() < self.span[i] + half_extent[i])
+    }
+}
+
+impl<T, const N: usize> SpatialTree<T, N> {
+    /// Iterates all items stored in the tree by reference, the items of each tree in the order of the depth first iteration.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::Tree3D;
+    /// let mut tree = Tree3D::builder().leaf_capacity(1).build();
+    /// tree.insert([0.5, 0.5, 0.5]).expect("Couldn't insert.");
+    /// tree.insert([-0.5, 0.5, 0.5]).expect("Couldn't insert.");
+    ///
+    /// assert_eq!(tree.iter_data().count(), 2);
+    /// ```
+    pub fn iter_data(&self) -> DataIterator<'_, T, N> {
+        DataIterator {
+            trees: self.iter_depth_first(),
+            items: Default::default(),
+        }
+    }
+
+    /// Iterates all items stored in the tree by mutable reference, in the same order as [`SpatialTree::iter_data`].
+    ///
+    /// Moving items such that their tree does not span them anymore breaks the tree, see [`SpatialTree::check_spatial_invariants`].
+    ///
+    /// ```
+    /// # use tpntree::tpntree::TimedTree;
+    /// let mut tree = TimedTree::<[f64; 2], u64, 2>::root(1.0);
+    /// tree.insert_with_timestamp([0.5, 0.5], 1, &|_| false).expect("Couldn't insert.");
+    ///
+    /// for item in tree.iter_data_mut() {
+    ///     item.timestamp += 10;
+    /// }
+    ///
+    /// assert_eq!(tree.iter_data().next().unwrap().timestamp, 11);
+    /// ```
+    pub fn iter_data_mut(&mut self) -> DataIterMut<'_, T, N> {
+        DataIterMut {
+            stack: vec![self],
+            items: Default::default(),
+        }
+    }
+}
+
+pub struct DataIterator<'a, T, const N: usize> {
+    trees: DepthFirstIterator<'a, Vec<T>, N>,
+    items: slice::Iter<'a, T>,
+}
+
+impl<'a, T, const N: usize> Iterator for DataIterator<'a, T, N> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.items.next() {
+                return Some(item);
+            }
+            self.items = self
+                .trees
+                .next()?
+                .data
+                .as_deref()
+                .unwrap_or_default()
+                .iter();
+        }
+    }
+}
+
+pub struct DataIterMut<'a, T, const N: usize> {
+    stack: Vec<&'a mut SpatialTree<T, N>>,
+    items: slice::IterMut<'a, T>,
+}
+
+impl<'a, T, const N: usize> Iterator for DataIterMut<'a, T, N> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.items.next() {
+                return Some(item);
+            }
+            let TpnTree { children, data, .. } = self.stack.pop()?;
+            self.stack.extend(children.iter_mut());
+            self.items = data.as_deref_mut().unwrap_or_default().iter_mut();
+        }
+    }
+}
+
+pub struct RegionIterator<'a, T, const N: usize> {
+    stack: Vec<&'a TpnTree<T, N>>,
+    center: [f64; N],
+    half_extent: [f64; N],
+}
+
+impl<'a, T, const N: usize> Iterator for RegionIterator<'a, T, N> {
+    type Item = &'a TpnTree<T, N>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(tree) = self.stack.pop() {
+            if tree.intersects_box(&self.center, &self.half_extent) {
+                for child in tree.iter_children() {
+                    self.stack.push(child);
+                }
+                return Some(tree);
+            }
+        }
+        None
+    }
+}
+
+pub struct LeafRegionIterator<'a, T, const N: usize> {
+    stack: Vec<&'a TpnTree<T, N>>,
+    center: [f64; N],
+    half_extent: [f64; N],
+}
+
+impl<'a, T, const N: usize> Iterator for LeafRegionIterator<'a, T, N> {
+    type Item = &'a TpnTree<T, N>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(tree) = self.stack.pop() {
+            if !tree.overlaps_box(&self.center, &self.half_extent) {
+                continue;
+            }
+            if tree.is_leaf() {
+                return Some(tree);
+            }
+            for child in tree.iter_children() {
+                self.stack.push(child);
+            }
+        }
+        None
+    }
+}
+
+pub struct LodIterator<'a, T, const N: usize, F> {
+    stack: Vec<&'a TpnTree<T, N>>,
+    viewpoint: [f64; N],
+    detail_fn: F,
+}
+
+impl<'a, T, const N: usize, F: Fn(f64) -> usize> Iterator for LodIterator<'a, T, N, F> {
+    type Item = &'a TpnTree<T, N>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(tree) = self.stack.pop() {
+            let max_level = (self.detail_fn)(tree.distance_to_point(&self.viewpoint));
+            if tree.is_leaf() || tree.level >= max_level {
+                return Some(tree);
+            }
+            for child in tree.iter_children() {
+                self.stack.push(child);
+            }
+        }
+        None
+    }
+}
 
 #[cfg(test)]
 mod tests {
-    use crate::tpntree::TpnTree;
+    use crate::tpntree::{SplitPolicy, TpnTree, Tree3D};
 
     #[test]
     fn iterate_depth_first() {
@@ -39,12 +297,12 @@ mod tests {
         assert!(tree.divide().is_ok());
 
         tree.get_child_mut(0).and_then::<(), _>(|child| {
-            child.data_mut().insert(2.0);
+            *child.data_mut() = Some(2.0);
             assert!(child.divide().is_ok());
             None
         });
         tree.get_child_mut(1).and_then::<(), _>(|child| {
-            child.data_mut().insert(3.0);
+            *child.data_mut() = Some(3.0);
             assert!(child.divide().is_ok());
             None
         });
@@ -55,4 +313,137 @@ mod tests {
         assert_eq!(iter.next().and_then(|t| t.data()), Some(&2.0));
         assert_eq!(iter.next().and_then(|t| t.data()), Some(&3.0));
     }
+
+    #[test]
+    fn iterate_to_level() {
+        let mut tree = TpnTree::<(), 2>::root(1.0);
+        tree.divide_to_depth(3).unwrap();
+
+        assert_eq!(tree.iter_to_level(0).count(), 1);
+        assert_eq!(tree.iter_to_level(2).count(), 1 + 4 + 16);
+        assert!(tree
+            .iter_to_level(2)
+            .zip(tree.iter_breadth_first())
+            .all(|(a, b)| std::ptr::eq(a, b)));
+        assert_eq!(
+            tree.iter_to_level(usize::MAX).count(),
+            tree.iter_breadth_first().count()
+        );
+        assert_eq!(tree.get_child(0).unwrap().iter_to_level(0).count(), 0);
+    }
+
+    #[test]
+    fn iterate_levels() {
+        let mut tree = TpnTree::<f64, 2>::root(1.0);
+
+        assert!(tree.divide().is_ok());
+        assert!(tree.get_child_mut(1).unwrap().divide().is_ok());
+        assert!(tree.get_child_mut(2).unwrap().divide().is_ok());
+
+        let levels = tree
+            .iter_levels()
+            .map(|(level, trees)| (level, trees.len()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(levels, vec![(0, 1), (1, 4), (2, 8)]);
+        assert!(tree
+            .iter_levels()
+            .all(|(level, trees)| trees.iter().all(|tree| tree.level() == level)));
+    }
+
+    #[test]
+    fn iterate_in_region_prunes_subtrees() {
+        let mut tree = TpnTree::<(), 2>::root(1.0);
+        assert!(tree.divide_to_depth(3).is_ok());
+
+        let in_region = tree
+            .iter_depth_first_in_region([-0.6, -0.6], [0.05, 0.05])
+            .collect::<Vec<_>>();
+
+        assert_eq!(in_region.len(), 4);
+        assert!(in_region
+            .iter()
+            .all(|tree| tree.contains_point(&[-0.6, -0.6])));
+        assert_eq!(
+            tree.iter_depth_first_in_region([0.0, 0.0], [0.0, 0.0])
+                .count(),
+            1 + 4 + 4 + 4
+        );
+    }
+
+    #[test]
+    fn leaves_in_region_cover_the_region() {
+        let mut tree = TpnTree::<(), 2>::root(1.0);
+        assert!(tree
+            .refine_where(|tree| tree.coordinates()[0] > 0.0, 3)
+            .is_ok());
+
+        let (center, half_extent) = ([0.0, 0.25], [0.5, 0.5]);
+        let leaves = tree
+            .iter_leaves_in_region(center, half_extent)
+            .collect::<Vec<_>>();
+
+        // area of the query box clipped to each leaf
+        let covered = leaves
+            .iter()
+            .map(|leaf| {
+                (0..2)
+                    .map(|i| {
+                        let min = leaf.min_corner()[i].max(center[i] - half_extent[i]);
+                        let max = leaf.max_corner()[i].min(center[i] + half_extent[i]);
+                        max - min
+                    })
+                    .product::<f64>()
+            })
+            .sum::<f64>();
+        assert!(leaves.iter().all(|leaf| leaf.is_leaf()));
+        assert!((covered - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn iterate_lod_refines_towards_viewpoint() {
+        let mut tree = TpnTree::<(), 3>::root(1.0);
+        assert!(tree.divide_to_depth(4).is_ok());
+
+        let viewpoint = [-1.0, 0.0, 1.0];
+        let detail = |distance: f64| 4 - (4.0 * distance).min(4.0) as usize;
+        let cut = tree.iter_lod(viewpoint, detail).collect::<Vec<_>>();
+
+        assert_eq!(
+            cut.iter().map(|tree| tree.volume()).sum::<f64>(),
+            tree.volume()
+        );
+        assert!(cut
+            .iter()
+            .all(|tree| tree.level() >= detail(tree.distance_to_point(&viewpoint))));
+        assert!(cut.iter().any(|tree| tree.level() == 4));
+        assert!(cut.iter().any(|tree| tree.level() == 1));
+    }
+
+    #[test]
+    fn data_iterators_flatten_all_trees() {
+        let mut tree = Tree3D::root(1.0);
+        let full = |tree: &Tree3D| tree.is_root() && tree.data().is_some();
+        for point in [[0.5, 0.5, 0.5], [-0.5, 0.5, 0.5], [0.6, 0.6, 0.6]].iter() {
+            tree.insert_with_policy(*point, &full, SplitPolicy::KeepInParent)
+                .unwrap();
+        }
+
+        // the root keeps its item, the children hold the others
+        let items = tree.iter_data().copied().collect::<Vec<_>>();
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[0], [0.5, 0.5, 0.5]);
+
+        for item in tree.iter_data_mut() {
+            item[2] = -item[2];
+        }
+        assert_eq!(
+            tree.iter_data().copied().collect::<Vec<_>>(),
+            items
+                .iter()
+                .map(|item| [item[0], item[1], -item[2]])
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(TpnTree::<Vec<f64>, 1>::root(1.0).iter_data().count(), 0);
+    }
 }
@@ -0,0 +1,172 @@
+use core::ptr;
+
+use super::{NodePath, TpnTree};
+
+/// A locational code identifying a tree by its path from the root, packed into a single number.
+///
+/// The key holds N bits per level, the child index at each level, below a leading marker bit that encodes the level.
+/// Keys fit trees up to [`NodeKey::MAX_LEVEL`] and sort parents before their children and siblings by child index.
+/// As child indices are stored in N bits, keys can only address the first 2^N children of trees divided with [`TpnTree::divide_with_branching`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeKey<const N: usize>(u128);
+
+impl<const N: usize> NodeKey<N> {
+    /// The deepest level a key can describe.
+    pub const MAX_LEVEL: usize = 127 / N;
+
+    /// Returns the key of the root.
+    pub fn root() -> Self {
+        Self(1)
+    }
+
+    /// Builds the key of the tree reached by following the child indices from the root.
+    ///
+    /// Returns `None` if an index is not below 2^N or the path is longer than [`NodeKey::MAX_LEVEL`].
+    ///
+    /// ```
+    /// # use tpntree::tpntree::NodeKey;
+    /// let key = NodeKey::<3>::from_path(&[7, 0, 2]).expect("Valid path.");
+    ///
+    /// assert_eq!(key.level(), 3);
+    /// assert_eq!(key.path(), vec![7, 0, 2]);
+    /// assert_eq!(key.parent(), NodeKey::from_path(&[7, 0]));
+    /// ```
+    pub fn from_path(path: &[usize]) -> Option<Self> {
+        path.iter()
+            .try_fold(Self::root(), |key, &index| key.child(index))
+    }
+
+    /// Rebuilds a key from its packed representation, returning `None` if the bits are no valid key.
+    pub fn from_bits(bits: u128) -> Option<Self> {
+        (bits != 0 && (127 - bits.leading_zeros() as usize) % N == 0).then_some(Self(bits))
+    }
+
+    /// Returns the packed representation.
+    pub fn to_bits(self) -> u128 {
+        self.0
+    }
+
+    /// Returns the level of the tree identified by the key.
+    pub fn level(self) -> usize {
+        (127 - self.0.leading_zeros() as usize) / N
+    }
+
+    /// Returns the child indices leading from the root to the tree.
+    pub fn path(self) -> NodePath {
+        let mask = (1 << N) - 1;
+        (0..self.level())
+            .rev()
+            .map(|level| (self.0 >> (level * N)) as usize & mask)
+            .collect()
+    }
+
+    /// Returns the key of the parent, or `None` for the root.
+    pub fn parent(self) -> Option<Self> {
+        (self.0 != 1).then_some(Self(self.0 >> N))
+    }
+
+    /// Returns the key of the child at the index, or `None` if the index is not below 2^N or the child is too deep.
+    pub fn child(self, index: usize) -> Option<Self> {
+        (index < 1 << N && self.level() < Self::MAX_LEVEL)
+            .then(|| Self(self.0 << N | index as u128))
+    }
+}
+
+impl<T, const N: usize> TpnTree<T, N> {
+    /// Returns the key of the descendant relative to this tree, or `None` if it is not a descendant or can not be addressed by a key.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::{NodeKey, TpnTree};
+    /// let mut root = TpnTree::<(), 2>::root(1.0);
+    /// root.divide_to_depth(2).expect("Couldn't divide.");
+    ///
+    /// let tree = root.get_child(3).unwrap().get_child(1).unwrap();
+    /// let key = root.key_of(tree).expect("Is a descendant.");
+    ///
+    /// assert_eq!(key, NodeKey::from_path(&[3, 1]).unwrap());
+    /// assert!(core::ptr::eq(root.find_by_key(key).unwrap(), tree));
+    /// ```
+    pub fn key_of(&self, tree: &Self) -> Option<NodeKey<N>> {
+        let mut current = self;
+        let mut key = NodeKey::root();
+        while current.level < tree.level && !current.is_leaf() {
+            let index = current.child_index_for(&tree.coordinates);
+            current = &current.children[index];
+            key = key.child(index)?;
+        }
+        ptr::eq(current, tree).then_some(key)
+    }
+
+    /// Returns the descendant identified by the key relative to this tree.
+    pub fn find_by_key(&self, key: NodeKey<N>) -> Option<&Self> {
+        key.path()
+            .into_iter()
+            .try_fold(self, |tree, index| tree.get_child(index))
+    }
+
+    /// Returns the descendant identified by the key relative to this tree by mutable reference.
+    pub fn find_by_key_mut(&mut self, key: NodeKey<N>) -> Option<&mut Self> {
+        key.path()
+            .into_iter()
+            .try_fold(self, |tree, index| tree.get_child_mut(index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tpntree::{NodeKey, TpnTree};
+
+    #[test]
+    fn keys_address_the_first_children_of_branching_trees() {
+        let mut root = TpnTree::<(), 2>::root(1.0);
+        root.divide_with_branching(3).unwrap();
+
+        for (index, child) in root.iter_children().enumerate() {
+            let key = root.key_of(child);
+            assert_eq!(key.is_some(), index < 4);
+            if let Some(key) = key {
+                assert!(std::ptr::eq(root.find_by_key(key).unwrap(), child));
+            }
+        }
+        assert_eq!(NodeKey::<2>::root().child(4), None);
+    }
+
+    #[test]
+    fn keys_round_trip_for_all_trees() {
+        let mut root = TpnTree::<(), 3>::root(1.0);
+        root.divide_to_depth(2).unwrap();
+        root.get_child_mut(5)
+            .unwrap()
+            .get_child_mut(6)
+            .unwrap()
+            .divide()
+            .unwrap();
+
+        for tree in root.iter_depth_first() {
+            let key = root.key_of(tree).unwrap();
+            assert_eq!(key.level(), tree.level());
+            assert!(std::ptr::eq(root.find_by_key(key).unwrap(), tree));
+            assert_eq!(NodeKey::from_bits(key.to_bits()), Some(key));
+        }
+
+        let other = TpnTree::<(), 3>::root(1.0);
+        assert_eq!(root.key_of(&other), None);
+        assert!(root
+            .find_by_key(NodeKey::from_path(&[5, 6, 1, 0]).unwrap())
+            .is_none());
+    }
+
+    #[test]
+    fn keys_are_bounded() {
+        assert_eq!(NodeKey::<2>::root().parent(), None);
+        assert_eq!(NodeKey::<2>::root().child(4), None);
+        assert_eq!(NodeKey::<3>::from_bits(0b10), None);
+        assert_eq!(NodeKey::<3>::MAX_LEVEL, 42);
+        assert!(NodeKey::<3>::from_path(&[1; 42]).is_some());
+        assert!(NodeKey::<3>::from_path(&[1; 43]).is_none());
+        assert_eq!(
+            NodeKey::<3>::from_path(&[1; 42]).unwrap().path(),
+            vec![1; 42]
+        );
+    }
+}
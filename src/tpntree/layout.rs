@@ -0,0 +1,144 @@
+use super::{branching, Region, TpnTree};
+use crate::errors::TpnTreeError;
+
+/// How a tree is split into its children, see [`TpnTree::split_layout`].
+///
+/// The default layout is the one of [`TpnTree::divide`], splitting the tree at its center along all axes into two parts each.
+///
+/// ```
+/// # use tpntree::tpntree::{SplitLayout, TpnTree};
+/// let mut tree = TpnTree::<(), 3>::root(1.0);
+/// tree.divide_axes(&[0, 2]).expect("Couldn't divide.");
+///
+/// let mut copy = TpnTree::<(), 3>::root(1.0);
+/// copy.divide_with_layout(tree.split_layout()).expect("Couldn't divide.");
+///
+/// assert_eq!(copy, tree);
+/// assert_eq!(TpnTree::<(), 3>::root(1.0).split_layout(), SplitLayout::default());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SplitLayout<const N: usize> {
+    /// Axes the tree is split along as bits, all of them if `None`, see [`TpnTree::divide_axes`].
+    pub axes: Option<usize>,
+    /// Parts each split axis is divided into, see [`TpnTree::divide_with_branching`].
+    pub branching: usize,
+    /// Point the tree is split at, its center if `None`, see [`TpnTree::divide_at`].
+    pub point: Option<[f64; N]>,
+}
+
+impl<const N: usize> Default for SplitLayout<N> {
+    fn default() -> Self {
+        Self {
+            axes: None,
+            branching: 2,
+            point: None,
+        }
+    }
+}
+
+impl<const N: usize> SplitLayout<N> {
+    // the count of children of a tree divided with the layout
+    pub(crate) fn child_count(&self) -> Result<usize, TpnTreeError> {
+        let split_count = self.axes.map_or(N, |axes| axes.count_ones() as usize);
+        branching::child_count(split_count, self.branching)
+    }
+
+    // a childless tree with the layout, to find children within the region without dividing it
+    pub(crate) fn shape(&self, region: &Region<N>) -> TpnTree<(), N> {
+        let mut tree = TpnTree::new(region.coordinates(), region.span(), region.level());
+        tree.set_split_layout(*self);
+        tree
+    }
+}
+
+impl<T, const N: usize> TpnTree<T, N> {
+    /// Returns how the tree is split into its children, or the default layout for a leaf.
+    ///
+    /// A split point at the center of the tree is reported as `None`.
+    pub fn split_layout(&self) -> SplitLayout<N> {
+        if self.is_leaf() {
+            return SplitLayout::default();
+        }
+        SplitLayout {
+            axes: self.split_axes,
+            branching: self.branching,
+            point: self.split_point.filter(|point| *point != self.coordinates),
+        }
+    }
+
+    /// Divides the tree like a tree with the layout, e.g. to replay the divisions of another tree.
+    ///
+    /// Errors with [`TpnTreeError::NoSuchAxis`] for an axis not below N, with [`TpnTreeError::DoesNotSpan`] if the split point is not strictly inside the tree
+    /// and otherwise like [`TpnTree::divide_axes`], [`TpnTree::divide_with_branching`] and [`TpnTree::divide_at`].
+    pub fn divide_with_layout(&mut self, layout: SplitLayout<N>) -> Result<(), TpnTreeError> {
+        if let Some(axis) = layout
+            .axes
+            .and_then(|axes| (N..usize::BITS as usize).find(|axis| axes & 1 << axis != 0))
+        {
+            return Err(TpnTreeError::NoSuchAxis { axis });
+        }
+        if let Some(point) = layout.point {
+            let inside =
+                (0..N).all(|axis| (point[axis] - self.coordinates[axis]).abs() < self.span[axis]);
+            if !inside {
+                return Err(self.does_not_span(&point));
+            }
+        }
+        self.divide_along(layout.axes, layout.branching, layout.point)
+    }
+
+    // takes over the layout without dividing, for trees whose children are built elsewhere
+    pub(crate) fn set_split_layout(&mut self, layout: SplitLayout<N>) {
+        self.split_axes = layout.axes;
+        self.branching = layout.branching;
+        self.split_point = layout.point;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tpntree::{SplitLayout, TpnTree};
+    use crate::TpnTreeError;
+
+    #[test]
+    fn layouts_replay_every_kind_of_division() {
+        let mut anisotropic = TpnTree::<(), 3>::root(1.0);
+        let mut branching = TpnTree::<(), 3>::root(1.0);
+        let mut off_center = TpnTree::<(), 3>::root(1.0);
+        assert!(anisotropic.divide_axes(&[1]).is_ok());
+        assert!(branching.divide_with_branching(3).is_ok());
+        assert!(off_center.divide_at([0.5, -0.25, 0.0]).is_ok());
+
+        for tree in [anisotropic, branching, off_center] {
+            let mut copy = TpnTree::<(), 3>::root(1.0);
+            assert!(copy.divide_with_layout(tree.split_layout()).is_ok());
+            assert_eq!(copy.split_layout(), tree.split_layout());
+            for (a, b) in copy.iter_children().zip(tree.iter_children()) {
+                assert_eq!(a.region(), b.region());
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_layouts_are_rejected() {
+        let mut tree = TpnTree::<(), 2>::root(1.0);
+
+        let axes = SplitLayout {
+            axes: Some(0b101),
+            ..SplitLayout::default()
+        };
+        assert_eq!(
+            tree.divide_with_layout(axes),
+            Err(TpnTreeError::NoSuchAxis { axis: 2 })
+        );
+        let point = SplitLayout {
+            point: Some([1.0, 0.0]),
+            ..SplitLayout::default()
+        };
+        assert!(matches!(
+            tree.divide_with_layout(point),
+            Err(TpnTreeError::DoesNotSpan { .. })
+        ));
+        assert!(tree.is_leaf());
+    }
+}
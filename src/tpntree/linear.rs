@@ -0,0 +1,394 @@
+use super::{SplitLayout, TpnTree};
+use crate::errors::TpnTreeError;
+use alloc::{vec, vec::Vec};
+
+/// A tree flattened into contiguous arrays, e.g. for upload to GPU buffers or passing over FFI.
+///
+/// The trees are stored in breadth first order, the root at index 0.
+/// All arrays but `data` have one entry per tree.
+/// The children of a tree are stored consecutively, starting at its `first_child`.
+/// Missing children and data are marked with [`LinearTree::NONE`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinearTree<T, const N: usize> {
+    /// Coordinates of the center of each tree.
+    pub centers: Vec<[f64; N]>,
+    /// Span of each tree.
+    pub spans: Vec<[f64; N]>,
+    /// Level of each tree.
+    pub levels: Vec<usize>,
+    /// Index of the first child of each tree.
+    pub first_child: Vec<usize>,
+    /// Axes each tree is split along as bits, all N of them for leaves, see [`TpnTree::divide_axes`].
+    pub split_axes: Vec<usize>,
+    /// Parts each split axis of each tree is divided into, 2 for leaves, see [`TpnTree::divide_with_branching`].
+    pub branching: Vec<usize>,
+    /// Point each tree is split at, its center for leaves, see [`TpnTree::divide_at`].
+    pub split_points: Vec<[f64; N]>,
+    /// Index into `data` for each tree.
+    pub data_index: Vec<usize>,
+    /// Data of all trees holding some, in breadth first order.
+    pub data: Vec<T>,
+}
+
+impl<T, const N: usize> LinearTree<T, N> {
+    /// Marks a missing child or missing data.
+    pub const NONE: usize = usize::MAX;
+
+    /// Returns the count of trees.
+    pub fn len(&self) -> usize {
+        self.centers.len()
+    }
+
+    /// Returns whether there are no trees.
+    pub fn is_empty(&self) -> bool {
+        self.centers.is_empty()
+    }
+}
+
+impl<T: Clone, const N: usize> TpnTree<T, N> {
+    /// Flattens the tree into contiguous arrays in breadth first order.
+    ///
+    /// The [`TreeConfig`](super::TreeConfig) of the tree is not part of the arrays.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::{LinearTree, TpnTree};
+    /// let mut root = TpnTree::<u32, 2>::root(1.0);
+    /// root.divide().expect("Couldn't divide.");
+    /// *root.get_child_mut(2).unwrap().data_mut() = Some(7);
+    ///
+    /// let linear = root.to_linear();
+    ///
+    /// assert_eq!(linear.len(), 5);
+    /// assert_eq!(linear.first_child[0], 1);
+    /// assert_eq!(linear.first_child[1], LinearTree::<u32, 2>::NONE);
+    /// assert_eq!(linear.data[linear.data_index[3]], 7);
+    /// assert_eq!(TpnTree::from_linear(linear), Ok(root));
+    /// ```
+    pub fn to_linear(&self) -> LinearTree<T, N> {
+        let mut linear = LinearTree {
+            centers: Vec::new(),
+            spans: Vec::new(),
+            levels: Vec::new(),
+            first_child: Vec::new(),
+            split_axes: Vec::new(),
+            branching: Vec::new(),
+            split_points: Vec::new(),
+            data_index: Vec::new(),
+            data: Vec::new(),
+        };
+
+        // children are numbered in the order their parents are visited
+        let mut next_index = 1;
+        for tree in self.iter_breadth_first() {
+            linear.centers.push(tree.coordinates);
+            linear.spans.push(tree.span);
+            linear.levels.push(tree.level);
+
+            if tree.is_leaf() {
+                linear.first_child.push(LinearTree::<T, N>::NONE);
+            } else {
+                linear.first_child.push(next_index);
+                next_index += tree.children.len();
+            }
+            linear.split_axes.push(
+                tree.split_axes_iter()
+                    .fold(0, |split_axes, axis| split_axes | 1 << axis),
+            );
+            linear.branching.push(tree.split_layout().branching);
+            linear.split_points.push(tree.split_point());
+
+            match &tree.data {
+                Some(data) => {
+                    linear.data_index.push(linear.data.len());
+                    linear.data.push(data.clone());
+                }
+                None => linear.data_index.push(LinearTree::<T, N>::NONE),
+            }
+        }
+        linear
+    }
+}
+
+impl<T, const N: usize> TpnTree<T, N> {
+    /// Rebuilds a tree from contiguous arrays as created by [`TpnTree::to_linear`].
+    ///
+    /// The trees do not need to be in breadth first order, but every child has to come after its parent.
+    /// Errors with [`TpnTreeError::MalformedLinearTree`] if the arrays differ in length, there is no root, an index is out of range or used twice,
+    /// or a tree is split along an axis not below N, into less than two parts per axis or at a point outside of it.
+    pub fn from_linear(linear: LinearTree<T, N>) -> Result<Self, TpnTreeError> {
+        let len = linear.len();
+        if len == 0
+            || linear.spans.len() != len
+            || linear.levels.len() != len
+            || linear.first_child.len() != len
+            || linear.split_axes.len() != len
+            || linear.branching.len() != len
+            || linear.split_points.len() != len
+            || linear.data_index.len() != len
+        {
+            return Err(TpnTreeError::MalformedLinearTree);
+        }
+
+        let mut data = linear.data.into_iter().map(Some).collect::<Vec<_>>();
+        let mut used = vec![false; len];
+        build(
+            0,
+            &linear.centers,
+            &linear.spans,
+            &linear.levels,
+            &linear.first_child,
+            &linear.split_axes,
+            &linear.branching,
+            &linear.split_points,
+            &linear.data_index,
+            &mut data,
+            &mut used,
+        )
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn build<T, const N: usize>(
+    index: usize,
+    centers: &[[f64; N]],
+    spans: &[[f64; N]],
+    levels: &[usize],
+    first_child: &[usize],
+    split_axes: &[usize],
+    branching: &[usize],
+    split_points: &[[f64; N]],
+    data_index: &[usize],
+    data: &mut [Option<T>],
+    used: &mut [bool],
+) -> Result<TpnTree<T, N>, TpnTreeError> {
+    if core::mem::replace(&mut used[index], true) {
+        return Err(TpnTreeError::MalformedLinearTree);
+    }
+
+    let mut tree = TpnTree::new(centers[index], spans[index], levels[index]);
+
+    if data_index[index] != LinearTree::<T, N>::NONE {
+        tree.data = Some(
+            data.get_mut(data_index[index])
+                .and_then(Option::take)
+                .ok_or(TpnTreeError::MalformedLinearTree)?,
+        );
+    }
+
+    let first = first_child[index];
+    if first != LinearTree::<T, N>::NONE {
+        let axes = split_axes[index];
+        if N < usize::BITS as usize && axes >> N != 0 {
+            return Err(TpnTreeError::MalformedLinearTree);
+        }
+        let layout = SplitLayout {
+            axes: (axes.count_ones() as usize != N).then_some(axes),
+            branching: branching[index],
+            point: (split_points[index] != centers[index]).then_some(split_points[index]),
+        };
+        if layout.point.is_some_and(|point| {
+            (0..N).any(|axis| (point[axis] - centers[index][axis]).abs() >= spans[index][axis])
+        }) {
+            return Err(TpnTreeError::MalformedLinearTree);
+        }
+        let count = layout
+            .child_count()
+            .map_err(|_| TpnTreeError::MalformedLinearTree)?;
+        tree.set_split_layout(layout);
+        let end = first
+            .checked_add(count)
+            .filter(|&end| first > index && end <= centers.len())
+            .ok_or(TpnTreeError::MalformedLinearTree)?;
+        tree.children = (first..end)
+            .map(|child| {
+                build(
+                    child,
+                    centers,
+                    spans,
+                    levels,
+                    first_child,
+                    split_axes,
+                    branching,
+                    split_points,
+                    data_index,
+                    data,
+                    used,
+                )
+            })
+            .collect::<Result<_, _>>()?;
+    }
+    Ok(tree)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tpntree::{LinearTree, TpnTree};
+    use crate::TpnTreeError;
+
+    #[test]
+    fn round_trip_through_linear() {
+        let mut tree = TpnTree::<String, 3>::root(2.0);
+        assert!(tree.divide().is_ok());
+        assert!(tree.get_child_mut(5).unwrap().divide().is_ok());
+        assert!(tree
+            .get_child_mut(5)
+            .unwrap()
+            .get_child_mut(0)
+            .unwrap()
+            .divide()
+            .is_ok());
+        *tree.data_mut() = Some("root".to_string());
+        *tree
+            .get_child_mut(5)
+            .unwrap()
+            .get_child_mut(7)
+            .unwrap()
+            .data_mut() = Some("deep".to_string());
+
+        let linear = tree.to_linear();
+
+        assert_eq!(linear.len(), 1 + 8 + 8 + 8);
+        assert_eq!(linear.data, vec!["root".to_string(), "deep".to_string()]);
+        // the children of every tree are consecutive and directly below it
+        for (index, &first) in linear.first_child.iter().enumerate() {
+            if first != LinearTree::<String, 3>::NONE {
+                assert!((first..first + 8)
+                    .all(|child| linear.levels[child] == linear.levels[index] + 1));
+            }
+        }
+        assert!(linear.levels.windows(2).all(|pair| pair[0] <= pair[1]));
+
+        assert_eq!(TpnTree::from_linear(linear), Ok(tree));
+    }
+
+    #[test]
+    fn round_trip_anisotropic_trees() {
+        let mut tree = TpnTree::<u8, 3>::root(1.0);
+        assert!(tree.divide_axes(&[0, 2]).is_ok());
+        assert!(tree.get_child_mut(1).unwrap().divide().is_ok());
+        assert!(tree.get_child_mut(3).unwrap().divide_axes(&[1]).is_ok());
+        *tree
+            .get_child_mut(3)
+            .unwrap()
+            .get_child_mut(1)
+            .unwrap()
+            .data_mut() = Some(1);
+
+        let linear = tree.to_linear();
+        assert_eq!(linear.len(), 1 + 4 + 8 + 2);
+        assert_eq!(linear.split_axes[0], 0b101);
+
+        let rebuilt = TpnTree::from_linear(linear).unwrap();
+        assert_eq!(rebuilt, tree);
+        for (a, b) in rebuilt.iter_depth_first().zip(tree.iter_depth_first()) {
+            assert_eq!(a.split_layout(), b.split_layout());
+        }
+    }
+
+    #[test]
+    fn round_trip_branching_trees() {
+        let mut tree = TpnTree::<u8, 2>::root(1.0);
+        assert!(tree.divide_with_branching(3).is_ok());
+        assert!(tree
+            .get_child_mut(4)
+            .unwrap()
+            .divide_with_branching(4)
+            .is_ok());
+        assert!(tree.get_child_mut(8).unwrap().divide().is_ok());
+        *tree
+            .get_child_mut(4)
+            .unwrap()
+            .get_child_mut(15)
+            .unwrap()
+            .data_mut() = Some(1);
+
+        let linear = tree.to_linear();
+        assert_eq!(linear.len(), 1 + 9 + 16 + 4);
+        assert_eq!(&linear.branching[..2], &[3, 2]);
+
+        let rebuilt = TpnTree::from_linear(linear).unwrap();
+        assert_eq!(rebuilt, tree);
+        assert_eq!(rebuilt.child_count(), 9);
+        for (a, b) in rebuilt.iter_depth_first().zip(tree.iter_depth_first()) {
+            assert_eq!(a.split_layout(), b.split_layout());
+        }
+    }
+
+    #[test]
+    fn round_trip_off_center_trees() {
+        let mut tree = TpnTree::<u8, 2>::root(1.0);
+        assert!(tree.divide_at([0.5, 0.5]).is_ok());
+        assert!(tree
+            .get_child_mut(3)
+            .unwrap()
+            .divide_at([-0.5, 0.0])
+            .is_ok());
+
+        let linear = tree.to_linear();
+        assert_eq!(linear.split_points[0], [0.5, 0.5]);
+
+        let rebuilt = TpnTree::from_linear(linear).unwrap();
+        assert_eq!(rebuilt, tree);
+        assert_eq!(rebuilt.child_index_for_point(&[0.2, 0.2]), 3);
+        for (a, b) in rebuilt.iter_depth_first().zip(tree.iter_depth_first()) {
+            assert_eq!(a.split_point(), b.split_point());
+        }
+    }
+
+    #[test]
+    fn malformed_arrays_are_rejected() {
+        let mut tree = TpnTree::<u8, 1>::root(1.0);
+        assert!(tree.divide().is_ok());
+        *tree.data_mut() = Some(1);
+        let linear = tree.to_linear();
+
+        let mut cyclic = linear.clone();
+        cyclic.first_child[1] = 0;
+        assert_eq!(
+            TpnTree::from_linear(cyclic),
+            Err(TpnTreeError::MalformedLinearTree)
+        );
+
+        let mut out_of_range = linear.clone();
+        out_of_range.first_child[0] = 2;
+        assert_eq!(
+            TpnTree::from_linear(out_of_range),
+            Err(TpnTreeError::MalformedLinearTree)
+        );
+
+        let mut shared_data = linear.clone();
+        shared_data.data_index[2] = 0;
+        assert_eq!(
+            TpnTree::from_linear(shared_data),
+            Err(TpnTreeError::MalformedLinearTree)
+        );
+
+        let mut no_such_axis = linear.clone();
+        no_such_axis.split_axes[0] = 0b10;
+        assert_eq!(
+            TpnTree::from_linear(no_such_axis),
+            Err(TpnTreeError::MalformedLinearTree)
+        );
+
+        let mut no_branching = linear.clone();
+        no_branching.branching[0] = 1;
+        assert_eq!(
+            TpnTree::from_linear(no_branching),
+            Err(TpnTreeError::MalformedLinearTree)
+        );
+
+        let mut outside = linear.clone();
+        outside.split_points[0] = [1.0];
+        assert_eq!(
+            TpnTree::from_linear(outside),
+            Err(TpnTreeError::MalformedLinearTree)
+        );
+
+        let mut short = linear;
+        short.levels.pop();
+        assert_eq!(
+            TpnTree::from_linear(short),
+            Err(TpnTreeError::MalformedLinearTree)
+        );
+    }
+}
@@ -0,0 +1,306 @@
+use super::TpnTree;
+use crate::{errors::TpnTreeError, Coordinates};
+use alloc::vec::Vec;
+
+/// An object with a spatial extent, described by an axis aligned bounding box.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bounded<T, const N: usize> {
+    /// Center of the bounding box.
+    pub center: [f64; N],
+    /// Half the edge lengths of the bounding box along each axis.
+    pub half_size: [f64; N],
+    /// The object itself.
+    pub data: T,
+}
+
+impl<T, const N: usize> Bounded<T, N> {
+    /// Creates a new bounded object.
+    pub fn new(data: T, center: [f64; N], half_size: [f64; N]) -> Self {
+        Self {
+            center,
+            half_size,
+            data,
+        }
+    }
+
+    /// Checks if the bounding box intersects the box given by center and half size.
+    ///
+    /// Touching boxes intersect.
+    pub fn intersects(&self, center: &[f64; N], half_size: &[f64; N]) -> bool {
+        (0..N).all(|i| (self.center[i] - center[i]).abs() <= self.half_size[i] + half_size[i])
+    }
+}
+
+impl<T, const N: usize> Coordinates<N> for Bounded<T, N> {
+    /// The coordinates of a bounded object are the center of its bounding box.
+    fn coordinates(&self) -> &[f64] {
+        &self.center
+    }
+}
+
+/// A helper type to work with objects of spatial extent.
+///
+/// In a loose tree every node bounds its objects with its span multiplied by a looseness factor, commonly 2.0.
+/// An object is stored in the deepest node whose loose bounds fully contain it, which may be an inner node.
+pub type LooseTree<T, const N: usize> = TpnTree<Vec<Bounded<T, N>>, N>;
+
+impl<T, const N: usize> LooseTree<T, N> {
+    /// Returns the looseness factor of the [`TreeConfig`](super::TreeConfig) of the tree, or 1.0 if there is none.
+    pub fn looseness(&self) -> f64 {
+        self.config()
+            .and_then(|config| config.looseness())
+            .unwrap_or(1.0)
+    }
+
+    /// Returns the span of the tree multiplied by its looseness factor.
+    pub fn loose_span(&self) -> [f64; N] {
+        let looseness = self.looseness();
+        let mut loose_span = self.span;
+        for span in loose_span.iter_mut() {
+            *span *= looseness;
+        }
+        loose_span
+    }
+
+    /// Checks if the loose bounds of the tree fully contain the box given by center and half size.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::LooseTree;
+    /// let tight = LooseTree::<(), 2>::root(1.0);
+    /// let loose = LooseTree::<(), 2>::builder().looseness(2.0).build();
+    ///
+    /// assert!(!tight.loosely_contains(&[0.5, 0.5], &[1.0, 1.0]));
+    /// assert!(loose.loosely_contains(&[0.5, 0.5], &[1.0, 1.0]));
+    /// ```
+    pub fn loosely_contains(&self, center: &[f64; N], half_size: &[f64; N]) -> bool {
+        let loose_span = self.loose_span();
+        (0..N).all(|i| {
+            center[i] - half_size[i] >= self.coordinates[i] - loose_span[i]
+                && center[i] + half_size[i] <= self.coordinates[i] + loose_span[i]
+        })
+    }
+
+    /// Inserts an object with extent in the deepest tree whose loose bounds contain it, given the constrains of the `division_condition`.
+    ///
+    /// Uses the configured looseness, or 1.0 if there is none.
+    ///
+    /// The `division condition` determines when a tree divides and moves its objects into its children, where they fit.
+    /// Objects straddling the boundaries of all children stay in the divided tree.
+    /// Errors if the root tree does not span the center of the object or its loose bounds do not contain the object.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::LooseTree;
+    /// let mut tree = LooseTree::<&str, 2>::builder().looseness(2.0).build();
+    ///
+    /// assert!(tree
+    ///     .insert_with_extent("box", [0.5, 0.5], [0.25, 0.25], &|_| false)
+    ///     .is_ok());
+    /// ```
+    pub fn insert_with_extent(
+        &mut self,
+        data: T,
+        center: [f64; N],
+        half_size: [f64; N],
+        division_condition: &dyn Fn(&Self) -> bool,
+    ) -> Result<(), TpnTreeError> {
+        let spans_center = (0..N).all(|i| (center[i] - self.coordinates[i]).abs() <= self.span[i]);
+        if self.is_root() && !(spans_center && self.loosely_contains(&center, &half_size)) {
+            return Err(self.does_not_span(&center));
+        }
+
+        self.insert_bounded(Bounded::new(data, center, half_size), division_condition);
+        Ok(())
+    }
+
+    /// Inserts an object with extent, dividing leaves according to the [`TreeConfig`](super::TreeConfig) of the tree.
+    ///
+    /// A leaf divides once it holds `leaf_capacity` objects, unless it is at `max_depth`.
+    /// Errors just like [`LooseTree::insert_with_extent`].
+    ///
+    /// ```
+    /// # use tpntree::tpntree::LooseTree;
+    /// let mut tree = LooseTree::<&str, 2>::builder().looseness(2.0).build();
+    ///
+    /// assert!(tree.insert_loose("box", [0.5, 0.5], [1.0, 1.0]).is_ok());
+    /// ```
+    pub fn insert_loose(
+        &mut self,
+        data: T,
+        center: [f64; N],
+        half_size: [f64; N],
+    ) -> Result<(), TpnTreeError> {
+        self.insert_with_extent(data, center, half_size, &|tree: &Self| {
+            tree.is_at_capacity()
+        })
+    }
+
+    fn insert_bounded(
+        &mut self,
+        bounded: Bounded<T, N>,
+        division_condition: &dyn Fn(&Self) -> bool,
+    ) {
+        if self.is_leaf() && division_condition(self) && self.divide().is_ok() {
+            for bounded in self.data.take().unwrap_or_default() {
+                self.place(bounded, division_condition);
+            }
+        }
+
+        if self.is_leaf() {
+            self.data.get_or_insert(Vec::new()).push(bounded);
+        } else {
+            self.place(bounded, division_condition);
+        }
+    }
+
+    // moves the object into the child covering its center if it fits there, otherwise keeps it
+    fn place(&mut self, bounded: Bounded<T, N>, division_condition: &dyn Fn(&Self) -> bool) {
+        let index = self.child_index_for(&bounded.center);
+        let child = &mut self.children[index];
+        if child.loosely_contains(&bounded.center, &bounded.half_size) {
+            child.insert_bounded(bounded, division_condition);
+        } else {
+            self.data.get_or_insert(Vec::new()).push(bounded);
+        }
+    }
+
+    /// Returns all objects whose bounding boxes intersect the box given by center and half size.
+    ///
+    /// Trees whose loose bounds do not intersect the box are skipped together with their children.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::LooseTree;
+    /// let mut tree = LooseTree::<&str, 2>::builder().looseness(2.0).build();
+    ///
+    /// tree.insert_with_extent("box", [0.5, 0.5], [0.25, 0.25], &|_| false)
+    ///     .expect("Couldn't insert.");
+    ///
+    /// assert_eq!(tree.find_intersecting(&[0.0, 0.0], &[0.3, 0.3]).len(), 1);
+    /// assert_eq!(tree.find_intersecting(&[-0.5, -0.5], &[0.3, 0.3]).len(), 0);
+    /// ```
+    pub fn find_intersecting(
+        &self,
+        center: &[f64; N],
+        half_size: &[f64; N],
+    ) -> Vec<&Bounded<T, N>> {
+        let mut found = Vec::new();
+        self.find_intersecting_recursive(center, half_size, &mut found);
+        found
+    }
+
+    fn find_intersecting_recursive<'a>(
+        &'a self,
+        center: &[f64; N],
+        half_size: &[f64; N],
+        found: &mut Vec<&'a Bounded<T, N>>,
+    ) {
+        let loose_span = self.loose_span();
+        if !(0..N).all(|i| (self.coordinates[i] - center[i]).abs() <= loose_span[i] + half_size[i])
+        {
+            return;
+        }
+
+        found.extend(
+            self.data
+                .iter()
+                .flatten()
+                .filter(|bounded| bounded.intersects(center, half_size)),
+        );
+
+        for child in &self.children {
+            child.find_intersecting_recursive(center, half_size, found);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tpntree::LooseTree;
+    use crate::TpnTreeError;
+
+    #[test]
+    fn insert_outside_root() {
+        let mut tree = LooseTree::<(), 2>::builder().looseness(2.0).build();
+
+        assert!(matches!(
+            tree.insert_with_extent((), [1.5, 0.0], [0.1, 0.1], &|_| false),
+            Err(TpnTreeError::DoesNotSpan { .. })
+        ));
+        assert!(matches!(
+            tree.insert_with_extent((), [1.0, 0.0], [1.5, 0.1], &|_| false),
+            Err(TpnTreeError::DoesNotSpan { .. })
+        ));
+    }
+
+    #[test]
+    fn small_objects_move_down_large_objects_stay() {
+        let mut tree = LooseTree::<&str, 2>::builder().looseness(2.0).build();
+
+        let division_condition = |tree: &LooseTree<&str, 2>| tree.is_root();
+
+        assert!(tree
+            .insert_with_extent("small", [0.1, 0.1], [0.2, 0.2], &division_condition)
+            .is_ok());
+        assert!(tree
+            .insert_with_extent("large", [0.0, 0.0], [0.9, 0.9], &division_condition)
+            .is_ok());
+
+        assert_eq!(tree.child_count(), 4);
+        assert_eq!(
+            tree.data().map(|vec| vec.iter().map(|b| b.data).collect()),
+            Some(vec!["large"])
+        );
+        assert!(tree
+            .iter_children()
+            .any(|child| child.data().map(|vec| vec[0].data) == Some("small")));
+    }
+
+    #[test]
+    fn straddling_objects_are_redistributed_on_divide() {
+        let mut tree = LooseTree::<usize, 2>::builder().looseness(2.0).build();
+
+        let division_condition =
+            |tree: &LooseTree<usize, 2>| tree.data().is_some_and(|vec| vec.len() >= 2);
+
+        for (i, center) in [[-0.5, -0.5], [0.5, 0.5], [0.0, 0.0]].iter().enumerate() {
+            assert!(tree
+                .insert_with_extent(i, *center, [0.05, 0.05], &division_condition)
+                .is_ok());
+        }
+
+        assert_eq!(tree.child_count(), 4);
+        assert!(tree.data().is_none());
+        assert_eq!(
+            tree.iter_depth_first()
+                .filter_map(|tree| tree.data())
+                .map(|vec| vec.len())
+                .sum::<usize>(),
+            3
+        );
+    }
+
+    #[test]
+    fn find_intersecting_objects() {
+        let mut tree = LooseTree::<usize, 2>::builder().looseness(2.0).build();
+
+        let division_condition = |tree: &LooseTree<usize, 2>| tree.level() < 2;
+
+        assert!(tree
+            .insert_with_extent(0, [0.6, 0.6], [0.1, 0.1], &division_condition)
+            .is_ok());
+        assert!(tree
+            .insert_with_extent(1, [-0.6, -0.6], [0.1, 0.1], &division_condition)
+            .is_ok());
+        assert!(tree
+            .insert_with_extent(2, [0.0, 0.0], [0.8, 0.1], &division_condition)
+            .is_ok());
+
+        let mut found = tree
+            .find_intersecting(&[0.6, 0.1], &[0.1, 0.45])
+            .iter()
+            .map(|bounded| bounded.data)
+            .collect::<Vec<_>>();
+        found.sort_unstable();
+
+        assert_eq!(found, vec![0, 2]);
+    }
+}
@@ -0,0 +1,75 @@
+use alloc::vec::Vec;
+use core::mem::size_of;
+
+use super::SpatialTree;
+
+/// Bytes allocated by a [`SpatialTree`], see [`SpatialTree::memory_usage`].
+///
+/// Memory owned by the items themselves, like their own heap allocations, is not included.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryBreakdown {
+    /// Bytes of the trees themselves, inline in their parent's children or wherever the root lives.
+    pub nodes: usize,
+    /// Bytes of unused capacity of the children vectors.
+    pub child_vectors: usize,
+    /// Bytes allocated for the items of the leaves.
+    pub leaf_data: usize,
+}
+
+impl MemoryBreakdown {
+    /// Returns the sum of all parts.
+    pub fn total(&self) -> usize {
+        self.nodes + self.child_vectors + self.leaf_data
+    }
+}
+
+impl<T, const N: usize> SpatialTree<T, N> {
+    /// Estimates the bytes used by the tree and its descendants from the sizes of their types and the capacities of their vectors.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::Tree3D;
+    /// # use core::mem::size_of;
+    /// let mut tree: Tree3D = Tree3D::root(1.0);
+    /// tree.divide().expect("Couldn't divide.");
+    /// tree.data_mut().get_or_insert_with(Vec::new).reserve_exact(2);
+    ///
+    /// let usage = tree.memory_usage();
+    ///
+    /// assert_eq!(usage.nodes, 9 * size_of::<Tree3D>());
+    /// assert_eq!(usage.leaf_data, 2 * size_of::<[f64; 3]>());
+    /// ```
+    pub fn memory_usage(&self) -> MemoryBreakdown {
+        self.iter_depth_first()
+            .fold(MemoryBreakdown::default(), |mut usage, tree| {
+                usage.nodes += size_of::<Self>();
+                usage.child_vectors +=
+                    (tree.children.capacity() - tree.children.len()) * size_of::<Self>();
+                usage.leaf_data += tree.data.as_ref().map_or(0, Vec::capacity) * size_of::<T>();
+                usage
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tpntree::Tree3D;
+    use std::mem::size_of;
+
+    #[test]
+    fn memory_grows_with_items_and_divisions() {
+        let mut tree = Tree3D::builder().leaf_capacity(2).build();
+        let empty = tree.memory_usage();
+        assert_eq!(empty.nodes, size_of::<Tree3D>());
+        assert_eq!(empty.leaf_data, 0);
+
+        for i in 0..5 {
+            let t = i as f64 / 5.0;
+            tree.insert([t, -t, 0.5 * t]).unwrap();
+        }
+        let usage = tree.memory_usage();
+
+        assert_eq!(usage.nodes, tree.stats().node_count * size_of::<Tree3D>());
+        assert!(usage.leaf_data >= 5 * size_of::<[f64; 3]>());
+        assert_eq!(usage.total(), tree.stats().memory_bytes);
+    }
+}
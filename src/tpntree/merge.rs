@@ -0,0 +1,147 @@
+use super::TpnTree;
+use crate::errors::TpnTreeError;
+
+impl<T, const N: usize> TpnTree<T, N> {
+    /// Overlays the other tree, dividing the tree wherever the other tree is divided and combining the data of both tree by tree.
+    ///
+    /// Where only one of the trees has a tree, `combine` sees the data of the leaf of the other tree covering it instead.
+    /// The function is called for every tree of the result, each tree before its children.
+    /// Errors with [`TpnTreeError::RegionMismatch`] if the trees do not cover the same region,
+    /// or if the [`TreeConfig`](super::TreeConfig) of the tree does not allow dividing as deep as the other tree is divided.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::TpnTree;
+    /// let mut a = TpnTree::<u8, 2>::root(1.0);
+    /// *a.data_mut() = Some(1);
+    ///
+    /// let mut b = TpnTree::<u8, 2>::root(1.0);
+    /// b.divide().expect("Couldn't divide.");
+    /// *b.get_child_mut(0).unwrap().data_mut() = Some(2);
+    ///
+    /// a.merge_with(&b, |a, b| b.or(a).copied()).expect("Same region.");
+    ///
+    /// assert_eq!(a.get_child(0).unwrap().data(), Some(&2));
+    /// assert_eq!(a.get_child(1).unwrap().data(), Some(&1));
+    /// ```
+    pub fn merge_with(
+        &mut self,
+        other: &Self,
+        combine: impl Fn(Option<&T>, Option<&T>) -> Option<T>,
+    ) -> Result<(), TpnTreeError> {
+        if self.coordinates != other.coordinates || self.span != other.span {
+            return Err(TpnTreeError::RegionMismatch {
+                expected: (self.coordinates.to_vec(), self.span.to_vec()),
+                got: (other.coordinates.to_vec(), other.span.to_vec()),
+            });
+        }
+        self.merge_recursive(Ok(other), &combine)
+    }
+
+    // the other side is either a tree or the data of the leaf covering it
+    fn merge_recursive(
+        &mut self,
+        other: Result<&Self, Option<&T>>,
+        combine: &impl Fn(Option<&T>, Option<&T>) -> Option<T>,
+    ) -> Result<(), TpnTreeError> {
+        let (theirs, their_divided) = match other {
+            Ok(tree) => (tree.data.as_ref(), (!tree.is_leaf()).then_some(tree)),
+            Err(data) => (data, None),
+        };
+
+        match their_divided {
+            Some(their_tree) if self.is_leaf() => {
+                self.divide_like(their_tree)?;
+                let own = self.data.take();
+                self.data = combine(own.as_ref(), theirs);
+                for (child, their_child) in self.children.iter_mut().zip(&their_tree.children) {
+                    child.overlay_leaf(own.as_ref(), their_child, combine)?;
+                }
+            }
+            _ => {
+                self.data = combine(self.data.as_ref(), theirs);
+                for (index, child) in self.children.iter_mut().enumerate() {
+                    let their_child = their_divided.map(|tree| &tree.children[index]);
+                    child.merge_recursive(their_child.ok_or(theirs), combine)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // copies the structure of the other tree below a former leaf whose data covers all of it
+    fn overlay_leaf(
+        &mut self,
+        own: Option<&T>,
+        other: &Self,
+        combine: &impl Fn(Option<&T>, Option<&T>) -> Option<T>,
+    ) -> Result<(), TpnTreeError> {
+        self.data = combine(own, other.data.as_ref());
+        if !other.is_leaf() {
+            self.divide_like(other)?;
+            for (child, their_child) in self.children.iter_mut().zip(&other.children) {
+                child.overlay_leaf(own, their_child, combine)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tpntree::{TpnTree, TreeConfig, VisitControl};
+    use crate::TpnTreeError;
+
+    #[test]
+    fn merge_captures_both_structures() {
+        let mut a = TpnTree::<u32, 2>::root(1.0);
+        a.divide().unwrap();
+        a.get_child_mut(3).unwrap().divide().unwrap();
+        a.visit_mut(&mut |tree: &mut TpnTree<u32, 2>| {
+            *tree.data_mut() = Some(1);
+            VisitControl::Continue
+        });
+
+        let mut b = TpnTree::<u32, 2>::root(1.0);
+        b.divide().unwrap();
+        b.get_child_mut(0).unwrap().divide().unwrap();
+        *b.get_child_mut(0)
+            .unwrap()
+            .get_child_mut(2)
+            .unwrap()
+            .data_mut() = Some(10);
+
+        a.merge_with(&b, |a, b| {
+            Some(a.copied().unwrap_or(0) + b.copied().unwrap_or(0))
+        })
+        .unwrap();
+
+        assert_eq!(a.iter_depth_first().count(), 1 + 4 + 4 + 4);
+        // the former leaf at index 0 passed its data to the new children
+        let child = a.get_child(0).unwrap();
+        assert_eq!(child.get_child(2).unwrap().data(), Some(&11));
+        assert_eq!(child.get_child(1).unwrap().data(), Some(&1));
+        // the children of index 3 only exist in a and see the data of the leaf of b covering them
+        assert_eq!(
+            a.get_child(3).unwrap().get_child(0).unwrap().data(),
+            Some(&1)
+        );
+    }
+
+    #[test]
+    fn merge_respects_region_and_config() {
+        let mut a = TpnTree::<(), 2>::root(1.0);
+        assert!(matches!(
+            a.merge_with(&TpnTree::root(2.0), |_, _| None),
+            Err(TpnTreeError::RegionMismatch { .. })
+        ));
+
+        a.set_config(TreeConfig::default().with_max_depth(0));
+        let mut b = TpnTree::<(), 2>::root(1.0);
+        b.divide().unwrap();
+
+        assert_eq!(
+            a.merge_with(&b, |_, _| None),
+            Err(TpnTreeError::MaxDepthExceeded { max_level: 0 })
+        );
+    }
+}
@@ -0,0 +1,163 @@
+use alloc::{collections::BTreeMap, vec::Vec};
+
+use super::TpnTree;
+
+/// The dual graph of the leaves of a tree, with a vertex at the center of every leaf and an edge between every two leaves sharing a face.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DualGraph<const N: usize> {
+    /// Centers of the leaves, in depth first order.
+    pub vertices: Vec<[f64; N]>,
+    /// Pairs of indices into `vertices` of leaves sharing a face, or a part of it for leaves of different size.
+    pub edges: Vec<[usize; 2]>,
+}
+
+/// The leaves of a three dimensional tree as hexahedral cells sharing their corners.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HexMesh {
+    /// Corners of the leaves, each corner appears once.
+    pub vertices: Vec<[f64; 3]>,
+    /// Eight indices into `vertices` per leaf, in depth first order.
+    ///
+    /// Corner i lies above the center of the leaf along axis a if bit a of i is set.
+    pub hexahedra: Vec<[usize; 8]>,
+}
+
+impl<T, const N: usize> TpnTree<T, N> {
+    /// Extracts the dual graph of the leaves.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::TpnTree;
+    /// let mut root = TpnTree::<(), 2>::root(1.0);
+    /// root.divide().expect("Couldn't divide.");
+    ///
+    /// let graph = root.dual_graph();
+    ///
+    /// // four quadrants form a cycle, diagonal ones share no face
+    /// assert_eq!(graph.vertices.len(), 4);
+    /// assert_eq!(graph.edges.len(), 4);
+    /// ```
+    pub fn dual_graph(&self) -> DualGraph<N> {
+        let leaves = self
+            .iter_depth_first()
+            .filter(|tree| tree.is_leaf())
+            .collect::<Vec<_>>();
+        let indices = leaves
+            .iter()
+            .enumerate()
+            .map(|(index, &leaf)| (leaf as *const Self, index))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut graph = DualGraph {
+            vertices: leaves.iter().map(|leaf| leaf.coordinates).collect(),
+            edges: Vec::new(),
+        };
+
+        // every leaf links to neighbors of the same size above it and to all larger neighbors,
+        // smaller neighbors link to it in turn
+        for (index, leaf) in leaves.iter().enumerate() {
+            for axis in 0..N {
+                for direction in [1.0, -1.0] {
+                    let mut center = leaf.coordinates;
+                    center[axis] += direction * 2.0 * leaf.span[axis];
+
+                    let neighbor = match self.find_at_level(&center, leaf.level) {
+                        Some(neighbor) if neighbor.is_leaf() => neighbor,
+                        _ => continue,
+                    };
+                    if neighbor.level < leaf.level || direction > 0.0 {
+                        graph
+                            .edges
+                            .push([index, indices[&(neighbor as *const Self)]]);
+                    }
+                }
+            }
+        }
+        graph
+    }
+}
+
+impl<T> TpnTree<T, 3> {
+    /// Extracts the leaves as hexahedral cells with shared corners.
+    ///
+    /// Corners are shared when their coordinates are exactly equal, which holds for all corners created by dividing.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::TpnTree;
+    /// let mut root = TpnTree::<(), 3>::root(1.0);
+    /// root.divide().expect("Couldn't divide.");
+    ///
+    /// let mesh = root.to_hex_mesh();
+    ///
+    /// assert_eq!(mesh.hexahedra.len(), 8);
+    /// assert_eq!(mesh.vertices.len(), 27);
+    /// ```
+    pub fn to_hex_mesh(&self) -> HexMesh {
+        let mut mesh = HexMesh::default();
+        let mut indices = BTreeMap::new();
+
+        for leaf in self.iter_depth_first().filter(|tree| tree.is_leaf()) {
+            let mut hexahedron = [0; 8];
+            for (i, vertex) in hexahedron.iter_mut().enumerate() {
+                let mut corner = leaf.coordinates;
+                for (axis, coordinate) in corner.iter_mut().enumerate() {
+                    if i & (1 << axis) != 0 {
+                        *coordinate += leaf.span[axis];
+                    } else {
+                        *coordinate -= leaf.span[axis];
+                    }
+                }
+
+                let vertices = &mut mesh.vertices;
+                *vertex = *indices.entry(corner.map(f64::to_bits)).or_insert_with(|| {
+                    vertices.push(corner);
+                    vertices.len() - 1
+                });
+            }
+            mesh.hexahedra.push(hexahedron);
+        }
+        mesh
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tpntree::TpnTree;
+
+    #[test]
+    fn dual_graph_links_leaves_of_different_size() {
+        let mut tree = TpnTree::<(), 2>::root(1.0);
+        assert!(tree.divide().is_ok());
+        assert!(tree.get_child_mut(0).unwrap().divide().is_ok());
+
+        let graph = tree.dual_graph();
+
+        assert_eq!(graph.vertices.len(), 7);
+        // four edges among the small leaves, four between small and large leaves, two among the large leaves
+        assert_eq!(graph.edges.len(), 10);
+        assert!(graph.edges.iter().all(|[a, b]| a != b));
+
+        let mut sorted = graph
+            .edges
+            .iter()
+            .map(|&[a, b]| [a.min(b), a.max(b)])
+            .collect::<Vec<_>>();
+        sorted.sort_unstable();
+        sorted.dedup();
+        assert_eq!(sorted.len(), 10);
+    }
+
+    #[test]
+    fn hex_mesh_shares_corners() {
+        let mut tree = TpnTree::<(), 3>::root(1.0);
+        assert!(tree.divide_to_depth(2).is_ok());
+
+        let mesh = tree.to_hex_mesh();
+
+        assert_eq!(mesh.hexahedra.len(), 64);
+        assert_eq!(mesh.vertices.len(), 125);
+        assert!(mesh
+            .hexahedra
+            .iter()
+            .all(|hexahedron| hexahedron.iter().all(|&index| index < mesh.vertices.len())));
+    }
+}
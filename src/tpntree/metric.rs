@@ -0,0 +1,146 @@
+use alloc::vec::Vec;
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use num_traits::Float;
+
+/// A distance between points used by the proximity queries of [`SpatialTree`](super::SpatialTree).
+///
+/// Implement [`Metric::distance`] for a user-defined metric.
+/// The default [`Metric::distance_to_box`] measures the distance to the point of the box closest along every axis,
+/// which is the correct lower bound for any metric that grows with the difference along each axis.
+///
+/// ```
+/// # use tpntree::tpntree::{Chebyshev, Euclidean, Manhattan, Metric};
+/// let (a, b) = ([0.0, 0.0], [3.0, 4.0]);
+///
+/// assert_eq!(Euclidean.distance(&a, &b), 5.0);
+/// assert_eq!(Manhattan.distance(&a, &b), 7.0);
+/// assert_eq!(Chebyshev.distance(&a, &b), 4.0);
+/// ```
+pub trait Metric {
+    /// Returns the distance between the points.
+    fn distance(&self, a: &[f64], b: &[f64]) -> f64;
+
+    /// Returns the distance from the point to the closest point of the box with the given center and span.
+    ///
+    /// Points inside the box have distance zero.
+    /// Must never exceed the distance to any point inside the box, or queries miss items.
+    fn distance_to_box(&self, coordinates: &[f64], span: &[f64], point: &[f64]) -> f64 {
+        let closest = coordinates
+            .iter()
+            .zip(span)
+            .zip(point)
+            .map(|((coordinate, span), point)| point.clamp(coordinate - span, coordinate + span))
+            .collect::<Vec<_>>();
+        self.distance(&closest, point)
+    }
+}
+
+/// The straight line distance.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Euclidean;
+
+/// The sum of the differences along each axis.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Manhattan;
+
+/// The largest difference along any axis.
+///
+/// Balls of this metric are boxes, so they align with the trees.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Chebyshev;
+
+impl Metric for Euclidean {
+    fn distance(&self, a: &[f64], b: &[f64]) -> f64 {
+        a.iter()
+            .zip(b)
+            .map(|(a, b)| (a - b).powi(2))
+            .sum::<f64>()
+            .sqrt()
+    }
+
+    fn distance_to_box(&self, coordinates: &[f64], span: &[f64], point: &[f64]) -> f64 {
+        gaps(coordinates, span, point)
+            .map(|gap| gap.powi(2))
+            .sum::<f64>()
+            .sqrt()
+    }
+}
+
+impl Metric for Manhattan {
+    fn distance(&self, a: &[f64], b: &[f64]) -> f64 {
+        a.iter().zip(b).map(|(a, b)| (a - b).abs()).sum()
+    }
+
+    fn distance_to_box(&self, coordinates: &[f64], span: &[f64], point: &[f64]) -> f64 {
+        gaps(coordinates, span, point).sum()
+    }
+}
+
+impl Metric for Chebyshev {
+    fn distance(&self, a: &[f64], b: &[f64]) -> f64 {
+        a.iter()
+            .zip(b)
+            .map(|(a, b)| (a - b).abs())
+            .fold(0.0, f64::max)
+    }
+
+    fn distance_to_box(&self, coordinates: &[f64], span: &[f64], point: &[f64]) -> f64 {
+        gaps(coordinates, span, point).fold(0.0, f64::max)
+    }
+}
+
+impl<M: Metric + ?Sized> Metric for &M {
+    fn distance(&self, a: &[f64], b: &[f64]) -> f64 {
+        (**self).distance(a, b)
+    }
+
+    fn distance_to_box(&self, coordinates: &[f64], span: &[f64], point: &[f64]) -> f64 {
+        (**self).distance_to_box(coordinates, span, point)
+    }
+}
+
+// the distance from the point to the box along each axis
+fn gaps<'a>(
+    coordinates: &'a [f64],
+    span: &'a [f64],
+    point: &'a [f64],
+) -> impl Iterator<Item = f64> + 'a {
+    coordinates
+        .iter()
+        .zip(span)
+        .zip(point)
+        .map(|((coordinate, span), point)| ((point - coordinate).abs() - span).max(0.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Chebyshev, Euclidean, Manhattan, Metric};
+
+    // a metric relying on the default box distance
+    struct Scaled;
+
+    impl Metric for Scaled {
+        fn distance(&self, a: &[f64], b: &[f64]) -> f64 {
+            2.0 * Manhattan.distance(a, b)
+        }
+    }
+
+    #[test]
+    fn box_distances_are_lower_bounds() {
+        let (coordinates, span) = ([1.0, -1.0], [0.5, 0.5]);
+        let point = [3.0, 1.0];
+
+        assert_eq!(
+            Euclidean.distance_to_box(&coordinates, &span, &point),
+            1.5f64.hypot(1.5)
+        );
+        assert_eq!(Manhattan.distance_to_box(&coordinates, &span, &point), 3.0);
+        assert_eq!(Chebyshev.distance_to_box(&coordinates, &span, &point), 1.5);
+        assert_eq!(Scaled.distance_to_box(&coordinates, &span, &point), 6.0);
+        assert_eq!(
+            Chebyshev.distance_to_box(&coordinates, &span, &[1.2, -0.9]),
+            0.0
+        );
+    }
+}
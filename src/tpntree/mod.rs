@@ -1,25 +1,130 @@
+mod anisotropic;
+mod arena;
+mod branching;
+mod builder;
+mod bulk;
+mod capacity;
+mod collision;
+mod components;
+#[cfg(feature = "concurrent")]
+mod concurrent;
+mod diff;
+mod downsample;
+mod extract;
+#[cfg(feature = "geo-types")]
+mod geo;
+mod grid;
+mod hilbert;
+mod image;
+mod indexed;
+mod integer;
+mod invariants;
 mod iterators;
+mod key;
+mod layout;
+mod linear;
+mod loose;
+mod memory;
+mod merge;
+mod mesh;
+mod metric;
+#[cfg(feature = "nalgebra")]
 mod nalgebra;
+#[cfg(feature = "ndarray")]
+mod ndarray;
+mod nearest;
+mod occupancy;
+mod off_center;
+mod orthant;
+mod overlap;
+mod path;
+mod periodic;
+#[cfg(feature = "persist")]
+mod persist;
+mod persistent;
+mod prune;
+mod refine;
+mod region;
+mod render;
+mod reroot;
+mod retain;
+#[cfg(feature = "rstar")]
+mod rstar;
+mod sample;
+mod small;
+mod snap;
+mod snapshot;
+mod sparse;
 mod spatial;
+mod split;
+mod stats;
+mod stencil;
+mod swept;
+mod timed;
+mod transform;
+mod visitor;
+mod weighted;
 
-use bitvec::bitvec;
+use alloc::{sync::Arc, vec::Vec};
 
 use crate::errors::TpnTreeError;
+pub use arena::{ArenaTree, NodeId};
+pub use builder::{DuplicatePolicy, TpnTreeBuilder, TreeConfig};
+pub use capacity::Spillover;
+pub use components::NodePath;
+#[cfg(feature = "concurrent")]
+pub use concurrent::ConcurrentSpatialTree;
+pub use diff::{TreeChange, TreeDiff};
+pub use indexed::{IndexedTree, ItemId};
+pub use integer::{GridTransform, IntTpnTree};
+pub use invariants::InvariantViolation;
+pub use key::NodeKey;
+pub use layout::SplitLayout;
+pub use linear::LinearTree;
+pub use loose::{Bounded, LooseTree};
+pub use memory::MemoryBreakdown;
+pub use mesh::{DualGraph, HexMesh};
+pub use metric::{Chebyshev, Euclidean, Manhattan, Metric};
+pub use occupancy::OccupancyTree;
+pub use orthant::{ChildIndex, Orthant, Sign};
+#[cfg(feature = "persist")]
+pub use persist::Persist;
+pub use persistent::PersistentTree;
+pub use region::{Region, RegionNode};
+pub use render::WireframeMesh;
+pub use sample::Interpolation;
+pub use small::{OctTree, QuadTree};
+pub use snapshot::TreeSnapshot;
+pub use sparse::{SparseSpatialTree, SparseTree};
 pub use spatial::SpatialTree;
 pub use spatial::Tree3D;
+pub use split::SplitPolicy;
+pub use stats::TreeStats;
+pub use timed::{TimedTree, Timestamped};
+pub use transform::WorldTransform;
+pub use visitor::{TpnTreeVisitor, TpnTreeVisitorMut, VisitControl};
+pub use weighted::{Weighted, WeightedBin, WeightedTree};
 
 #[derive(Debug, Clone)]
 pub struct TpnTree<T, const N: usize> {
     /// Coordinates of the N-dimensional hyperrectangle center.
-    coordinates: [f64; N],
+    pub(crate) coordinates: [f64; N],
     /// Length of the normals from center of N-dimensional hyperrectangle to its faces.
-    span: [f64; N],
+    pub(crate) span: [f64; N],
     /// Height in tree.
-    level: usize,
-    /// There are zero or 2^N children, one times two per axis.
-    children: Vec<Self>,
+    pub(crate) level: usize,
+    /// There are zero or 2^k children, two per axis the tree is split along.
+    pub(crate) children: Vec<Self>,
+    /// Axes the children split the tree along as bits, all of them if `None`.
+    pub(crate) split_axes: Option<usize>,
+    /// Parts each split axis is divided into.
+    pub(crate) branching: usize,
+    /// Point the children split the tree at, the center if `None`.
+    pub(crate) split_point: Option<[f64; N]>,
     /// Any potential data the tree might hold.
-    data: Option<T>,
+    pub(crate) data: Option<T>,
+    /// Configuration shared by all trees descending from a root built with [`TpnTree::builder`].
+    pub(crate) config: Option<Arc<TreeConfig>>,
 }
 
 impl<T, const N: usize> TpnTree<T, N> {
@@ -52,7 +157,11 @@ impl<T, const N: usize> TpnTree<T, N> {
             span,
             level,
             children: Vec::new(),
+            split_axes: None,
+            branching: 2,
+            split_point: None,
             data: None,
+            config: None,
         }
     }
 
@@ -72,9 +181,41 @@ impl<T, const N: usize> TpnTree<T, N> {
         Self::new([0.0; N], [span; N], 0)
     }
 
+    /// Creates a new TpnTree at level zero covering the hyperrectangle between the min and max corner.
+    ///
+    /// Errors with [`TpnTreeError::InvalidBounds`] if any coordinate of the min corner exceeds the one of the max corner.
+    ///
+    /// # Examples
+    ///
+    /// Here we create a two dimensional TpnTree covering the rectangle from (0.0/0.0) to (4.0/2.0).
+    /// ```
+    /// # use tpntree::tpntree::TpnTree;
+    /// let root = TpnTree::<(), 2>::from_bounds([0.0, 0.0], [4.0, 2.0]).expect("Valid bounds.");
+    ///
+    /// assert_eq!(root.coordinates(), [2.0, 1.0]);
+    /// assert_eq!(root.span(), [2.0, 1.0]);
+    /// ```
+    pub fn from_bounds(min: [f64; N], max: [f64; N]) -> Result<Self, TpnTreeError> {
+        // also rejects NaN
+        if !(0..N).all(|i| min[i] <= max[i]) {
+            return Err(TpnTreeError::InvalidBounds {
+                min: min.to_vec(),
+                max: max.to_vec(),
+            });
+        }
+
+        let mut coordinates = [0.0; N];
+        let mut span = [0.0; N];
+        for i in 0..N {
+            coordinates[i] = (min[i] + max[i]) / 2.0;
+            span[i] = (max[i] - min[i]) / 2.0;
+        }
+        Ok(Self::new(coordinates, span, 0))
+    }
+
     /// Divides the TpnTree into subregions creating new TpnTrees as children.
     ///
-    /// Returns true if division happened, returns false when already divided.
+    /// Errors if the TpnTree has been divided before or is at the max depth of its [`TreeConfig`].
     ///
     /// Each created child has its center moved by half the parents span up or down along the axis.
     /// Every child is equal to one unique combination of such half span moves.
@@ -94,46 +235,153 @@ impl<T, const N: usize> TpnTree<T, N> {
     /// assert_eq!(root.child_count(), 4);
     /// ```
     pub fn divide(&mut self) -> Result<(), TpnTreeError> {
-        if self.is_leaf() {
-            let mut children = Vec::<Self>::new();
-            let mut pattern = bitvec![0; self.coordinates.len()];
-
-            // iterate for 2^N to generate all children
-            for _ in 0..2usize.pow(self.coordinates.len() as u32) {
-                let mut coordinates = self.coordinates;
-                let mut span = self.span;
-                // generate sign pattern from bits
-                for i in 0..self.coordinates.len() {
-                    span[i] = self.span[i] / 2.0;
-                    coordinates[i] += span[i] - self.span[i] * pattern[i] as usize as f64;
-                }
-
-                children.push(Self::new(coordinates, span, self.level + 1));
-
-                let mut carry = pattern.clone();
-                carry.set_elements(0);
-                let mut one = carry.clone();
-                one.set(0, true);
-
-                // "add one" to the pattern, loop to accomodate for carry
-                while one.any() {
-                    carry = pattern.clone() & one.clone();
-                    pattern ^= one;
-                    one = carry.clone();
-                    // push so we can shift
-                    one.push(false);
-                    one.shift_right(1);
-                    // pop to have an overflowing shift
-                    one.pop();
-                }
+        self.divide_along(None, 2, None)
+    }
+
+    // divides the axes given as bits, or all of them, into the given count of parts each, at the split point or the center
+    pub(crate) fn divide_along(
+        &mut self,
+        split_axes: Option<usize>,
+        branching: usize,
+        split_point: Option<[f64; N]>,
+    ) -> Result<(), TpnTreeError> {
+        if let Some(max_level) = self.config().and_then(|config| config.max_depth()) {
+            if self.level >= max_level {
+                return Err(TpnTreeError::MaxDepthExceeded { max_level });
             }
-            self.children = children;
+        }
+
+        if self.is_leaf() {
+            let split_count = split_axes.map_or(N, |axes| axes.count_ones() as usize);
+            let count = branching::child_count(split_count, branching)?;
+            self.split_axes = split_axes;
+            self.branching = branching;
+            self.split_point = split_point;
+            // bit i of the child index selects the lower half along the i-th split axis
+            self.children = (0..count)
+                .map(|index| {
+                    let (coordinates, span) = self.split_child_region(index);
+                    let mut child = Self::new(coordinates, span, self.level + 1);
+                    child.config = self.config.clone();
+                    child
+                })
+                .collect();
             Ok(())
         } else {
             Err(TpnTreeError::CanNotDivide)
         }
     }
 
+    /// Returns the index of the child covering the point, whether the tree is divided or not.
+    ///
+    /// Bit `i` of the index is set if the child lies below the center along axis `i`.
+    /// Trees divided by [`TpnTree::divide_axes`], [`TpnTree::divide_with_branching`] or [`TpnTree::divide_at`] number their children as described there.
+    /// Children are half-open, i.e. a point exactly on the center of an axis belongs to the child above it.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::TpnTree;
+    /// let mut root = TpnTree::<(), 2>::root(1.0);
+    ///
+    /// assert_eq!(root.child_index_for_point(&[0.5, 0.5]), 0);
+    /// assert_eq!(root.child_index_for_point(&[-0.5, 0.5]), 1);
+    /// assert_eq!(root.child_index_for_point(&[0.5, -0.5]), 2);
+    /// assert_eq!(root.child_index_for_point(&[0.0, -0.5]), 2);
+    ///
+    /// root.divide().expect("Couldn't divide.");
+    /// let child = root.get_child(root.child_index_for_point(&[-0.5, 0.5])).unwrap();
+    /// assert_eq!(child.coordinates(), [-0.5, 0.5]);
+    /// ```
+    pub fn child_index_for_point(&self, point: &[f64; N]) -> usize {
+        self.child_index_for(point)
+    }
+
+    pub(crate) fn child_index_for(&self, coordinates: &[f64]) -> usize {
+        if self.is_leaf() {
+            return self.centered_child_index(coordinates);
+        }
+        self.split_child_index(coordinates)
+    }
+
+    // the index of the child covering the coordinates when dividing at the center along all axes
+    pub(crate) fn centered_child_index(&self, coordinates: &[f64]) -> usize {
+        match (N, coordinates.len()) {
+            (2, 2) => small::child_index_2(&self.coordinates, coordinates),
+            (3, 3) => small::child_index_3(&self.coordinates, coordinates),
+            _ => self.child_index_generic(coordinates),
+        }
+    }
+
+    pub(crate) fn child_index_generic(&self, coordinates: &[f64]) -> usize {
+        self.coordinates
+            .iter()
+            .zip(coordinates.iter())
+            .enumerate()
+            .fold(0, |index, (axis, (center, coordinate))| {
+                index | ((coordinate < center) as usize) << axis
+            })
+    }
+
+    /// Returns the coordinates and span the child at the index has or would have after division.
+    ///
+    /// A divided tree reports the regions of its actual children, wherever it has been split.
+    /// A leaf reports the regions [`TpnTree::divide`] would create.
+    ///
+    /// Panics if the index is not below the child count of a divided tree, or not below 2^N for a leaf.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::TpnTree;
+    /// let mut root = TpnTree::<(), 2>::root(1.0);
+    ///
+    /// assert_eq!(root.child_region(2), ([0.5, -0.5], [0.5, 0.5]));
+    ///
+    /// root.divide_at([0.5, 0.5]).expect("Couldn't divide.");
+    /// assert_eq!(root.child_region(2), ([0.75, -0.25], [0.25, 0.75]));
+    /// ```
+    pub fn child_region(&self, index: usize) -> ([f64; N], [f64; N]) {
+        if !self.is_leaf() {
+            assert!(
+                index < self.children.len(),
+                "Expected child index below {}, got {}.",
+                self.children.len(),
+                index
+            );
+            return self.split_child_region(index);
+        }
+        self.centered_child_region(index)
+    }
+
+    // the region of the child at the index when dividing at the center along all axes
+    pub(crate) fn centered_child_region(&self, index: usize) -> ([f64; N], [f64; N]) {
+        assert!(
+            N >= usize::BITS as usize || index < 1 << N,
+            "Expected child index below 2^{}, got {}.",
+            N,
+            index
+        );
+        let mut coordinates = self.coordinates;
+        let mut span = self.span;
+        match N {
+            2 => small::child_region_2(&mut coordinates, &mut span, index),
+            3 => small::child_region_3(&mut coordinates, &mut span, index),
+            _ => return self.child_region_generic(index),
+        }
+        (coordinates, span)
+    }
+
+    pub(crate) fn child_region_generic(&self, index: usize) -> ([f64; N], [f64; N]) {
+        let mut coordinates = self.coordinates;
+        let mut span = self.span;
+        let orthant = Orthant::<N>(index);
+        for i in 0..N {
+            span[i] = self.span[i] / 2.0;
+            match orthant.sign(i) {
+                Sign::Positive => coordinates[i] += span[i],
+                Sign::Negative => coordinates[i] -= span[i],
+            }
+        }
+        (coordinates, span)
+    }
+
     /// Get a reference to a child TpnTree if it exists.
     pub fn get_child(&self, index: usize) -> Option<&Self> {
         self.children.get(index)
@@ -143,11 +391,94 @@ impl<T, const N: usize> TpnTree<T, N> {
         self.children.get_mut(index)
     }
 
+    /// Removes the child at the index and returns it together with its descendants.
+    ///
+    /// An empty leaf covering the same region takes its place, so the tree keeps all its children.
+    /// Returns `None` if there is no child at the index.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::TpnTree;
+    /// let mut root = TpnTree::<(), 2>::root(1.0);
+    /// root.divide().expect("Couldn't divide.");
+    /// root.get_child_mut(0).unwrap().divide().expect("Couldn't divide.");
+    ///
+    /// let child = root.take_child(0).expect("Child exists.");
+    ///
+    /// assert!(!child.is_leaf());
+    /// assert!(root.get_child(0).unwrap().is_leaf());
+    /// ```
+    pub fn take_child(&mut self, index: usize) -> Option<Self> {
+        let child = self.children.get_mut(index)?;
+        let empty = {
+            let mut empty = Self::new(child.coordinates, child.span, child.level);
+            empty.config = child.config.clone();
+            empty
+        };
+        Some(core::mem::replace(child, empty))
+    }
+
+    /// Replaces the child at the index with the subtree.
+    ///
+    /// The subtree must cover exactly the region of the child it replaces.
+    /// Levels of the subtree are adjusted to its new position and they share the configuration of the tree.
+    /// Errors if there is no child at the index or the regions do not match, the tree is left unchanged then.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::TpnTree;
+    /// let mut root = TpnTree::<(), 2>::root(1.0);
+    /// root.divide().expect("Couldn't divide.");
+    ///
+    /// let mut child = root.take_child(1).expect("Child exists.");
+    /// child.divide().expect("Couldn't divide.");
+    ///
+    /// assert!(root.attach_child(1, child.clone()).is_ok());
+    /// assert_eq!(root.get_child(1).unwrap().child_count(), 4);
+    /// assert!(root.attach_child(0, child).is_err());
+    /// ```
+    pub fn attach_child(&mut self, index: usize, mut subtree: Self) -> Result<(), TpnTreeError> {
+        let child = self
+            .children
+            .get_mut(index)
+            .ok_or(TpnTreeError::NoSuchChild { index })?;
+
+        if child.coordinates != subtree.coordinates || child.span != subtree.span {
+            return Err(TpnTreeError::RegionMismatch {
+                expected: (child.coordinates.to_vec(), child.span.to_vec()),
+                got: (subtree.coordinates.to_vec(), subtree.span.to_vec()),
+            });
+        }
+
+        subtree.set_level(child.level);
+        subtree.share_config(self.config.as_ref());
+        *child = subtree;
+        Ok(())
+    }
+
+    // moves the tree and its descendants to the level
+    pub(crate) fn set_level(&mut self, level: usize) {
+        self.level = level;
+        for child in &mut self.children {
+            child.set_level(level + 1);
+        }
+    }
+
     /// Returns the count of direct children.
     pub fn child_count(&self) -> usize {
         self.children.len()
     }
 
+    /// Returns the direct children, ordered by their [`Orthant`], or an empty slice for a leaf.
+    pub fn children(&self) -> &[Self] {
+        &self.children
+    }
+
+    /// Returns the direct children by mutable reference, ordered by their [`Orthant`].
+    ///
+    /// Reordering the children breaks the tree, see [`TpnTree::check_invariants`].
+    pub fn children_mut(&mut self) -> &mut [Self] {
+        &mut self.children
+    }
+
     /// Iterates all direct children by reference.
     pub fn iter_children(&self) -> impl Iterator<Item = &Self> {
         self.children.iter()
@@ -215,10 +546,33 @@ impl<T, const N: usize> TpnTree<T, N> {
     }
 }
 
+/// Creates a root with a span of 1.0 along every axis, like [`TpnTree::root`].
+impl<T, const N: usize> Default for TpnTree<T, N> {
+    fn default() -> Self {
+        Self::root(1.0)
+    }
+}
+
+/// Trees are equal if they cover the same region at the same level, hold equal data, are split the same way and have equal children.
+///
+/// The [`TreeConfig`] does not take part, so a tree built with [`TpnTree::builder`] equals one created by [`TpnTree::root`].
+/// The split is compared by [`TpnTree::split_layout`], as it decides which child a point belongs to even where the regions of the children agree.
+impl<T: PartialEq, const N: usize> PartialEq for TpnTree<T, N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.coordinates == other.coordinates
+            && self.span == other.span
+            && self.level == other.level
+            && self.data == other.data
+            && self.split_layout() == other.split_layout()
+            && self.children == other.children
+    }
+}
+
 #[cfg(test)]
 #[allow(clippy::float_cmp)]
 mod tests {
     use super::TpnTree;
+    use crate::TpnTreeError;
 
     #[test]
     pub fn divide_into_subregions_dim_1() {
@@ -285,6 +639,154 @@ mod tests {
         assert!(root.divide().is_err());
     }
 
+    #[test]
+    pub fn child_regions_match_divided_children() {
+        let mut root = TpnTree::<(), 3>::new([1.0, 2.0, 3.0], [1.0, 2.0, 4.0], 0);
+
+        assert!(root.divide().is_ok());
+
+        for (index, child) in root.iter_children().enumerate() {
+            assert_eq!(
+                root.child_region(index),
+                (child.coordinates(), child.span())
+            );
+            assert_eq!(root.child_index_for_point(&child.coordinates()), index);
+        }
+    }
+
+    #[test]
+    pub fn child_regions_match_split_children() {
+        let mut off_center = TpnTree::<(), 2>::root(1.0);
+        let mut anisotropic = TpnTree::<(), 3>::root(1.0);
+        let mut branching = TpnTree::<(), 2>::root(1.0);
+
+        assert!(off_center.divide_at([0.25, -0.5]).is_ok());
+        assert!(anisotropic.divide_axes(&[1]).is_ok());
+        assert!(branching.divide_with_branching(3).is_ok());
+
+        for (index, child) in off_center.iter_children().enumerate() {
+            assert_eq!(
+                off_center.child_region(index),
+                (child.coordinates(), child.span())
+            );
+        }
+        for (index, child) in anisotropic.iter_children().enumerate() {
+            assert_eq!(
+                anisotropic.child_region(index),
+                (child.coordinates(), child.span())
+            );
+        }
+        for (index, child) in branching.iter_children().enumerate() {
+            assert_eq!(
+                branching.child_region(index),
+                (child.coordinates(), child.span())
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    pub fn child_region_beyond_split_children() {
+        let mut root = TpnTree::<(), 2>::root(1.0);
+        root.divide_axes(&[0]).unwrap();
+
+        root.child_region(2);
+    }
+
+    #[test]
+    #[should_panic]
+    pub fn child_region_out_of_range() {
+        let root = TpnTree::<(), 2>::root(1.0);
+
+        root.child_region(4);
+    }
+
+    #[test]
+    pub fn from_bounds_matches_corners() {
+        let root = TpnTree::<(), 3>::from_bounds([-1.0, 2.0, 0.0], [3.0, 2.0, 1.0]).unwrap();
+
+        assert_eq!(root.min_corner(), [-1.0, 2.0, 0.0]);
+        assert_eq!(root.max_corner(), [3.0, 2.0, 1.0]);
+        assert!(root.is_root());
+
+        assert_eq!(
+            TpnTree::<(), 2>::from_bounds([1.0, 0.0], [0.0, 1.0]),
+            Err(TpnTreeError::InvalidBounds {
+                min: vec![1.0, 0.0],
+                max: vec![0.0, 1.0]
+            })
+        );
+        assert!(TpnTree::<(), 1>::from_bounds([f64::NAN], [1.0]).is_err());
+        assert_eq!(TpnTree::<(), 2>::default(), TpnTree::root(1.0));
+    }
+
+    #[test]
+    pub fn equality_ignores_config() {
+        let mut built = TpnTree::<u8, 2>::builder()
+            .max_depth(3)
+            .leaf_capacity(2)
+            .build();
+        let mut plain = TpnTree::<u8, 2>::root(1.0);
+
+        assert_eq!(built, plain);
+
+        assert!(built.divide().is_ok());
+        assert_ne!(built, plain);
+        assert!(plain.divide().is_ok());
+        assert_eq!(built, plain);
+
+        built.children[0].data = Some(1);
+        assert_ne!(built, plain);
+    }
+
+    #[test]
+    pub fn graft_subtree_between_trees() {
+        let mut source = TpnTree::<u8, 2>::root(1.0);
+        let mut target = TpnTree::<u8, 2>::builder().span([1.0, 1.0]).build();
+
+        assert!(source.divide().is_ok());
+        assert!(target.divide().is_ok());
+
+        // grow a subtree covering the region of a child outside of any tree
+        let (coordinates, span) = source.child_region(2);
+        let mut subtree = TpnTree::new(coordinates, span, 5);
+        subtree.data = Some(7);
+        assert!(subtree.divide().is_ok());
+
+        assert!(source.attach_child(2, subtree).is_ok());
+        let moved = source.take_child(2).unwrap();
+        assert_eq!(source.get_child(2).map(|child| child.is_leaf()), Some(true));
+
+        assert!(target.attach_child(2, moved).is_ok());
+        let grafted = target.get_child(2).unwrap();
+        assert_eq!(grafted.data(), Some(&7));
+        assert!(grafted.iter_children().all(|child| child.level() == 2));
+        assert!(grafted
+            .iter_depth_first()
+            .all(|tree| tree.config() == target.config()));
+    }
+
+    #[test]
+    pub fn attach_child_validates() {
+        let mut root = TpnTree::<(), 2>::root(1.0);
+
+        assert_eq!(
+            root.attach_child(0, TpnTree::new([0.5, 0.5], [0.5, 0.5], 1)),
+            Err(TpnTreeError::NoSuchChild { index: 0 })
+        );
+
+        assert!(root.divide().is_ok());
+
+        assert_eq!(
+            root.attach_child(0, TpnTree::new([0.5, 0.5], [0.25, 0.25], 1)),
+            Err(TpnTreeError::RegionMismatch {
+                expected: (vec![0.5, 0.5], vec![0.5, 0.5]),
+                got: (vec![0.5, 0.5], vec![0.25, 0.25])
+            })
+        );
+        assert!(root.take_child(4).is_none());
+    }
+
     #[test]
     pub fn get_adjacent_trees_dimension_one() {
         let root = TpnTree::<(), 1>::root(1.0);
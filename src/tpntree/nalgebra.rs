@@ -1,41 +1,89 @@
-use nalgebra::DVector;
+use nalgebra::{Point, SVector};
 
 use super::TpnTree;
+use crate::Coordinates;
 
-#[cfg(feature = "nalgebra")]
-impl<const N: usize> TpnTree<f64, N> {
-    /// Calculate the variance of TpnTrees with f64 data.
-    pub fn variance(&self) -> f64 {
-        DVector::from_iterator(
-            self.children.len(),
-            self.children.iter().map(|c| c.data.unwrap_or(0.0)),
-        )
-        .variance()
+impl<const N: usize> Coordinates<N> for Point<f64, N> {
+    /// Implementation for nalgebra points of dimension N.
+    fn coordinates(&self) -> &[f64] {
+        self.coords.as_slice()
+    }
+}
+
+impl<const N: usize> Coordinates<N> for SVector<f64, N> {
+    /// Implementation for nalgebra vectors of dimension N.
+    fn coordinates(&self) -> &[f64] {
+        self.as_slice()
+    }
+}
+
+impl<T, const N: usize> TpnTree<T, N> {
+    /// Creates a new TpnTree from a nalgebra point as center and a nalgebra vector as span.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::TpnTree;
+    /// use nalgebra::{Point2, Vector2};
+    ///
+    /// let root = TpnTree::<(), 2>::from_nalgebra(Point2::new(1.0, 1.0), Vector2::new(2.0, 0.5), 0);
+    ///
+    /// assert_eq!(root.coordinates(), [1.0, 1.0]);
+    /// assert_eq!(root.span(), [2.0, 0.5]);
+    /// ```
+    pub fn from_nalgebra(center: Point<f64, N>, span: SVector<f64, N>, level: usize) -> Self {
+        let mut coordinates = [0.0; N];
+        coordinates.copy_from_slice(center.coords.as_slice());
+        let mut spans = [0.0; N];
+        spans.copy_from_slice(span.as_slice());
+        Self::new(coordinates, spans, level)
+    }
+
+    /// Returns the center of the TpnTree as nalgebra point.
+    pub fn center_point(&self) -> Point<f64, N> {
+        Point::from(self.coordinates)
+    }
+
+    /// Returns the span of the TpnTree as nalgebra vector.
+    pub fn span_vector(&self) -> SVector<f64, N> {
+        SVector::from(self.span)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::tpntree::TpnTree;
+    use nalgebra::{Point3, Vector3};
+
+    use crate::tpntree::{SpatialTree, TpnTree};
 
     #[test]
-    fn calculate_variance_alone() {
-        let tree = TpnTree::<f64, 2>::root(1.0);
+    fn construct_from_and_convert_to_nalgebra() {
+        let tree = TpnTree::<(), 3>::from_nalgebra(
+            Point3::new(1.0, 2.0, 3.0),
+            Vector3::new(0.5, 0.5, 0.5),
+            0,
+        );
 
-        assert!(tree.variance() < f64::EPSILON);
+        assert_eq!(tree.center_point(), Point3::new(1.0, 2.0, 3.0));
+        assert_eq!(tree.span_vector(), Vector3::new(0.5, 0.5, 0.5));
     }
 
     #[test]
-    fn calculate_variance_with_children() {
-        let mut tree = TpnTree::<f64, 2>::root(1.0);
+    fn insert_and_find_nalgebra_points() {
+        let mut tree = SpatialTree::<Point3<f64>, 3>::root(1.0);
 
-        assert!(tree.divide().is_ok());
+        let division_condition = |tree: &SpatialTree<Point3<f64>, 3>| tree.data().is_some();
 
-        for (i, c) in tree.iter_children_mut().enumerate() {
-            c.data = Some(i as f64)
-        }
+        assert!(tree
+            .insert_by_coordinates(Point3::new(0.5, 0.5, 0.5), &division_condition)
+            .is_ok());
+        assert!(tree
+            .insert_by_coordinates(Point3::new(-0.5, -0.5, -0.5), &division_condition)
+            .is_ok());
 
-        // population variance of 0,1,2,3
-        assert!((tree.variance() - 1.25).abs() < f64::EPSILON);
+        assert_eq!(
+            tree.find_by_coordinates(&Point3::new(0.7, 0.7, 0.7))
+                .ok()
+                .and_then(|tree| tree.data()),
+            Some(&vec![Point3::new(0.5, 0.5, 0.5)])
+        );
     }
 }
@@ -0,0 +1,45 @@
+use alloc::vec::Vec;
+use ndarray::{ArrayBase, ArrayD, Data, IxDyn};
+
+use super::TpnTree;
+use crate::errors::TpnTreeError;
+
+impl<const N: usize> TpnTree<f64, N> {
+    /// Builds a tree from an N-dimensional array, dividing every region whose variance exceeds `tolerance`, see [`TpnTree::from_grid`].
+    ///
+    /// Errors with [`TpnTreeError::DimensionMismatch`] if the array does not have N dimensions.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::TpnTree;
+    /// use ndarray::{ArrayD, IxDyn};
+    ///
+    /// let array = ArrayD::from_shape_fn(IxDyn(&[4, 4]), |index| (index[0] / 2) as f64);
+    ///
+    /// let tree = TpnTree::<f64, 2>::from_ndarray(&array, 0.0).expect("Couldn't build from array.");
+    ///
+    /// assert_eq!(tree.child_count(), 4);
+    /// assert_eq!(tree.to_ndarray([4, 4]), array);
+    /// ```
+    pub fn from_ndarray<S: Data<Elem = f64>>(
+        array: &ArrayBase<S, IxDyn>,
+        tolerance: f64,
+    ) -> Result<Self, TpnTreeError> {
+        if array.ndim() != N {
+            return Err(TpnTreeError::DimensionMismatch {
+                expected: N,
+                got: array.ndim(),
+            });
+        }
+        let mut shape = [0; N];
+        shape.copy_from_slice(array.shape());
+        // iterating an array visits its elements in logical row-major order, whatever its memory layout
+        let values = array.iter().copied().collect::<Vec<_>>();
+        Self::from_grid(shape, &values, tolerance)
+    }
+
+    /// Samples the data of the leaves onto a dense array with `resolution` cells along each axis, see [`TpnTree::to_grid`].
+    pub fn to_ndarray(&self, resolution: [usize; N]) -> ArrayD<f64> {
+        ArrayD::from_shape_vec(IxDyn(&resolution), self.to_grid(resolution))
+            .expect("The grid holds one value per cell.")
+    }
+}
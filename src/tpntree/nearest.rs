@@ -0,0 +1,402 @@
+use super::{Euclidean, Metric, SpatialTree};
+use crate::Coordinates;
+use alloc::{vec, vec::Vec};
+
+impl<T: Coordinates<N>, const N: usize> SpatialTree<T, N> {
+    /// Returns the `k` items closest to the point in ascending order of distance.
+    ///
+    /// Trees farther away than the k-th closest item found so far are skipped together with their children.
+    /// Items at equal distance appear in the order they are found in.
+    /// Along periodic axes distances are measured to the closest image, see [`TreeConfig::with_periodic_axes`](super::TreeConfig::with_periodic_axes).
+    ///
+    /// ```
+    /// # use tpntree::tpntree::Tree3D;
+    /// let mut tree = Tree3D::root(1.0);
+    /// for data in [[0.5, 0.5, 0.5], [-0.5, 0.0, 0.0], [0.1, 0.1, 0.1]] {
+    ///     tree.insert_by_coordinates(data, &|tree| tree.data().is_some()).expect("Couldn't insert.");
+    /// }
+    ///
+    /// assert_eq!(
+    ///     tree.nearest_neighbors(&[0.2, 0.2, 0.2], 2),
+    ///     vec![&[0.1, 0.1, 0.1], &[0.5, 0.5, 0.5]]
+    /// );
+    /// ```
+    pub fn nearest_neighbors(&self, point: &[f64; N], k: usize) -> Vec<&T> {
+        self.nearest_neighbors_with_distance(point, k)
+            .into_iter()
+            .map(|(_, data)| data)
+            .collect()
+    }
+
+    /// Returns the `k` items closest to the point together with their distance, in ascending order of distance.
+    ///
+    /// See [`SpatialTree::nearest_neighbors`].
+    pub fn nearest_neighbors_with_distance(&self, point: &[f64; N], k: usize) -> Vec<(f64, &T)> {
+        self.nearest_neighbors_by(point, k, &Euclidean)
+    }
+
+    /// Returns the `k` items closest to the point under the metric together with their distance, in ascending order of distance.
+    ///
+    /// See [`SpatialTree::nearest_neighbors`].
+    ///
+    /// ```
+    /// # use tpntree::tpntree::{Chebyshev, Manhattan, Tree3D};
+    /// let mut tree = Tree3D::root(1.0);
+    /// for data in [[0.6, 0.0, 0.0], [0.4, 0.4, 0.0]] {
+    ///     tree.insert_by_coordinates(data, &|_| false).expect("Couldn't insert.");
+    /// }
+    ///
+    /// assert_eq!(tree.nearest_neighbors_by(&[0.0; 3], 1, &Manhattan)[0].1, &[0.6, 0.0, 0.0]);
+    /// assert_eq!(tree.nearest_neighbors_by(&[0.0; 3], 1, &Chebyshev)[0].1, &[0.4, 0.4, 0.0]);
+    /// ```
+    pub fn nearest_neighbors_by(
+        &self,
+        point: &[f64; N],
+        k: usize,
+        metric: &impl Metric,
+    ) -> Vec<(f64, &T)> {
+        let mut found = Vec::new();
+        if k > 0 {
+            self.collect_nearest(point, k, f64::INFINITY, &self.periodic(metric), &mut found);
+        }
+        found
+    }
+
+    /// Returns the item closest to the point together with its distance.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::Tree3D;
+    /// let mut tree = Tree3D::root(1.0);
+    /// tree.insert_by_coordinates([0.0, 0.0, 1.0], &|_| false).expect("Couldn't insert.");
+    ///
+    /// assert_eq!(tree.nearest_one(&[0.0, 0.0, 0.0]), Some((1.0, &[0.0, 0.0, 1.0])));
+    /// ```
+    pub fn nearest_one(&self, point: &[f64; N]) -> Option<(f64, &T)> {
+        self.nearest_neighbors_with_distance(point, 1).pop()
+    }
+
+    /// Returns the `k` items closest to each query point together with their distance, in ascending order of distance.
+    ///
+    /// The results are in the order of the queries and equal those of [`SpatialTree::nearest_neighbors_with_distance`].
+    /// Queries are answered in the depth first order of the leaves covering them.
+    /// Each search is bounded from the start by the distance to the neighbors of the query before, so nearby queries skip most of the tree.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::Tree3D;
+    /// let mut tree = Tree3D::root(1.0);
+    /// for data in [[0.5, 0.5, 0.5], [-0.5, 0.0, 0.0]] {
+    ///     tree.insert_by_coordinates(data, &|_| false).expect("Couldn't insert.");
+    /// }
+    ///
+    /// let results = tree.batch_nearest_neighbors(&[[1.0, 1.0, 1.0], [-1.0, 0.0, 0.0]], 1);
+    ///
+    /// assert_eq!(results[0][0].1, &[0.5, 0.5, 0.5]);
+    /// assert_eq!(results[1][0].1, &[-0.5, 0.0, 0.0]);
+    /// ```
+    pub fn batch_nearest_neighbors(&self, queries: &[[f64; N]], k: usize) -> Vec<Vec<(f64, &T)>> {
+        let mut results = vec![Vec::new(); queries.len()];
+        if k == 0 {
+            return results;
+        }
+        let metric = self.periodic(&Euclidean);
+
+        let mut order = (0..queries.len()).collect::<Vec<_>>();
+        order.sort_by_cached_key(|&index| self.leaf_path(&queries[index]));
+
+        let mut previous = Vec::with_capacity(k);
+        for index in order {
+            let point = &queries[index];
+            // the k neighbors of the previous query are k items within this distance
+            let bound = if previous.len() == k {
+                previous
+                    .iter()
+                    .map(|data: &&T| metric.distance(data.coordinates(), point))
+                    .fold(0.0, f64::max)
+            } else {
+                f64::INFINITY
+            };
+
+            let mut found = Vec::with_capacity(k);
+            self.collect_nearest(point, k, bound, &metric, &mut found);
+
+            previous.clear();
+            previous.extend(found.iter().map(|&(_, data)| data));
+            results[index] = found;
+        }
+        results
+    }
+
+    /// Like [`SpatialTree::batch_nearest_neighbors`], but answers chunks of the queries in parallel on the rayon thread pool.
+    #[cfg(feature = "rayon")]
+    pub fn par_batch_nearest_neighbors(&self, queries: &[[f64; N]], k: usize) -> Vec<Vec<(f64, &T)>>
+    where
+        T: Sync,
+    {
+        use rayon::prelude::*;
+
+        let threads = rayon::current_num_threads();
+        let chunk_size = ((queries.len() + threads - 1) / threads).max(1);
+
+        queries
+            .par_chunks(chunk_size)
+            .map(|chunk| self.batch_nearest_neighbors(chunk, k))
+            .collect::<Vec<_>>()
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+
+    // child indices leading to the leaf covering the point, their order is the depth first order of the leaves
+    fn leaf_path(&self, point: &[f64; N]) -> Vec<usize> {
+        let mut path = Vec::new();
+        let mut tree = self;
+        while !tree.is_leaf() {
+            let index = tree.child_index_for(point);
+            path.push(index);
+            tree = &tree.children[index];
+        }
+        path
+    }
+
+    // keeps the k closest items in found, sorted by distance, ignoring items farther away than the bound
+    fn collect_nearest<'a>(
+        &'a self,
+        point: &[f64; N],
+        k: usize,
+        bound: f64,
+        metric: &impl Metric,
+        found: &mut Vec<(f64, &'a T)>,
+    ) {
+        for data in self.data.iter().flatten() {
+            let distance = metric.distance(data.coordinates(), point);
+            if distance <= bound && (found.len() < k || distance < found[k - 1].0) {
+                let index = found.partition_point(|(other, _)| *other <= distance);
+                found.insert(index, (distance, data));
+                found.truncate(k);
+            }
+        }
+
+        let mut children = self
+            .children
+            .iter()
+            .map(|child| (child.distance_by(point, metric), child))
+            .collect::<Vec<_>>();
+        children.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+
+        for (distance, child) in children {
+            if distance > bound || (found.len() == k && distance > found[k - 1].0) {
+                break;
+            }
+            child.collect_nearest(point, k, bound, metric, found);
+        }
+    }
+
+    /// Returns all items within the radius around the point, including its boundary, in ascending order of distance.
+    ///
+    /// Along periodic axes the radius wraps around the tree, like with [`SpatialTree::nearest_neighbors`].
+    ///
+    /// ```
+    /// # use tpntree::tpntree::Tree3D;
+    /// let mut tree = Tree3D::root(1.0);
+    /// for data in [[0.5, 0.5, 0.5], [-0.5, 0.0, 0.0], [0.1, 0.1, 0.1]] {
+    ///     tree.insert_by_coordinates(data, &|tree| tree.data().is_some()).expect("Couldn't insert.");
+    /// }
+    ///
+    /// assert_eq!(tree.within_radius(&[0.0, 0.0, 0.0], 0.5), vec![&[0.1, 0.1, 0.1], &[-0.5, 0.0, 0.0]]);
+    /// ```
+    pub fn within_radius(&self, point: &[f64; N], radius: f64) -> Vec<&T> {
+        self.within_radius_with_distance(point, radius)
+            .into_iter()
+            .map(|(_, data)| data)
+            .collect()
+    }
+
+    /// Returns all items within the radius around the point together with their distance, in ascending order of distance.
+    ///
+    /// See [`SpatialTree::within_radius`].
+    pub fn within_radius_with_distance(&self, point: &[f64; N], radius: f64) -> Vec<(f64, &T)> {
+        self.within_radius_by(point, radius, &Euclidean)
+    }
+
+    /// Returns all items within the radius around the point under the metric together with their distance, in ascending order of distance.
+    ///
+    /// With [`Chebyshev`](super::Chebyshev) the ball is a box, so whole trees are either fully inside or outside of it near its boundary.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::{Chebyshev, Tree3D};
+    /// let mut tree = Tree3D::root(1.0);
+    /// for data in [[0.5, 0.5, 0.5], [-0.5, 0.0, 0.0]] {
+    ///     tree.insert_by_coordinates(data, &|_| false).expect("Couldn't insert.");
+    /// }
+    ///
+    /// assert_eq!(tree.within_radius_by(&[0.0; 3], 0.5, &Chebyshev).len(), 2);
+    /// ```
+    pub fn within_radius_by(
+        &self,
+        point: &[f64; N],
+        radius: f64,
+        metric: &impl Metric,
+    ) -> Vec<(f64, &T)> {
+        let mut found = Vec::new();
+        self.collect_within(point, radius, &self.periodic(metric), &mut found);
+        found.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+        found
+    }
+
+    fn collect_within<'a>(
+        &'a self,
+        point: &[f64; N],
+        radius: f64,
+        metric: &impl Metric,
+        found: &mut Vec<(f64, &'a T)>,
+    ) {
+        if self.distance_by(point, metric) > radius {
+            return;
+        }
+
+        found.extend(
+            self.data
+                .iter()
+                .flatten()
+                .map(|data| (metric.distance(data.coordinates(), point), data))
+                .filter(|(distance, _)| *distance <= radius),
+        );
+
+        for child in &self.children {
+            child.collect_within(point, radius, metric, found);
+        }
+    }
+
+    // lower bound of the distance from the point to anything in the tree
+    fn distance_by(&self, point: &[f64; N], metric: &impl Metric) -> f64 {
+        metric.distance_to_box(&self.coordinates, &self.span, point)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tpntree::{Chebyshev, Euclidean, Manhattan, Metric, Tree3D};
+
+    fn distance(a: &[f64], b: &[f64]) -> f64 {
+        Euclidean.distance(a, b)
+    }
+
+    fn tree_with_points() -> (Tree3D, Vec<[f64; 3]>) {
+        let points = (0..300)
+            .map(|i| {
+                let t = i as f64 * 0.61;
+                [t.sin(), (t * 1.7).cos(), (t * 0.3).sin()]
+            })
+            .collect::<Vec<_>>();
+        let mut tree = Tree3D::builder().leaf_capacity(4).max_depth(8).build();
+        tree.extend(points.clone());
+        (tree, points)
+    }
+
+    #[test]
+    fn nearest_neighbors_match_brute_force() {
+        let (tree, mut points) = tree_with_points();
+        let query = [0.2, -0.3, 0.1];
+
+        points.sort_by(|a, b| distance(a, &query).total_cmp(&distance(b, &query)));
+
+        let found = tree.nearest_neighbors_with_distance(&query, 10);
+        assert_eq!(found.len(), 10);
+        for ((distance_found, data), expected) in found.iter().zip(&points) {
+            assert_eq!(*data, expected);
+            assert!((distance_found - distance(expected, &query)).abs() < 1e-12);
+        }
+        assert_eq!(
+            tree.nearest_one(&query).map(|(_, data)| data),
+            Some(&points[0])
+        );
+        assert!(tree.nearest_neighbors(&query, 0).is_empty());
+        assert_eq!(tree.nearest_neighbors(&query, 1000).len(), 300);
+    }
+
+    #[test]
+    fn radius_query_matches_brute_force() {
+        let (tree, points) = tree_with_points();
+        let query = [-0.5, 0.5, 0.0];
+
+        let found = tree.within_radius_with_distance(&query, 0.4);
+
+        assert_eq!(
+            found.len(),
+            points
+                .iter()
+                .filter(|point| distance(*point, &query) <= 0.4)
+                .count()
+        );
+        assert!(found.windows(2).all(|pair| pair[0].0 <= pair[1].0));
+        assert!(Tree3D::<[f64; 3]>::root(1.0).nearest_one(&query).is_none());
+    }
+
+    #[test]
+    fn batch_queries_match_single_ones() {
+        let (tree, points) = tree_with_points();
+        let queries = points
+            .iter()
+            .step_by(7)
+            .map(|point| point.map(|c| c * 0.9))
+            .collect::<Vec<_>>();
+
+        let batch = tree.batch_nearest_neighbors(&queries, 3);
+
+        assert_eq!(batch.len(), queries.len());
+        for (query, result) in queries.iter().zip(&batch) {
+            assert_eq!(result, &tree.nearest_neighbors_with_distance(query, 3));
+        }
+
+        #[cfg(feature = "rayon")]
+        assert_eq!(tree.par_batch_nearest_neighbors(&queries, 3), batch);
+    }
+
+    #[test]
+    fn batch_queries_handle_few_items_and_far_queries() {
+        let mut tree = Tree3D::root(1.0);
+        for data in [[0.5, 0.5, 0.5], [-0.5, 0.0, 0.0]] {
+            tree.insert_by_coordinates(data, &|tree| tree.data().is_some())
+                .unwrap();
+        }
+        let queries = [
+            [0.4, 0.4, 0.4],
+            [3.0, 0.0, 0.0],
+            [0.5, 0.5, 0.5],
+            [-0.9, -0.9, 0.0],
+        ];
+
+        for k in 0..4 {
+            let batch = tree.batch_nearest_neighbors(&queries, k);
+            for (query, result) in queries.iter().zip(&batch) {
+                assert_eq!(result, &tree.nearest_neighbors_with_distance(query, k));
+            }
+        }
+    }
+
+    #[test]
+    fn metric_queries_match_brute_force() {
+        let (tree, points) = tree_with_points();
+        let query = [0.3, 0.1, -0.2];
+
+        for metric in [&Manhattan as &dyn Metric, &Chebyshev] {
+            let mut expected = points
+                .iter()
+                .map(|point| metric.distance(point, &query))
+                .collect::<Vec<_>>();
+            expected.sort_by(f64::total_cmp);
+
+            let found = tree.nearest_neighbors_by(&query, 5, &metric);
+            assert!(found
+                .iter()
+                .zip(&expected)
+                .all(|((distance, _), expected)| distance == expected));
+
+            let within = tree.within_radius_by(&query, 0.3, &metric);
+            assert_eq!(
+                within.len(),
+                expected.iter().filter(|distance| **distance <= 0.3).count()
+            );
+        }
+    }
+}
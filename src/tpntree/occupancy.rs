@@ -0,0 +1,243 @@
+use super::TpnTree;
+use crate::errors::TpnTreeError;
+
+/// A tree marking regions of space as occupied, like an octomap.
+///
+/// A leaf holding `Some(true)` is occupied, every other leaf is free.
+/// Trees are kept compact, children of equal occupancy are merged into their parent.
+pub type OccupancyTree<const N: usize> = TpnTree<bool, N>;
+
+impl<const N: usize> OccupancyTree<N> {
+    /// Marks the box given by center and half extent as occupied, dividing down to the level `level` along its boundary.
+    ///
+    /// Leaves at `level` which partially overlap the box are marked as a whole, touching leaves are not.
+    /// Errors if the [`TreeConfig`](super::TreeConfig) of the tree does not allow dividing to `level`.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::OccupancyTree;
+    /// let mut tree = OccupancyTree::<2>::root(1.0);
+    ///
+    /// tree.set_region([0.5, 0.5], [0.5, 0.5], 4).expect("Couldn't set region.");
+    ///
+    /// assert!(tree.is_occupied(&[0.9, 0.1]));
+    /// assert!(!tree.is_occupied(&[-0.1, 0.1]));
+    /// // the region aligns with a child, so no deeper division is needed
+    /// assert_eq!(tree.iter_depth_first().count(), 5);
+    /// ```
+    pub fn set_region(
+        &mut self,
+        center: [f64; N],
+        half_extent: [f64; N],
+        level: usize,
+    ) -> Result<(), TpnTreeError> {
+        self.paint_region(&center, &half_extent, level, true)
+    }
+
+    /// Marks the box given by center and half extent as free, see [`OccupancyTree::set_region`].
+    pub fn clear_region(
+        &mut self,
+        center: [f64; N],
+        half_extent: [f64; N],
+        level: usize,
+    ) -> Result<(), TpnTreeError> {
+        self.paint_region(&center, &half_extent, level, false)
+    }
+
+    /// Checks if the point lies in an occupied leaf, points outside of the tree are free.
+    pub fn is_occupied(&self, point: &[f64; N]) -> bool {
+        self.contains_point(point) && self.leaf_at(point).is_occupied_leaf()
+    }
+
+    /// Returns the summed volume of all occupied leaves.
+    pub fn occupied_volume(&self) -> f64 {
+        self.iter_depth_first()
+            .filter(|tree| tree.is_occupied_leaf())
+            .map(|tree| tree.volume())
+            .sum()
+    }
+
+    /// Returns a tree occupied where either tree is occupied.
+    ///
+    /// Errors with [`TpnTreeError::RegionMismatch`] if the trees do not cover the same region.
+    /// The result has the config of `self`.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::OccupancyTree;
+    /// let mut a = OccupancyTree::<2>::root(1.0);
+    /// let mut b = OccupancyTree::<2>::root(1.0);
+    /// a.set_region([-0.5, 0.0], [0.5, 1.0], 1).expect("Couldn't set region.");
+    /// b.set_region([0.0, -0.5], [1.0, 0.5], 1).expect("Couldn't set region.");
+    ///
+    /// assert_eq!(a.union(&b).unwrap().occupied_volume(), 3.0);
+    /// assert_eq!(a.intersection(&b).unwrap().occupied_volume(), 1.0);
+    /// assert_eq!(a.difference(&b).unwrap().occupied_volume(), 1.0);
+    /// ```
+    pub fn union(&self, other: &Self) -> Result<Self, TpnTreeError> {
+        self.combine(other, |a, b| a || b)
+    }
+
+    /// Returns a tree occupied where both trees are occupied, see [`OccupancyTree::union`].
+    pub fn intersection(&self, other: &Self) -> Result<Self, TpnTreeError> {
+        self.combine(other, |a, b| a && b)
+    }
+
+    /// Returns a tree occupied where `self` but not `other` is occupied, see [`OccupancyTree::union`].
+    pub fn difference(&self, other: &Self) -> Result<Self, TpnTreeError> {
+        self.combine(other, |a, b| a && !b)
+    }
+
+    fn is_occupied_leaf(&self) -> bool {
+        self.is_leaf() && self.data == Some(true)
+    }
+
+    fn paint_region(
+        &mut self,
+        center: &[f64; N],
+        half_extent: &[f64; N],
+        level: usize,
+        value: bool,
+    ) -> Result<(), TpnTreeError> {
+        let overlaps =
+            (0..N).all(|i| (self.coordinates[i] - center[i]).abs() < self.span[i] + half_extent[i]);
+        if !overlaps {
+            return Ok(());
+        }
+
+        let covered = (0..N)
+            .all(|i| (self.coordinates[i] - center[i]).abs() + self.span[i] <= half_extent[i]);
+        if covered || self.level >= level {
+            self.clear_children();
+            self.data = Some(value);
+            return Ok(());
+        }
+
+        if self.is_leaf() {
+            if self.is_occupied_leaf() == value {
+                return Ok(());
+            }
+            self.divide()?;
+            let inherited = self.data.take() == Some(true);
+            for child in &mut self.children {
+                child.data = Some(inherited);
+            }
+        }
+        for child in &mut self.children {
+            child.paint_region(center, half_extent, level, value)?;
+        }
+        self.collapse();
+        Ok(())
+    }
+
+    // replaces children which are all leaves of the same occupancy by their parent
+    fn collapse(&mut self) {
+        let value = match self.children.first() {
+            Some(first) => first.is_occupied_leaf(),
+            None => return,
+        };
+        if self
+            .children
+            .iter()
+            .all(|child| child.is_leaf() && child.is_occupied_leaf() == value)
+        {
+            self.clear_children();
+            self.data = Some(value);
+        }
+    }
+
+    fn combine(&self, other: &Self, op: fn(bool, bool) -> bool) -> Result<Self, TpnTreeError> {
+        if self.coordinates != other.coordinates || self.span != other.span {
+            return Err(TpnTreeError::RegionMismatch {
+                expected: (self.coordinates.to_vec(), self.span.to_vec()),
+                got: (other.coordinates.to_vec(), other.span.to_vec()),
+            });
+        }
+
+        let mut result = Self::combine_nodes(self, Ok(self), Ok(other), op);
+        result.share_config(self.config.as_ref());
+        Ok(result)
+    }
+
+    fn as_operand(&self) -> Result<&Self, bool> {
+        if self.is_leaf() {
+            Err(self.is_occupied_leaf())
+        } else {
+            Ok(self)
+        }
+    }
+
+    // an operand is either a divided tree or the occupancy of a leaf covering the region
+    fn combine_nodes(
+        region: &Self,
+        a: Result<&Self, bool>,
+        b: Result<&Self, bool>,
+        op: fn(bool, bool) -> bool,
+    ) -> Self {
+        let a = a.and_then(Self::as_operand);
+        let b = b.and_then(Self::as_operand);
+
+        let mut node = Self::new(region.coordinates, region.span, region.level);
+        match (a, b) {
+            (Err(a), Err(b)) => node.data = Some(op(a, b)),
+            _ => {
+                node.children = (0..1 << N)
+                    .map(|index| {
+                        let a = a.map(|tree| &tree.children[index]);
+                        let b = b.map(|tree| &tree.children[index]);
+                        let region = a.or(b).expect("One operand is divided.");
+                        Self::combine_nodes(region, a, b, op)
+                    })
+                    .collect();
+                node.collapse();
+            }
+        }
+        node
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tpntree::OccupancyTree;
+    use crate::TpnTreeError;
+
+    #[test]
+    fn clearing_restores_empty_tree() {
+        let mut tree = OccupancyTree::<3>::root(1.0);
+
+        tree.set_region([0.3, 0.1, -0.2], [0.2, 0.4, 0.3], 4)
+            .unwrap();
+        assert!(tree.is_occupied(&[0.3, 0.1, -0.2]));
+        assert!(!tree.is_occupied(&[-0.5, 0.1, -0.2]));
+        assert!(tree.occupied_volume() > 0.2 * 0.4 * 0.3 * 8.0);
+
+        tree.clear_region([0.0; 3], [1.0; 3], 4).unwrap();
+        assert!(tree.is_leaf());
+        assert_eq!(tree.occupied_volume(), 0.0);
+    }
+
+    #[test]
+    fn set_operations_refine_as_needed() {
+        let mut a = OccupancyTree::<2>::root(1.0);
+        let mut b = OccupancyTree::<2>::root(1.0);
+        a.set_region([0.0, 0.0], [1.0, 1.0], 0).unwrap();
+        b.set_region([0.25, 0.25], [0.25, 0.25], 3).unwrap();
+
+        let difference = a.difference(&b).unwrap();
+        assert_eq!(difference.occupied_volume(), 4.0 - 0.25);
+        assert!(!difference.is_occupied(&[0.25, 0.25]));
+
+        assert_eq!(a.intersection(&b).unwrap(), b);
+        assert_eq!(a.union(&b).unwrap(), a);
+        assert!(a.union(&b).unwrap().is_leaf());
+    }
+
+    #[test]
+    fn set_operations_require_same_region() {
+        let a = OccupancyTree::<2>::root(1.0);
+        let b = OccupancyTree::<2>::root(2.0);
+
+        assert!(matches!(
+            a.union(&b),
+            Err(TpnTreeError::RegionMismatch { .. })
+        ));
+    }
+}
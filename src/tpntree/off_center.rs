@@ -0,0 +1,130 @@
+use super::TpnTree;
+use crate::errors::TpnTreeError;
+
+impl<T, const N: usize> TpnTree<T, N> {
+    /// Divides the tree at the point instead of its center, creating 2^N children of unequal spans.
+    ///
+    /// Use this when the data is heavily skewed within the tree, e.g. splitting at the median of the data like a kd-tree.
+    /// The children are numbered like with [`TpnTree::divide`], relative to the split point instead of the center.
+    ///
+    /// Errors with [`TpnTreeError::DoesNotSpan`] if the point is not strictly inside the tree, as some children would be empty,
+    /// and otherwise like [`TpnTree::divide`].
+    ///
+    /// ```
+    /// # use tpntree::tpntree::TpnTree;
+    /// let mut root = TpnTree::<(), 2>::root(1.0);
+    ///
+    /// root.divide_at([0.5, -0.5]).expect("Couldn't divide.");
+    ///
+    /// assert_eq!(root.split_point(), [0.5, -0.5]);
+    /// // above the split point along both axes
+    /// assert_eq!(root.get_child(0).unwrap().coordinates(), [0.75, 0.25]);
+    /// assert_eq!(root.get_child(0).unwrap().span(), [0.25, 0.75]);
+    /// assert_eq!(root.child_index_for_point(&[0.4, 0.0]), 1);
+    /// ```
+    pub fn divide_at(&mut self, split_point: [f64; N]) -> Result<(), TpnTreeError> {
+        let inside =
+            (0..N).all(|axis| (split_point[axis] - self.coordinates[axis]).abs() < self.span[axis]);
+        if !inside {
+            return Err(self.does_not_span(&split_point));
+        }
+        self.divide_along(None, 2, Some(split_point))
+    }
+
+    /// Returns the point the children split the tree at, which is its center unless divided with [`TpnTree::divide_at`].
+    pub fn split_point(&self) -> [f64; N] {
+        self.split_point
+            .filter(|_| !self.is_leaf())
+            .unwrap_or(self.coordinates)
+    }
+
+    // divides the leaf the same way as the other tree
+    pub(crate) fn divide_like<U>(&mut self, other: &TpnTree<U, N>) -> Result<(), TpnTreeError> {
+        self.divide_along(other.split_axes, other.branching, other.split_point)
+    }
+
+    // the region of the child at the index when split at the point
+    pub(crate) fn off_center_child_region(
+        &self,
+        split_point: &[f64; N],
+        index: usize,
+    ) -> ([f64; N], [f64; N]) {
+        let mut coordinates = self.coordinates;
+        let mut span = self.span;
+        for axis in 0..N {
+            let (min, max) = if index & 1 << axis == 0 {
+                (split_point[axis], self.coordinates[axis] + self.span[axis])
+            } else {
+                (self.coordinates[axis] - self.span[axis], split_point[axis])
+            };
+            coordinates[axis] = (min + max) / 2.0;
+            span[axis] = (max - min) / 2.0;
+        }
+        (coordinates, span)
+    }
+}
+
+// the index of the child covering the coordinates when split at the point
+pub(crate) fn child_index_at<const N: usize>(split_point: &[f64; N], coordinates: &[f64]) -> usize {
+    split_point
+        .iter()
+        .zip(coordinates)
+        .enumerate()
+        .fold(0, |index, (axis, (split, coordinate))| {
+            index | ((coordinate < split) as usize) << axis
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tpntree::{SpatialTree, TpnTree};
+    use crate::TpnTreeError;
+
+    #[test]
+    fn median_splits_balance_skewed_data() {
+        let mut tree = SpatialTree::<[f64; 2], 2>::root(1.0);
+        let points = (1..=40)
+            .map(|i| {
+                let t = i as f64 / 40.0;
+                [t.powi(4) - 0.99, (t * 7.0).sin() * 0.1]
+            })
+            .collect::<Vec<_>>();
+        let mut xs = points.iter().map(|point| point[0]).collect::<Vec<_>>();
+        xs.sort_by(f64::total_cmp);
+
+        tree.divide_at([xs[20], 0.0]).unwrap();
+        for point in points.iter() {
+            tree.insert_by_coordinates(*point, &|_| false).unwrap();
+        }
+
+        assert_eq!(tree.check_spatial_invariants(), Ok(()));
+        let left = tree.get_child(1).unwrap().data().map_or(0, Vec::len)
+            + tree.get_child(3).unwrap().data().map_or(0, Vec::len);
+        assert_eq!(left, 20);
+
+        let mut merged = SpatialTree::<[f64; 2], 2>::root(1.0);
+        merged
+            .merge_with(&tree, |_, theirs| theirs.cloned())
+            .unwrap();
+        assert_eq!(merged, tree);
+
+        #[cfg(feature = "persist")]
+        {
+            let mut bytes = Vec::new();
+            tree.save_to(&mut bytes).unwrap();
+            assert_eq!(SpatialTree::load_from(bytes.as_slice()).unwrap(), tree);
+        }
+    }
+
+    #[test]
+    fn split_point_must_be_inside() {
+        let mut tree = TpnTree::<(), 2>::root(1.0);
+
+        assert!(matches!(
+            tree.divide_at([1.0, 0.0]),
+            Err(TpnTreeError::DoesNotSpan { .. })
+        ));
+        assert!(tree.is_leaf());
+        assert_eq!(tree.split_point(), [0.0, 0.0]);
+    }
+}
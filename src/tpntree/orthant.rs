@@ -0,0 +1,185 @@
+use super::TpnTree;
+
+/// The side of the center of a tree a child lies on along one axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Sign {
+    /// Above the center, including the center itself.
+    Positive,
+    /// Below the center.
+    Negative,
+}
+
+impl Sign {
+    /// Returns the other side.
+    pub fn opposite(self) -> Self {
+        match self {
+            Sign::Positive => Sign::Negative,
+            Sign::Negative => Sign::Positive,
+        }
+    }
+}
+
+/// The position of a child relative to the center of its parent, one of the 2^N orthants around the center.
+///
+/// Children of a tree divided by [`TpnTree::divide`] are ordered by the index of their orthant.
+/// Bit `i` of the index is clear if the child lies above the center along axis `i` and set if it lies below.
+/// The ordering is stable, algorithms can rely on it to tell which child is which.
+///
+/// ```
+/// # use tpntree::tpntree::{Orthant, Sign, TpnTree};
+/// let mut root = TpnTree::<(), 2>::root(1.0);
+/// root.divide().expect("Couldn't divide.");
+///
+/// let orthant = Orthant::from_signs([Sign::Positive, Sign::Negative]);
+///
+/// assert_eq!(orthant.to_index(), 2);
+/// assert_eq!(root.get_orthant(orthant).unwrap().coordinates(), [0.5, -0.5]);
+/// assert_eq!(orthant.opposite(), Orthant::from_signs([Sign::Negative, Sign::Positive]));
+/// assert_eq!(Orthant::<2>::from_index(3).unwrap().signs(), [Sign::Negative; 2]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Orthant<const N: usize>(pub(crate) usize);
+
+/// The former name of [`Orthant`].
+pub type ChildIndex<const N: usize> = Orthant<N>;
+
+impl<const N: usize> Orthant<N> {
+    /// Returns the orthant with the index, if it is below 2^N.
+    pub fn from_index(index: usize) -> Option<Self> {
+        (N >= usize::BITS as usize || index < 1 << N).then_some(Self(index))
+    }
+
+    /// Returns the orthant with the index, if it is below 2^N.
+    #[deprecated(note = "use `Orthant::from_index` instead")]
+    pub fn new(index: usize) -> Option<Self> {
+        Self::from_index(index)
+    }
+
+    /// Returns the orthant lying on the given side of the center along each axis.
+    pub fn from_signs(signs: [Sign; N]) -> Self {
+        Self(signs.iter().enumerate().fold(0, |index, (axis, sign)| {
+            index | ((*sign == Sign::Negative) as usize) << axis
+        }))
+    }
+
+    /// Returns the side of the center the orthant lies on along each axis.
+    pub fn signs(&self) -> [Sign; N] {
+        core::array::from_fn(|axis| self.sign(axis))
+    }
+
+    /// Returns the side of the center the orthant lies on along the axis.
+    ///
+    /// Panics if the axis is not below N.
+    pub fn sign(&self, axis: usize) -> Sign {
+        assert!(axis < N, "Expected axis below {}, got {}.", N, axis);
+        if self.0 & (1 << axis) == 0 {
+            Sign::Positive
+        } else {
+            Sign::Negative
+        }
+    }
+
+    /// Returns the orthant on the other side of the center along every axis.
+    pub fn opposite(&self) -> Self {
+        Self::from_signs(self.signs().map(Sign::opposite))
+    }
+
+    /// Returns the orthant on the other side of the center along the axis only.
+    ///
+    /// Panics if the axis is not below N.
+    pub fn mirror(&self, axis: usize) -> Self {
+        assert!(axis < N, "Expected axis below {}, got {}.", N, axis);
+        Self(self.0 ^ 1 << axis)
+    }
+
+    /// Returns the index into the children of a tree divided by [`TpnTree::divide`].
+    pub fn to_index(&self) -> usize {
+        self.0
+    }
+
+    /// Returns the index into the children of a tree divided by [`TpnTree::divide`].
+    #[deprecated(note = "use `Orthant::to_index` instead")]
+    pub fn index(&self) -> usize {
+        self.to_index()
+    }
+}
+
+impl<const N: usize> From<Orthant<N>> for usize {
+    fn from(orthant: Orthant<N>) -> Self {
+        orthant.0
+    }
+}
+
+impl<T, const N: usize> TpnTree<T, N> {
+    /// Returns the orthant around the center of the tree the point lies in.
+    ///
+    /// Like children, orthants are half-open, a point exactly on the center of an axis lies in the orthant above it.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::{Orthant, Sign, TpnTree};
+    /// let root = TpnTree::<(), 2>::root(1.0);
+    ///
+    /// assert_eq!(root.orthant_of(&[0.0, -0.5]).signs(), [Sign::Positive, Sign::Negative]);
+    /// ```
+    pub fn orthant_of(&self, point: &[f64; N]) -> Orthant<N> {
+        Orthant(self.child_index_generic(point))
+    }
+
+    /// Returns the child in the orthant, if the tree is divided by [`TpnTree::divide`] or [`TpnTree::divide_at`].
+    ///
+    /// Trees divided along some axes only or into more than two parts per axis have no child per orthant and return `None`.
+    pub fn get_orthant(&self, orthant: Orthant<N>) -> Option<&Self> {
+        self.has_orthants()
+            .then(|| self.children.get(orthant.0))
+            .flatten()
+    }
+
+    /// Returns the child in the orthant by mutable reference, see [`TpnTree::get_orthant`].
+    pub fn get_orthant_mut(&mut self, orthant: Orthant<N>) -> Option<&mut Self> {
+        if !self.has_orthants() {
+            return None;
+        }
+        self.children.get_mut(orthant.0)
+    }
+
+    // checks if the children are exactly the orthants around the split point
+    fn has_orthants(&self) -> bool {
+        self.branching == 2 && self.split_axes.is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tpntree::{Orthant, Sign, TpnTree};
+
+    #[test]
+    fn signs_match_child_regions() {
+        let mut root = TpnTree::<(), 3>::root(1.0);
+        root.divide().expect("Couldn't divide.");
+
+        for (index, child) in root.children().iter().enumerate() {
+            let orthant = Orthant::<3>::from_index(index).unwrap();
+            for (axis, sign) in orthant.signs().iter().enumerate() {
+                let above = child.coordinates()[axis] > 0.0;
+                assert_eq!(above, *sign == Sign::Positive);
+                assert_eq!(orthant.mirror(axis).sign(axis), sign.opposite());
+            }
+            assert_eq!(Orthant::from_signs(orthant.signs()), orthant);
+            assert_eq!(root.orthant_of(&child.coordinates()), orthant);
+            assert_eq!(orthant.opposite().opposite(), orthant);
+            assert_eq!(
+                root.get_orthant(orthant.opposite()).unwrap().coordinates(),
+                child.coordinates().map(|coordinate| -coordinate)
+            );
+        }
+        assert!(Orthant::<3>::from_index(8).is_none());
+    }
+
+    #[test]
+    fn partial_divisions_have_no_orthants() {
+        let mut root = TpnTree::<(), 2>::root(1.0);
+        root.divide_axes(&[0]).expect("Couldn't divide.");
+
+        assert!(root.get_orthant(Orthant::from_index(0).unwrap()).is_none());
+    }
+}
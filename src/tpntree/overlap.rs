@@ -0,0 +1,116 @@
+use super::TpnTree;
+use alloc::vec::Vec;
+
+impl<T, const N: usize> TpnTree<T, N> {
+    /// Checks if the regions of both trees overlap with a non-zero volume.
+    ///
+    /// Trees only touching on their faces do not overlap.
+    pub fn overlaps<U>(&self, other: &TpnTree<U, N>) -> bool {
+        (0..N).all(|i| {
+            (self.coordinates[i] - other.coordinates[i]).abs() < self.span[i] + other.span[i]
+        })
+    }
+
+    /// Returns all pairs of leaves, one of each tree, whose regions overlap.
+    ///
+    /// Both trees are walked simultaneously, skipping all pairs of subtrees that do not overlap.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::TpnTree;
+    /// let mut tree = TpnTree::<(), 2>::root(1.0);
+    /// tree.divide().expect("Couldn't divide.");
+    ///
+    /// // a square overlapping two of the children of tree
+    /// let other = TpnTree::<(), 2>::new([0.5, 0.0], [0.25, 0.25], 0);
+    ///
+    /// assert_eq!(tree.overlapping_pairs(&other).len(), 2);
+    /// ```
+    pub fn overlapping_pairs<'a, 'b, U>(
+        &'a self,
+        other: &'b TpnTree<U, N>,
+    ) -> Vec<(&'a Self, &'b TpnTree<U, N>)> {
+        let mut pairs = Vec::new();
+        self.overlapping_pairs_recursive(other, &mut pairs);
+        pairs
+    }
+
+    fn overlapping_pairs_recursive<'a, 'b, U>(
+        &'a self,
+        other: &'b TpnTree<U, N>,
+        pairs: &mut Vec<(&'a Self, &'b TpnTree<U, N>)>,
+    ) {
+        if !self.overlaps(other) {
+            return;
+        }
+
+        match (self.is_leaf(), other.is_leaf()) {
+            (true, true) => pairs.push((self, other)),
+            (true, false) => {
+                for other_child in &other.children {
+                    self.overlapping_pairs_recursive(other_child, pairs);
+                }
+            }
+            (false, true) => {
+                for child in &self.children {
+                    child.overlapping_pairs_recursive(other, pairs);
+                }
+            }
+            (false, false) => {
+                for child in &self.children {
+                    for other_child in &other.children {
+                        child.overlapping_pairs_recursive(other_child, pairs);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tpntree::TpnTree;
+
+    #[test]
+    fn touching_trees_do_not_overlap() {
+        let tree = TpnTree::<(), 2>::new([0.0, 0.0], [1.0, 1.0], 0);
+        let other = TpnTree::<(), 2>::new([2.0, 0.0], [1.0, 1.0], 0);
+
+        assert!(!tree.overlaps(&other));
+        assert!(tree.overlapping_pairs(&other).is_empty());
+    }
+
+    #[test]
+    fn identical_structures_pair_identical_leaves() {
+        let mut tree = TpnTree::<usize, 2>::root(1.0);
+        let mut other = TpnTree::<&str, 2>::root(1.0);
+
+        assert!(tree.divide().is_ok());
+        assert!(other.divide().is_ok());
+
+        let pairs = tree.overlapping_pairs(&other);
+
+        assert_eq!(pairs.len(), 4);
+        assert!(pairs
+            .iter()
+            .all(|(leaf, other_leaf)| leaf.coordinates() == other_leaf.coordinates()));
+    }
+
+    #[test]
+    fn pair_leaves_of_different_depth() {
+        let mut tree = TpnTree::<(), 1>::root(1.0);
+        let mut other = TpnTree::<(), 1>::new([1.0], [1.0], 0);
+
+        assert!(tree.divide().is_ok());
+        assert!(tree.get_child_mut(0).unwrap().divide().is_ok());
+        assert!(other.divide().is_ok());
+
+        // tree leaves: [0.5, 1], [0, 0.5], [-1, 0]
+        // other leaves: [1, 2], [0, 1]
+        let pairs = tree.overlapping_pairs(&other);
+
+        assert_eq!(pairs.len(), 2);
+        assert!(pairs
+            .iter()
+            .all(|(_, other_leaf)| other_leaf.coordinates() == [0.5]));
+    }
+}
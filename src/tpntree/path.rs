@@ -0,0 +1,184 @@
+use alloc::{
+    collections::{BTreeMap, BinaryHeap},
+    vec,
+    vec::Vec,
+};
+use core::cmp::Ordering;
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use num_traits::Float;
+
+use super::TpnTree;
+
+impl<T, const N: usize> TpnTree<T, N> {
+    /// Plans the cheapest path of leaves from the leaf covering `start` to the leaf covering `goal` with A*.
+    ///
+    /// Paths move between leaves sharing a face, see [`TpnTree::dual_graph`].
+    /// The `cost_fn` returns the cost per unit of distance to move through a leaf, or `None` if the leaf can not be passed.
+    /// Moving between two leaves costs the distance of their centers times the cost of the leaf moved into.
+    ///
+    /// Returns the leaves along the path including both ends, or `None` if either point is outside the tree, lies in an impassable leaf or the goal is unreachable.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::OccupancyTree;
+    /// let mut tree = OccupancyTree::<2>::root(1.0);
+    /// tree.divide().expect("Couldn't divide.");
+    /// // block the upper left quadrant
+    /// *tree.get_child_mut(1).unwrap().data_mut() = Some(true);
+    ///
+    /// let free = |leaf: &OccupancyTree<2>| (leaf.data() != Some(&true)).then_some(1.0);
+    /// let path = tree.astar(&[0.5, 0.5], &[-0.5, -0.5], free).unwrap();
+    ///
+    /// // the way around through the lower right quadrant
+    /// assert_eq!(path.len(), 3);
+    /// assert_eq!(path[1].coordinates(), [0.5, -0.5]);
+    /// ```
+    pub fn astar(
+        &self,
+        start: &[f64; N],
+        goal: &[f64; N],
+        cost_fn: impl Fn(&Self) -> Option<f64>,
+    ) -> Option<Vec<&Self>> {
+        let start = self.find_at_level(start, usize::MAX)?;
+        let goal = self.find_at_level(goal, usize::MAX)?;
+
+        // the vertices of the dual graph are the leaves in depth first order
+        let leaves = self
+            .iter_depth_first()
+            .filter(|tree| tree.is_leaf())
+            .collect::<Vec<_>>();
+        let costs = leaves.iter().map(|&leaf| cost_fn(leaf)).collect::<Vec<_>>();
+        let mut neighbors = vec![Vec::new(); leaves.len()];
+        for [a, b] in self.dual_graph().edges {
+            neighbors[a].push(b);
+            neighbors[b].push(a);
+        }
+        let index_of = |tree: &Self| {
+            leaves
+                .iter()
+                .position(|&leaf| core::ptr::eq(leaf, tree))
+                .expect("Found trees are leaves.")
+        };
+        let (start, goal) = (index_of(start), index_of(goal));
+        costs[start]?;
+        costs[goal]?;
+
+        // scaling the straight distance by the lowest cost keeps the heuristic admissible
+        let min_cost = costs
+            .iter()
+            .flatten()
+            .copied()
+            .fold(f64::INFINITY, f64::min);
+        let heuristic = |index: usize| {
+            distance(&leaves[index].coordinates, &leaves[goal].coordinates) * min_cost
+        };
+
+        let mut best = BTreeMap::new();
+        let mut previous = BTreeMap::new();
+        let mut open = BinaryHeap::new();
+        best.insert(start, 0.0);
+        open.push(Open {
+            estimate: heuristic(start),
+            index: start,
+        });
+
+        while let Some(Open { index, .. }) = open.pop() {
+            if index == goal {
+                let mut path = vec![leaves[goal]];
+                let mut current = goal;
+                while let Some(&before) = previous.get(&current) {
+                    path.push(leaves[before]);
+                    current = before;
+                }
+                path.reverse();
+                return Some(path);
+            }
+            let cost_so_far = best[&index];
+            for &neighbor in &neighbors[index] {
+                let Some(cost) = costs[neighbor] else {
+                    continue;
+                };
+                let cost = cost_so_far
+                    + cost * distance(&leaves[index].coordinates, &leaves[neighbor].coordinates);
+                if cost < best.get(&neighbor).copied().unwrap_or(f64::INFINITY) {
+                    best.insert(neighbor, cost);
+                    previous.insert(neighbor, index);
+                    open.push(Open {
+                        estimate: cost + heuristic(neighbor),
+                        index: neighbor,
+                    });
+                }
+            }
+        }
+        None
+    }
+}
+
+fn distance<const N: usize>(a: &[f64; N], b: &[f64; N]) -> f64 {
+    a.iter()
+        .zip(b)
+        .map(|(a, b)| (a - b).powi(2))
+        .sum::<f64>()
+        .sqrt()
+}
+
+// ordered such that the max-heap pops the lowest estimate first
+struct Open {
+    estimate: f64,
+    index: usize,
+}
+
+impl PartialEq for Open {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Open {}
+
+impl PartialOrd for Open {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Open {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .estimate
+            .total_cmp(&self.estimate)
+            .then_with(|| other.index.cmp(&self.index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tpntree::TpnTree;
+
+    #[test]
+    fn astar_routes_around_walls() {
+        let mut tree = TpnTree::<bool, 2>::root(4.0);
+        tree.divide_to_depth(2).expect("Couldn't divide.");
+        // a wall along x = 1 with a gap at the top
+        let wall = |tree: &TpnTree<bool, 2>| {
+            let [x, y] = tree.coordinates();
+            x > 0.0 && x < 2.0 && y < 2.0
+        };
+        let cost = |tree: &TpnTree<bool, 2>| (!wall(tree)).then_some(1.0);
+
+        let path = tree.astar(&[-3.0, -3.0], &[3.0, -3.0], cost).unwrap();
+
+        assert!(path.iter().all(|leaf| !wall(leaf)));
+        // all leaves have the same size, so neighbors are one leaf width apart along one axis
+        assert!(path.windows(2).all(|pair| {
+            let (a, b) = (pair[0].coordinates(), pair[1].coordinates());
+            (a[0] - b[0]).abs() + (a[1] - b[1]).abs() == 2.0
+        }));
+        assert_eq!(path.first().unwrap().coordinates(), [-3.0, -3.0]);
+        assert_eq!(path.last().unwrap().coordinates(), [3.0, -3.0]);
+        assert!(path.iter().any(|leaf| leaf.coordinates()[1] > 2.0));
+
+        assert!(tree.astar(&[1.0, -3.0], &[3.0, -3.0], cost).is_none());
+        assert!(tree.astar(&[5.0, -3.0], &[3.0, -3.0], cost).is_none());
+    }
+}
@@ -0,0 +1,134 @@
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use num_traits::Float;
+
+use super::{Metric, TpnTree};
+
+impl<T, const N: usize> TpnTree<T, N> {
+    /// Returns the image of the point inside the tree along its periodic axes.
+    ///
+    /// Points are moved by multiples of the extent of the tree along every axis configured as periodic,
+    /// see [`TreeConfig::with_periodic_axes`](super::TreeConfig::with_periodic_axes).
+    /// Other axes are left as they are.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::TpnTree;
+    /// let tree = TpnTree::<(), 2>::builder().periodic_axes([true, false]).build();
+    ///
+    /// assert_eq!(tree.wrap(&[1.5, 1.5]), [-0.5, 1.5]);
+    /// assert_eq!(tree.wrap(&[-3.25, 0.0]), [0.75, 0.0]);
+    /// ```
+    pub fn wrap(&self, point: &[f64; N]) -> [f64; N] {
+        let mut wrapped = *point;
+        for (axis, period) in self.periods().iter().enumerate() {
+            if let Some(period) = period {
+                let min = self.coordinates[axis] - self.span[axis];
+                // the remainder keeps the sign of the offset, negative ones are moved up by a period
+                let mut offset = (point[axis] - min) % period;
+                if offset < 0.0 {
+                    offset += period;
+                }
+                wrapped[axis] = min + offset;
+            }
+        }
+        wrapped
+    }
+
+    // the extent of the tree along each periodic axis
+    pub(crate) fn periods(&self) -> [Option<f64>; N] {
+        let mut periods = [None; N];
+        if let Some(periodic_axes) = self.config().and_then(|config| config.periodic_axes()) {
+            for (axis, periodic) in periodic_axes.iter().enumerate().take(N) {
+                if *periodic {
+                    periods[axis] = Some(2.0 * self.span[axis]);
+                }
+            }
+        }
+        periods
+    }
+
+    // the metric measuring along periodic axes to the closest image
+    pub(crate) fn periodic<'a, M: Metric>(&self, metric: &'a M) -> Periodic<'a, M, N> {
+        Periodic {
+            metric,
+            periods: self.periods(),
+        }
+    }
+}
+
+// wraps a metric such that the differences along periodic axes are to the closest image
+pub(crate) struct Periodic<'a, M, const N: usize> {
+    metric: &'a M,
+    periods: [Option<f64>; N],
+}
+
+impl<M, const N: usize> Periodic<'_, M, N> {
+    // the image of the point closest to the reference along each periodic axis
+    fn closest_image(&self, reference: &[f64], point: &[f64]) -> [f64; N] {
+        core::array::from_fn(|axis| match self.periods[axis] {
+            Some(period) => {
+                let difference = point[axis] - reference[axis];
+                reference[axis] + difference - period * (difference / period).round()
+            }
+            None => point[axis],
+        })
+    }
+}
+
+impl<M: Metric, const N: usize> Metric for Periodic<'_, M, N> {
+    fn distance(&self, a: &[f64], b: &[f64]) -> f64 {
+        self.metric.distance(a, &self.closest_image(a, b))
+    }
+
+    // the image closest to the center is closest to the box, as the box is at most one period wide
+    fn distance_to_box(&self, coordinates: &[f64], span: &[f64], point: &[f64]) -> f64 {
+        self.metric
+            .distance_to_box(coordinates, span, &self.closest_image(coordinates, point))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tpntree::{Manhattan, TpnTree, Tree3D, VisitControl};
+
+    #[test]
+    fn queries_wrap_around_periodic_axes() {
+        let mut tree = Tree3D::builder()
+            .leaf_capacity(1)
+            .max_depth(6)
+            .periodic_axes([true, true, false])
+            .build();
+        for data in [[0.9, 0.9, 0.9], [-0.9, 0.0, 0.0], [0.3, 0.3, 0.3]] {
+            assert!(tree.insert(data).is_ok());
+        }
+
+        // the first item is 0.2 away across the boundary of the first two axes, but not the third
+        let found = tree.nearest_neighbors_with_distance(&[-0.9, -0.9, 0.9], 1);
+        assert_eq!(found[0].1, &[0.9, 0.9, 0.9]);
+        assert!((found[0].0 - 0.2f64.hypot(0.2)).abs() < 1e-12);
+
+        let within = tree.within_radius_by(&[0.9, 0.0, 0.0], 0.25, &Manhattan);
+        assert_eq!(within.len(), 1);
+        assert!((within[0].0 - 0.2).abs() < 1e-12);
+        assert!(tree.within_radius(&[0.0, 0.0, -0.9], 0.3).is_empty());
+    }
+
+    #[test]
+    fn stencil_neighbors_wrap_around_periodic_axes() {
+        let mut tree = TpnTree::<f64, 1>::builder().periodic_axes([true]).build();
+        tree.divide_to_depth(2).expect("Couldn't divide.");
+        let mut value = 0.0;
+        tree.visit_mut(&mut |tree: &mut TpnTree<f64, 1>| {
+            if tree.is_leaf() {
+                value += 1.0;
+                *tree.data_mut() = Some(value);
+            }
+            VisitControl::Continue
+        });
+
+        tree.apply_stencil(|&value, neighbors| {
+            assert!(neighbors.iter().all(Option::is_some));
+            value
+        });
+    }
+}
@@ -0,0 +1,644 @@
+use alloc::{string::String, vec::Vec};
+use core::convert::{TryFrom, TryInto};
+use std::io::{self, Read, Write};
+
+use super::{branching, DuplicatePolicy, NodeKey, Spillover, TpnTree, TreeConfig, WorldTransform};
+
+// identifies the format, followed by its version
+const MAGIC: &[u8; 4] = b"TPNT";
+const VERSION: u8 = 9;
+const CHUNK_MAGIC: &[u8; 4] = b"TPNC";
+const CHUNK_VERSION: u8 = 4;
+
+/// Data that can be written to and read from the binary format of [`TpnTree::save_to`].
+///
+/// Numbers are stored little endian, `usize` as `u64`.
+/// Options are stored as a boolean followed by the value if there is one, vectors and strings as their length followed by their elements.
+///
+/// Implement it for own data by writing and reading its fields in the same order:
+///
+/// ```
+/// # use std::io::{self, Read, Write};
+/// # use tpntree::tpntree::{Persist, TpnTree};
+/// #[derive(Debug, PartialEq)]
+/// struct Voxel {
+///     material: u8,
+///     density: f32,
+/// }
+///
+/// impl Persist for Voxel {
+///     fn write_to(&self, writer: &mut impl Write) -> io::Result<()> {
+///         self.material.write_to(writer)?;
+///         self.density.write_to(writer)
+///     }
+///
+///     fn read_from(reader: &mut impl Read) -> io::Result<Self> {
+///         Ok(Self {
+///             material: u8::read_from(reader)?,
+///             density: f32::read_from(reader)?,
+///         })
+///     }
+/// }
+///
+/// let mut tree = TpnTree::<Voxel, 3>::root(1.0);
+/// *tree.data_mut() = Some(Voxel { material: 3, density: 0.5 });
+///
+/// let mut bytes = Vec::new();
+/// tree.save_to(&mut bytes).expect("Couldn't save.");
+///
+/// assert_eq!(TpnTree::load_from(bytes.as_slice()).expect("Couldn't load."), tree);
+/// ```
+pub trait Persist: Sized {
+    /// Writes the value to the writer.
+    fn write_to(&self, writer: &mut impl Write) -> io::Result<()>;
+    /// Reads a value written by [`Persist::write_to`] from the reader.
+    ///
+    /// Errors with [`std::io::ErrorKind::InvalidData`] if the bytes do not describe a value,
+    /// and with [`std::io::ErrorKind::UnexpectedEof`] if the reader ends early.
+    fn read_from(reader: &mut impl Read) -> io::Result<Self>;
+}
+
+macro_rules! impl_persist_for_number {
+    ( $( $t:ty ),* ) => {
+        $(
+            impl Persist for $t {
+                fn write_to(&self, writer: &mut impl Write) -> io::Result<()> {
+                    writer.write_all(&self.to_le_bytes())
+                }
+
+                fn read_from(reader: &mut impl Read) -> io::Result<Self> {
+                    let mut bytes = [0; core::mem::size_of::<$t>()];
+                    reader.read_exact(&mut bytes)?;
+                    Ok(<$t>::from_le_bytes(bytes))
+                }
+            }
+        )*
+    };
+}
+
+impl_persist_for_number!(u8, u16, u32, u64, u128, i8, i16, i32, i64, f32, f64);
+
+impl Persist for usize {
+    fn write_to(&self, writer: &mut impl Write) -> io::Result<()> {
+        (*self as u64).write_to(writer)
+    }
+
+    fn read_from(reader: &mut impl Read) -> io::Result<Self> {
+        let value = u64::read_from(reader)?;
+        usize::try_from(value).map_err(|_| invalid_data("Value does not fit into usize."))
+    }
+}
+
+impl Persist for bool {
+    fn write_to(&self, writer: &mut impl Write) -> io::Result<()> {
+        (*self as u8).write_to(writer)
+    }
+
+    fn read_from(reader: &mut impl Read) -> io::Result<Self> {
+        match u8::read_from(reader)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(invalid_data("Expected a boolean.")),
+        }
+    }
+}
+
+impl Persist for () {
+    fn write_to(&self, _writer: &mut impl Write) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn read_from(_reader: &mut impl Read) -> io::Result<Self> {
+        Ok(())
+    }
+}
+
+impl<T: Persist> Persist for Option<T> {
+    fn write_to(&self, writer: &mut impl Write) -> io::Result<()> {
+        self.is_some().write_to(writer)?;
+        match self {
+            Some(value) => value.write_to(writer),
+            None => Ok(()),
+        }
+    }
+
+    fn read_from(reader: &mut impl Read) -> io::Result<Self> {
+        if bool::read_from(reader)? {
+            T::read_from(reader).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+impl<T: Persist> Persist for Vec<T> {
+    fn write_to(&self, writer: &mut impl Write) -> io::Result<()> {
+        self.len().write_to(writer)?;
+        self.iter().try_for_each(|value| value.write_to(writer))
+    }
+
+    fn read_from(reader: &mut impl Read) -> io::Result<Self> {
+        let len = usize::read_from(reader)?;
+        // the length is not trusted for preallocation, the reader might end early
+        (0..len).map(|_| T::read_from(reader)).collect()
+    }
+}
+
+impl<T: Persist, const M: usize> Persist for [T; M] {
+    fn write_to(&self, writer: &mut impl Write) -> io::Result<()> {
+        self.iter().try_for_each(|value| value.write_to(writer))
+    }
+
+    fn read_from(reader: &mut impl Read) -> io::Result<Self> {
+        let values = (0..M)
+            .map(|_| T::read_from(reader))
+            .collect::<io::Result<Vec<T>>>()?;
+        values
+            .try_into()
+            .map_err(|_| invalid_data("Expected an array."))
+    }
+}
+
+impl Persist for String {
+    fn write_to(&self, writer: &mut impl Write) -> io::Result<()> {
+        self.as_bytes().to_vec().write_to(writer)
+    }
+
+    fn read_from(reader: &mut impl Read) -> io::Result<Self> {
+        String::from_utf8(Vec::read_from(reader)?).map_err(|_| invalid_data("Expected UTF-8."))
+    }
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+impl<T: Persist, const N: usize> TpnTree<T, N> {
+    /// Writes the tree in a compact binary format.
+    ///
+    /// The format consists of a header holding the dimension, the [`TreeConfig`] and the count of trees,
+    /// followed by all trees in depth first order, each with its coordinates, span, level, whether it is divided and its data.
+    /// The writer is not buffered, wrap it in a [`std::io::BufWriter`] when writing to a file.
+    ///
+    /// The format is not suitable for zero-copy access, e.g. by memory mapping a file.
+    /// Loading always reads the whole tree and allocates every tree and its data.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::TpnTree;
+    /// let mut tree = TpnTree::<Vec<f64>, 3>::builder().max_depth(4).build();
+    /// tree.divide().expect("Couldn't divide.");
+    /// *tree.get_child_mut(0).unwrap().data_mut() = Some(vec![1.0, 2.0]);
+    ///
+    /// let mut bytes = Vec::new();
+    /// tree.save_to(&mut bytes).expect("Couldn't save.");
+    ///
+    /// assert_eq!(TpnTree::load_from(bytes.as_slice()).expect("Couldn't load."), tree);
+    /// ```
+    pub fn save_to(&self, mut writer: impl Write) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+        VERSION.write_to(&mut writer)?;
+        N.write_to(&mut writer)?;
+
+        let config = self.config();
+        config.is_some().write_to(&mut writer)?;
+        if let Some(config) = config {
+            config.max_depth().write_to(&mut writer)?;
+            config.leaf_capacity().write_to(&mut writer)?;
+            config.looseness().write_to(&mut writer)?;
+            config
+                .duplicate_policy()
+                .map(|policy| match policy {
+                    DuplicatePolicy::KeepAll => 0_u8,
+                    DuplicatePolicy::Reject => 1,
+                    DuplicatePolicy::Replace => 2,
+                })
+                .write_to(&mut writer)?;
+            config.duplicate_tolerance().write_to(&mut writer)?;
+            config
+                .periodic_axes()
+                .map(<[bool]>::to_vec)
+                .write_to(&mut writer)?;
+            config.epsilon().write_to(&mut writer)?;
+            let transform = config.world_transform();
+            transform
+                .map(|transform| transform.linear().to_vec())
+                .write_to(&mut writer)?;
+            transform
+                .map(|transform| transform.translation().to_vec())
+                .write_to(&mut writer)?;
+            config
+                .spillover()
+                .map(|spillover| match spillover {
+                    Spillover::Split => 0_u8,
+                    Spillover::Overflow => 1,
+                    Spillover::Reject => 2,
+                })
+                .write_to(&mut writer)?;
+        }
+
+        self.iter_depth_first().count().write_to(&mut writer)?;
+        self.write_trees(&mut writer)
+    }
+
+    fn write_trees(&self, writer: &mut impl Write) -> io::Result<()> {
+        self.coordinates.write_to(writer)?;
+        self.span.write_to(writer)?;
+        self.level.write_to(writer)?;
+        self.is_leaf().write_to(writer)?;
+        if !self.is_leaf() {
+            self.split_axes.write_to(writer)?;
+            self.branching.write_to(writer)?;
+            self.split_point.write_to(writer)?;
+        }
+        self.data.write_to(writer)?;
+        self.children
+            .iter()
+            .try_for_each(|child| child.write_trees(writer))
+    }
+
+    /// Reads a tree written by [`TpnTree::save_to`].
+    ///
+    /// Errors with [`std::io::ErrorKind::InvalidData`] if the data is not in the format or of another dimension.
+    /// The reader is not buffered, wrap it in a [`std::io::BufReader`] when reading from a file.
+    pub fn load_from(mut reader: impl Read) -> io::Result<Self> {
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid_data("Expected a tpntree of a known version."));
+        }
+        // version 1 lacks the duplicate settings of the config, version 2 the periodic axes, version 3 the split axes, version 4 the branching, version 5 the split point, version 6 the epsilon, version 7 the world transform and version 8 the spillover
+        let version = u8::read_from(&mut reader)?;
+        if version == 0 || version > VERSION {
+            return Err(invalid_data("Expected a tpntree of a known version."));
+        }
+        if usize::read_from(&mut reader)? != N {
+            return Err(invalid_data("Expected a tpntree of the same dimension."));
+        }
+
+        let config = if bool::read_from(&mut reader)? {
+            let mut config = TreeConfig::default();
+            if let Some(max_depth) = Option::read_from(&mut reader)? {
+                config = config.with_max_depth(max_depth);
+            }
+            if let Some(leaf_capacity) = Option::read_from(&mut reader)? {
+                config = config.with_leaf_capacity(leaf_capacity);
+            }
+            if let Some(looseness) = Option::read_from(&mut reader)? {
+                config = config.with_looseness(looseness);
+            }
+            if version >= 2 {
+                if let Some(policy) = Option::<u8>::read_from(&mut reader)? {
+                    config = config.with_duplicate_policy(match policy {
+                        0 => DuplicatePolicy::KeepAll,
+                        1 => DuplicatePolicy::Reject,
+                        2 => DuplicatePolicy::Replace,
+                        _ => return Err(invalid_data("Expected a known duplicate policy.")),
+                    });
+                }
+                if let Some(tolerance) = Option::read_from(&mut reader)? {
+                    config = config.with_duplicate_tolerance(tolerance);
+                }
+            }
+            if version >= 3 {
+                if let Some(periodic_axes) = Option::read_from(&mut reader)? {
+                    config = config.with_periodic_axes(periodic_axes);
+                }
+            }
+            if version >= 7 {
+                if let Some(epsilon) = Option::read_from(&mut reader)? {
+                    config = config.with_epsilon(epsilon);
+                }
+            }
+            if version >= 8 {
+                let linear = Option::<Vec<f64>>::read_from(&mut reader)?;
+                let translation = Option::<Vec<f64>>::read_from(&mut reader)?;
+                if let (Some(linear), Some(translation)) = (linear, translation) {
+                    let transform = WorldTransform::from_parts(linear, translation)
+                        .ok()
+                        .filter(|transform| transform.dimension() == N)
+                        .ok_or_else(|| {
+                            invalid_data(
+                                "Expected an invertible world transform of the same dimension.",
+                            )
+                        })?;
+                    config = config.with_world_transform(transform);
+                }
+            }
+            if version >= 9 {
+                if let Some(spillover) = Option::<u8>::read_from(&mut reader)? {
+                    config = config.with_spillover(match spillover {
+                        0 => Spillover::Split,
+                        1 => Spillover::Overflow,
+                        2 => Spillover::Reject,
+                        _ => return Err(invalid_data("Expected a known spillover policy.")),
+                    });
+                }
+            }
+            Some(config)
+        } else {
+            None
+        };
+
+        let count = usize::read_from(&mut reader)?;
+        let mut remaining = count;
+        let mut tree = Self::read_trees(&mut reader, version, &mut remaining)?;
+        if remaining != 0 {
+            return Err(invalid_data("Expected more trees."));
+        }
+
+        if let Some(config) = config {
+            tree.set_config(config);
+        }
+        Ok(tree)
+    }
+
+    fn read_trees(reader: &mut impl Read, version: u8, remaining: &mut usize) -> io::Result<Self> {
+        *remaining = remaining
+            .checked_sub(1)
+            .ok_or_else(|| invalid_data("Expected less trees."))?;
+
+        let mut tree = Self::new(
+            <[f64; N]>::read_from(reader)?,
+            <[f64; N]>::read_from(reader)?,
+            usize::read_from(reader)?,
+        );
+        let is_leaf = bool::read_from(reader)?;
+        if !is_leaf && version >= 4 {
+            tree.split_axes = read_split_axes::<N>(reader)?;
+        }
+        if !is_leaf && version >= 5 {
+            tree.branching = read_branching(reader, tree.split_axes, N)?;
+        }
+        if !is_leaf && version >= 6 {
+            tree.split_point = Option::read_from(reader)?;
+        }
+        tree.data = Option::read_from(reader)?;
+        if !is_leaf {
+            tree.children = (0..tree.split_child_count())
+                .map(|_| Self::read_trees(reader, version, remaining))
+                .collect::<io::Result<_>>()?;
+        }
+        Ok(tree)
+    }
+}
+
+impl<T: Persist, const N: usize> TpnTree<T, N> {
+    /// Serializes the subtree at the key relative to this tree as a chunk, e.g. for streaming parts of a world over a network.
+    ///
+    /// Chunks are more compact than [`TpnTree::save_to`], as the regions of the trees follow from the region in the header.
+    /// After a versioned header holding the dimension, the key and the region of the subtree,
+    /// every tree is stored in depth first order as whether it is divided, the axes it is split along, the parts per axis and the split point if so, and its data.
+    /// Errors with [`std::io::ErrorKind::NotFound`] if there is no tree at the key.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::{NodeKey, TpnTree};
+    /// let mut server = TpnTree::<u16, 3>::root(1.0);
+    /// server.divide_to_depth(2).expect("Couldn't divide.");
+    /// *server.get_child_mut(5).unwrap().get_child_mut(1).unwrap().data_mut() = Some(42);
+    ///
+    /// let key = NodeKey::from_path(&[5]).unwrap();
+    /// let chunk = server.export_subtree(key).expect("Key exists.");
+    ///
+    /// let mut client = TpnTree::<u16, 3>::root(1.0);
+    /// client.divide().expect("Couldn't divide.");
+    /// client.import_subtree(key, &chunk).expect("Couldn't import.");
+    ///
+    /// assert_eq!(client.get_child(5), server.get_child(5));
+    /// ```
+    pub fn export_subtree(&self, key: NodeKey<N>) -> io::Result<Vec<u8>> {
+        let tree = self.find_by_key(key).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::NotFound, "There is no tree at the key.")
+        })?;
+
+        let mut bytes = Vec::new();
+        bytes.write_all(CHUNK_MAGIC)?;
+        CHUNK_VERSION.write_to(&mut bytes)?;
+        N.write_to(&mut bytes)?;
+        key.to_bits().write_to(&mut bytes)?;
+        tree.coordinates.write_to(&mut bytes)?;
+        tree.span.write_to(&mut bytes)?;
+        tree.write_chunk_trees(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Replaces the subtree at the key relative to this tree with a chunk written by [`TpnTree::export_subtree`].
+    ///
+    /// The chunk must have been exported for the same key and cover the same region as the tree it replaces,
+    /// otherwise it errors with [`std::io::ErrorKind::InvalidData`], just like for malformed chunks or chunks dividing deeper than the [`TreeConfig`] of the tree allows.
+    /// Errors with [`std::io::ErrorKind::NotFound`] if there is no tree at the key.
+    /// On error the tree is left unchanged.
+    pub fn import_subtree(&mut self, key: NodeKey<N>, mut bytes: &[u8]) -> io::Result<()> {
+        let mut magic = [0; 4];
+        bytes.read_exact(&mut magic)?;
+        if &magic != CHUNK_MAGIC || u8::read_from(&mut bytes)? != CHUNK_VERSION {
+            return Err(invalid_data("Expected a chunk of a known version."));
+        }
+        if usize::read_from(&mut bytes)? != N {
+            return Err(invalid_data("Expected a chunk of the same dimension."));
+        }
+        if NodeKey::from_bits(u128::read_from(&mut bytes)?) != Some(key) {
+            return Err(invalid_data("Expected a chunk exported for the same key."));
+        }
+
+        let target = self.find_by_key_mut(key).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::NotFound, "There is no tree at the key.")
+        })?;
+        if <[f64; N]>::read_from(&mut bytes)? != target.coordinates
+            || <[f64; N]>::read_from(&mut bytes)? != target.span
+        {
+            return Err(invalid_data(
+                "Expected a chunk covering the region of the tree.",
+            ));
+        }
+
+        let mut subtree = Self::new(target.coordinates, target.span, target.level);
+        subtree.config = target.config.clone();
+        subtree.read_chunk_trees(&mut bytes)?;
+        if !bytes.is_empty() {
+            return Err(invalid_data("Expected the chunk to end."));
+        }
+
+        *target = subtree;
+        Ok(())
+    }
+
+    fn write_chunk_trees(&self, writer: &mut impl Write) -> io::Result<()> {
+        self.is_leaf().write_to(writer)?;
+        if !self.is_leaf() {
+            self.split_axes.write_to(writer)?;
+            self.branching.write_to(writer)?;
+            self.split_point.write_to(writer)?;
+        }
+        self.data.write_to(writer)?;
+        self.children
+            .iter()
+            .try_for_each(|child| child.write_chunk_trees(writer))
+    }
+
+    // fills the empty leaf with the structure and data of the chunk
+    fn read_chunk_trees(&mut self, reader: &mut impl Read) -> io::Result<()> {
+        let is_leaf = bool::read_from(reader)?;
+        let (split_axes, branching, split_point) = if is_leaf {
+            (None, 2, None)
+        } else {
+            let split_axes = read_split_axes::<N>(reader)?;
+            let branching = read_branching(reader, split_axes, N)?;
+            (split_axes, branching, Option::read_from(reader)?)
+        };
+        self.data = Option::read_from(reader)?;
+        if !is_leaf {
+            self.divide_along(split_axes, branching, split_point)
+                .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+            for child in &mut self.children {
+                child.read_chunk_trees(reader)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// reads the axes a divided tree is split along, see TpnTree::divide_axes
+fn read_split_axes<const N: usize>(reader: &mut impl Read) -> io::Result<Option<usize>> {
+    let split_axes = Option::<usize>::read_from(reader)?;
+    if split_axes.is_some_and(|axes| axes.checked_shr(N as u32).is_some_and(|rest| rest != 0)) {
+        return Err(invalid_data("Expected split axes below the dimension."));
+    }
+    Ok(split_axes)
+}
+
+// reads the parts per split axis of a divided tree, see TpnTree::divide_with_branching
+fn read_branching(
+    reader: &mut impl Read,
+    split_axes: Option<usize>,
+    dimensions: usize,
+) -> io::Result<usize> {
+    let branching = usize::read_from(reader)?;
+    let split_count = split_axes.map_or(dimensions, |axes| axes.count_ones() as usize);
+    branching::child_count(split_count, branching)
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+    Ok(branching)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::ErrorKind;
+
+    use crate::tpntree::{
+        DuplicatePolicy, NodeKey, Spillover, TpnTree, Tree3D, TreeConfig, WorldTransform,
+    };
+
+    #[test]
+    fn round_trip_keeps_structure_data_and_config() {
+        let mut tree = Tree3D::builder()
+            .leaf_capacity(2)
+            .looseness(2.0)
+            .duplicate_policy(DuplicatePolicy::Replace)
+            .duplicate_tolerance(0.01)
+            .periodic_axes([true, false, true])
+            .epsilon(1e-9)
+            .world_transform(WorldTransform::from_translation([1.0, 2.0, 3.0]))
+            .spillover(Spillover::Split)
+            .build();
+        for data in [
+            [0.5, 0.5, 0.5],
+            [0.6, 0.6, 0.6],
+            [-0.5, 0.5, 0.5],
+            [0.1, -0.9, 0.3],
+        ] {
+            assert!(tree.insert(data).is_ok());
+        }
+
+        let mut bytes = Vec::new();
+        assert!(tree.save_to(&mut bytes).is_ok());
+        let loaded = Tree3D::load_from(bytes.as_slice()).unwrap();
+
+        assert_eq!(loaded, tree);
+        assert_eq!(loaded.config(), tree.config());
+        assert!(loaded
+            .iter_depth_first()
+            .all(|tree| tree.config().and_then(|config| config.leaf_capacity()) == Some(2)));
+    }
+
+    #[test]
+    fn load_rejects_invalid_data() {
+        let tree = TpnTree::<u8, 2>::root(1.0);
+        let mut bytes = Vec::new();
+        assert!(tree.save_to(&mut bytes).is_ok());
+
+        let other_dimension = TpnTree::<u8, 3>::load_from(bytes.as_slice());
+        assert_eq!(other_dimension.unwrap_err().kind(), ErrorKind::InvalidData);
+
+        let truncated = TpnTree::<u8, 2>::load_from(&bytes[..bytes.len() - 1]);
+        assert_eq!(truncated.unwrap_err().kind(), ErrorKind::UnexpectedEof);
+
+        assert!(TpnTree::<u8, 2>::load_from(&b"JSON"[..]).is_err());
+    }
+
+    #[test]
+    fn load_version_one() {
+        let mut tree = TpnTree::<u8, 1>::root(1.0);
+        tree.set_config(TreeConfig::default());
+        let mut bytes = Vec::new();
+        assert!(tree.save_to(&mut bytes).is_ok());
+
+        // magic, version, dimension, config flag and three unset options precede the duplicate settings, periodic axes, epsilon, world transform and spillover
+        bytes[4] = 1;
+        bytes.drain(17..24);
+
+        assert_eq!(TpnTree::<u8, 1>::load_from(bytes.as_slice()).unwrap(), tree);
+    }
+
+    #[test]
+    fn import_validates_chunks() {
+        let mut tree = TpnTree::<u8, 2>::builder().max_depth(2).build();
+        tree.divide_to_depth(2).unwrap();
+        *tree.get_child_mut(1).unwrap().data_mut() = Some(3);
+        let key = NodeKey::from_path(&[1]).unwrap();
+        let chunk = tree.export_subtree(key).unwrap();
+
+        let mut other = tree.clone();
+        let other_key = NodeKey::from_path(&[2]).unwrap();
+        assert_eq!(
+            other.import_subtree(other_key, &chunk).unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+        assert_eq!(
+            TpnTree::<u8, 2>::root(2.0)
+                .import_subtree(
+                    NodeKey::root(),
+                    &tree.export_subtree(NodeKey::root()).unwrap()
+                )
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidData
+        );
+        assert_eq!(
+            other
+                .import_subtree(key, &chunk[..chunk.len() - 1])
+                .unwrap_err()
+                .kind(),
+            ErrorKind::UnexpectedEof
+        );
+        assert_eq!(
+            other
+                .import_subtree(NodeKey::from_path(&[1, 0, 0]).unwrap(), &chunk)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidData
+        );
+        assert_eq!(other, tree);
+
+        // the chunk divides deeper than the config of the target allows
+        let mut shallow = TpnTree::<u8, 2>::builder().max_depth(1).build();
+        shallow.divide().unwrap();
+        assert_eq!(
+            shallow.import_subtree(key, &chunk).unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+        assert!(shallow.get_child(1).unwrap().is_leaf());
+
+        other.get_child_mut(1).unwrap().children.clear();
+        other.import_subtree(key, &chunk).unwrap();
+        assert_eq!(other, tree);
+    }
+}
@@ -0,0 +1,362 @@
+use alloc::{sync::Arc, vec::Vec};
+use core::iter::once;
+
+use super::{Region, SplitLayout, TpnTree};
+use crate::{errors::TpnTreeError, Coordinates};
+
+/// An immutable tree sharing unchanged subtrees between its versions.
+///
+/// Every modification returns a new version, copying only the trees on the path to the change.
+/// Versions are cheap to clone and can be kept for undo and redo or handed to readers on other threads.
+#[derive(Debug, PartialEq)]
+pub struct PersistentTree<T, const N: usize> {
+    region: Region<N>,
+    children: Vec<Arc<Self>>,
+    split: SplitLayout<N>,
+    data: Option<Arc<T>>,
+}
+
+impl<T, const N: usize> Clone for PersistentTree<T, N> {
+    /// Clones the tree in constant time, sharing all children and data.
+    fn clone(&self) -> Self {
+        Self {
+            region: self.region,
+            children: self.children.clone(),
+            split: self.split,
+            data: self.data.clone(),
+        }
+    }
+}
+
+impl<T, const N: usize> PersistentTree<T, N> {
+    /// Creates a new leaf without data, see [`TpnTree::new`].
+    pub fn new(coordinates: [f64; N], span: [f64; N], level: usize) -> Self {
+        Self {
+            region: Region::new(coordinates, span, level),
+            children: Vec::new(),
+            split: SplitLayout::default(),
+            data: None,
+        }
+    }
+
+    /// Creates a new leaf with equal span in all dimension at the center of the space at level zero, see [`TpnTree::root`].
+    pub fn root(span: f64) -> Self {
+        Self::new([0.0; N], [span; N], 0)
+    }
+
+    /// Returns the region covered by the tree.
+    pub fn region(&self) -> Region<N> {
+        self.region
+    }
+
+    /// Returns the data of the tree.
+    pub fn data(&self) -> Option<&T> {
+        self.data.as_deref()
+    }
+
+    /// Returns the child at the index, if the tree is divided.
+    pub fn get_child(&self, index: usize) -> Option<&Self> {
+        self.children.get(index).map(Arc::as_ref)
+    }
+
+    /// Iterates the children in the order of their index.
+    pub fn iter_children(&self) -> impl Iterator<Item = &Self> {
+        self.children.iter().map(Arc::as_ref)
+    }
+
+    /// Checks if the tree has no children.
+    pub fn is_leaf(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    /// Returns how the tree is split into its children, see [`TpnTree::split_layout`].
+    pub fn split_layout(&self) -> SplitLayout<N> {
+        self.split
+    }
+
+    /// Returns a version of the tree divided into 2^N children without data, see [`TpnTree::divide`].
+    ///
+    /// Errors with [`TpnTreeError::CanNotDivide`] if the tree is divided already.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::PersistentTree;
+    /// let before = PersistentTree::<(), 2>::root(1.0);
+    /// let after = before.divide().expect("Couldn't divide.");
+    ///
+    /// assert!(before.is_leaf());
+    /// assert_eq!(after.iter_children().count(), 4);
+    /// ```
+    pub fn divide(&self) -> Result<Self, TpnTreeError> {
+        if !self.is_leaf() {
+            return Err(TpnTreeError::CanNotDivide);
+        }
+        Ok(Self {
+            region: self.region,
+            children: self
+                .region
+                .iter_virtual_children()
+                .map(|region| {
+                    Arc::new(Self {
+                        region,
+                        children: Vec::new(),
+                        split: SplitLayout::default(),
+                        data: None,
+                    })
+                })
+                .collect(),
+            split: SplitLayout::default(),
+            data: self.data.clone(),
+        })
+    }
+
+    /// Returns a version of the tree with the data replaced, sharing all children.
+    pub fn with_data(&self, data: Option<T>) -> Self {
+        Self {
+            region: self.region,
+            children: self.children.clone(),
+            split: self.split,
+            data: data.map(Arc::new),
+        }
+    }
+
+    /// Returns a version of the tree with the descendant at the path of child indices replaced by the result of `f`.
+    ///
+    /// Only the trees along the path are copied, all others are shared with this version.
+    /// Errors with [`TpnTreeError::NoSuchChild`] if the path leads past a leaf, or with the error of `f`.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::PersistentTree;
+    /// let before = PersistentTree::<u8, 2>::root(1.0).divide().expect("Couldn't divide.");
+    ///
+    /// let after = before
+    ///     .update_at(&[3], |child| Ok(child.with_data(Some(1))))
+    ///     .expect("Path exists.");
+    ///
+    /// assert_eq!(after.get_child(3).unwrap().data(), Some(&1));
+    /// assert_eq!(before.get_child(3).unwrap().data(), None);
+    /// ```
+    pub fn update_at(
+        &self,
+        path: &[usize],
+        f: impl FnOnce(&Self) -> Result<Self, TpnTreeError>,
+    ) -> Result<Self, TpnTreeError> {
+        match path.split_first() {
+            None => f(self),
+            Some((&index, rest)) => {
+                let child = self
+                    .get_child(index)
+                    .ok_or(TpnTreeError::NoSuchChild { index })?
+                    .update_at(rest, f)?;
+                Ok(self.with_child(index, child))
+            }
+        }
+    }
+
+    /// Builds a persistent copy of the tree, keeping how it is split.
+    pub fn from_tree(tree: &TpnTree<T, N>) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            region: tree.region(),
+            children: tree
+                .iter_children()
+                .map(|child| Arc::new(Self::from_tree(child)))
+                .collect(),
+            split: tree.split_layout(),
+            data: tree.data().cloned().map(Arc::new),
+        }
+    }
+
+    /// Builds a mutable copy of the tree, without any [`TreeConfig`](super::TreeConfig).
+    pub fn to_tree(&self) -> TpnTree<T, N>
+    where
+        T: Clone,
+    {
+        let mut tree = TpnTree::new(
+            self.region.coordinates(),
+            self.region.span(),
+            self.region.level(),
+        );
+        tree.children = self.iter_children().map(Self::to_tree).collect();
+        if !tree.is_leaf() {
+            tree.set_split_layout(self.split);
+        }
+        tree.data = self.data().cloned();
+        tree
+    }
+
+    fn with_child(&self, index: usize, child: Self) -> Self {
+        let mut tree = self.clone();
+        tree.children[index] = Arc::new(child);
+        tree
+    }
+}
+
+impl<T: Coordinates<N> + Clone, const N: usize> PersistentTree<Vec<T>, N> {
+    /// Returns a version of the tree with the data inserted, see [`SpatialTree::insert_by_coordinates`](super::SpatialTree::insert_by_coordinates).
+    ///
+    /// Only the items of the leaf receiving the data are cloned.
+    /// Errors if the tree does not span the data or the data has other than N coordinates.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::PersistentTree;
+    /// let empty = PersistentTree::<Vec<[f64; 2]>, 2>::root(1.0);
+    ///
+    /// let one = empty.insert_by_coordinates([0.5, 0.5], &|_| false).expect("Couldn't insert.");
+    /// let two = one
+    ///     .insert_by_coordinates([-0.5, 0.5], &|tree| tree.data().is_some())
+    ///     .expect("Couldn't insert.");
+    ///
+    /// assert_eq!(one.data().map(Vec::len), Some(1));
+    /// assert_eq!(two.iter_children().filter(|child| child.data().is_some()).count(), 2);
+    /// ```
+    pub fn insert_by_coordinates(
+        &self,
+        data: T,
+        division_condition: &dyn Fn(&Self) -> bool,
+    ) -> Result<Self, TpnTreeError> {
+        let coordinates = data.try_coordinates()?;
+        let (center, span) = (self.region.coordinates(), self.region.span());
+        if !(0..N).all(|i| (coordinates[i] - center[i]).abs() <= span[i]) {
+            return Err(TpnTreeError::does_not_span(coordinates, &center, &span));
+        }
+        self.insert_unchecked(data, division_condition)
+    }
+
+    fn insert_unchecked(
+        &self,
+        data: T,
+        division_condition: &dyn Fn(&Self) -> bool,
+    ) -> Result<Self, TpnTreeError> {
+        if !self.is_leaf() {
+            return self.insert_into_child(data, division_condition);
+        }
+
+        if division_condition(self) {
+            let mut tree = self.divide()?;
+            let items = tree
+                .data
+                .take()
+                .map(|items| items.as_ref().clone())
+                .unwrap_or_default();
+            for data in items.into_iter().chain(once(data)) {
+                tree = tree.insert_into_child(data, division_condition)?;
+            }
+            Ok(tree)
+        } else {
+            let mut items = self.data().cloned().unwrap_or_default();
+            items.push(data);
+            Ok(self.with_data(Some(items)))
+        }
+    }
+
+    fn insert_into_child(
+        &self,
+        data: T,
+        division_condition: &dyn Fn(&Self) -> bool,
+    ) -> Result<Self, TpnTreeError> {
+        let index = self
+            .split
+            .shape(&self.region)
+            .split_child_index(data.coordinates());
+        let child = self.children[index].insert_unchecked(data, division_condition)?;
+        Ok(self.with_child(index, child))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::tpntree::{PersistentTree, Tree3D};
+    use crate::TpnTreeError;
+
+    fn points() -> Vec<[f64; 3]> {
+        (0..50)
+            .map(|i| {
+                let i = i as f64;
+                [(i * 0.37).sin(), (i * 0.71).cos(), (i * 0.13).sin()]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn versions_share_untouched_subtrees() {
+        let condition = |tree: &PersistentTree<Vec<[f64; 3]>, 3>| {
+            tree.data().is_some_and(|data| data.len() >= 4)
+        };
+        let before = points()
+            .into_iter()
+            .try_fold(PersistentTree::root(1.0), |tree, point| {
+                tree.insert_by_coordinates(point, &condition)
+            })
+            .unwrap();
+
+        let after = before
+            .insert_by_coordinates([0.9, 0.9, 0.9], &condition)
+            .unwrap();
+
+        let changed = before.region().child_index_for_point(&[0.9, 0.9, 0.9]);
+        for (index, (a, b)) in before.children.iter().zip(&after.children).enumerate() {
+            assert_eq!(Arc::ptr_eq(a, b), index != changed);
+        }
+        assert_ne!(before, after);
+    }
+
+    #[test]
+    fn insertion_matches_mutable_tree() {
+        let mut tree = Tree3D::root(1.0);
+        let mut persistent = PersistentTree::root(1.0);
+        for point in points() {
+            tree.insert_by_coordinates(point, &|tree| {
+                tree.data().is_some_and(|data| data.len() >= 3)
+            })
+            .unwrap();
+            persistent = persistent
+                .insert_by_coordinates(point, &|tree| {
+                    tree.data().is_some_and(|data| data.len() >= 3)
+                })
+                .unwrap();
+        }
+
+        assert_eq!(persistent.to_tree(), tree);
+        assert_eq!(PersistentTree::from_tree(&tree), persistent);
+    }
+
+    #[test]
+    fn insertion_follows_the_split_of_the_tree() {
+        let mut tree = Tree3D::root(1.0);
+        assert!(tree.divide_axes(&[0]).is_ok());
+        assert!(tree
+            .get_child_mut(1)
+            .unwrap()
+            .divide_at([-0.25, 0.5, 0.0])
+            .is_ok());
+
+        let mut persistent = PersistentTree::from_tree(&tree);
+        for point in points() {
+            assert!(tree.insert_by_coordinates(point, &|_| false).is_ok());
+            persistent = persistent.insert_by_coordinates(point, &|_| false).unwrap();
+        }
+
+        assert_eq!(persistent.to_tree(), tree);
+        for (a, b) in persistent
+            .to_tree()
+            .iter_depth_first()
+            .zip(tree.iter_depth_first())
+        {
+            assert_eq!(a.split_layout(), b.split_layout());
+        }
+    }
+
+    #[test]
+    fn update_at_reports_missing_children() {
+        let tree = PersistentTree::<(), 2>::root(1.0);
+
+        assert_eq!(
+            tree.update_at(&[1], |tree| Ok(tree.clone())),
+            Err(TpnTreeError::NoSuchChild { index: 1 })
+        );
+    }
+}
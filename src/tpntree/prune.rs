@@ -0,0 +1,136 @@
+use alloc::{collections::BinaryHeap, vec::Vec};
+use core::cmp::Ordering;
+
+use super::{NodePath, TpnTree};
+
+impl<T, const N: usize> TpnTree<T, N> {
+    /// Removes the children of the least valuable trees until the tree holds at most `max_nodes` trees, itself included.
+    ///
+    /// Only trees whose children are all leaves are pruned, i.e. the tree shrinks from its leaves upwards.
+    /// Such a tree becomes a leaf once its `score` is the lowest among all candidates, its data stays and the data of its children is dropped.
+    /// Trees becoming candidates by pruning are scored once the last of their divided children has been pruned.
+    /// To keep a summary of the dropped data, store it in the inner trees beforehand, e.g. with [`TpnTree::aggregate`].
+    ///
+    /// Returns the count of removed trees.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::TpnTree;
+    /// let mut root = TpnTree::<u32, 2>::root(1.0);
+    /// root.divide_to_depth(2).expect("Couldn't divide.");
+    /// // mark the lower left quadrant as recently used
+    /// *root.get_child_mut(3).unwrap().data_mut() = Some(10);
+    ///
+    /// let removed = root.prune_to_budget(9, |tree| tree.data().copied().unwrap_or(0) as f64);
+    ///
+    /// assert_eq!(removed, 12);
+    /// assert!(!root.get_child(3).unwrap().is_leaf());
+    /// ```
+    pub fn prune_to_budget(&mut self, max_nodes: usize, score: impl Fn(&Self) -> f64) -> usize {
+        let mut node_count = self.iter_depth_first().count();
+        let mut candidates = BinaryHeap::new();
+        self.collect_prune_candidates(&mut Vec::new(), &score, &mut candidates);
+
+        let initial_count = node_count;
+        while node_count > max_nodes {
+            let Some(Candidate { path, .. }) = candidates.pop() else {
+                break;
+            };
+            let tree = self
+                .get_descendant_mut(&path)
+                .expect("Candidates lead to existing trees.");
+            node_count -= tree.children.len();
+            tree.clear_children();
+
+            if let Some((_, parent_path)) = path.split_last() {
+                let parent = self
+                    .get_descendant(parent_path)
+                    .expect("Candidates lead to existing trees.");
+                if parent.iter_children().all(TpnTree::is_leaf) {
+                    candidates.push(Candidate {
+                        score: score(parent),
+                        path: parent_path.to_vec(),
+                    });
+                }
+            }
+        }
+        initial_count - node_count
+    }
+
+    fn collect_prune_candidates(
+        &self,
+        path: &mut NodePath,
+        score: &impl Fn(&Self) -> f64,
+        candidates: &mut BinaryHeap<Candidate>,
+    ) {
+        if self.is_leaf() {
+            return;
+        }
+        if self.iter_children().all(TpnTree::is_leaf) {
+            candidates.push(Candidate {
+                score: score(self),
+                path: path.clone(),
+            });
+            return;
+        }
+        for (index, child) in self.children.iter().enumerate() {
+            path.push(index);
+            child.collect_prune_candidates(path, score, candidates);
+            path.pop();
+        }
+    }
+}
+
+// ordered such that the max-heap pops the lowest score first, ties broken by path for determinism
+struct Candidate {
+    score: f64,
+    path: NodePath,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .score
+            .total_cmp(&self.score)
+            .then_with(|| other.path.cmp(&self.path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tpntree::TpnTree;
+
+    #[test]
+    fn prune_lowest_scores_first() {
+        let mut root = TpnTree::<f64, 1>::root(1.0);
+        root.divide_to_depth(3).expect("Couldn't divide.");
+        // score the trees by their position, the trees at the left go first
+        let score = |tree: &TpnTree<f64, 1>| tree.coordinates()[0];
+
+        assert_eq!(root.prune_to_budget(15, score), 0);
+        assert_eq!(root.prune_to_budget(13, score), 2);
+        assert!(root.get_descendant(&[1, 1]).unwrap().is_leaf());
+        assert!(!root.get_descendant(&[1, 0]).unwrap().is_leaf());
+
+        assert_eq!(root.prune_to_budget(9, score), 4);
+        // the left half is collapsed up to its root once its children are pruned
+        assert!(root.get_child(1).unwrap().is_leaf());
+        assert!(!root.get_descendant(&[0, 1]).unwrap().is_leaf());
+
+        assert_eq!(root.prune_to_budget(0, score), 8);
+        assert!(root.is_leaf());
+    }
+}
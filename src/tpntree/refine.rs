@@ -0,0 +1,161 @@
+use super::TpnTree;
+use crate::errors::TpnTreeError;
+
+impl<T, const N: usize> TpnTree<T, N> {
+    /// Divides all leaves recursively until every leaf is at the level `depth`.
+    ///
+    /// Leaves at or below `depth` are left untouched, data stays in the trees that held it.
+    /// Errors if the [`TreeConfig`](super::TreeConfig) of the tree does not allow dividing to `depth`.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::TpnTree;
+    /// let mut root = TpnTree::<(), 2>::root(1.0);
+    ///
+    /// root.divide_to_depth(2).expect("Couldn't divide.");
+    ///
+    /// assert_eq!(root.iter_depth_first().filter(|tree| tree.is_leaf()).count(), 16);
+    /// ```
+    pub fn divide_to_depth(&mut self, depth: usize) -> Result<(), TpnTreeError> {
+        self.refine_where(|_| true, depth)
+    }
+
+    /// Divides all leaves recursively as long as they fulfill the `predicate` and are above the level `max_depth`.
+    ///
+    /// The predicate is checked again on each newly created child.
+    /// Errors if the [`TreeConfig`](super::TreeConfig) of the tree does not allow dividing to `max_depth`.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::TpnTree;
+    /// let mut root = TpnTree::<(), 2>::root(1.0);
+    ///
+    /// // refine the upper right quadrant
+    /// root.refine_where(|tree| tree.coordinates().iter().all(|&c| c >= 0.0), 3)
+    ///     .expect("Couldn't divide.");
+    ///
+    /// assert_eq!(root.iter_depth_first().filter(|tree| tree.level() == 3).count(), 16);
+    /// ```
+    pub fn refine_where(
+        &mut self,
+        predicate: impl Fn(&Self) -> bool,
+        max_depth: usize,
+    ) -> Result<(), TpnTreeError> {
+        self.refine_recursive(&predicate, max_depth)
+    }
+
+    /// Divides all leaves recursively as long as their error exceeds the `tolerance` and they are above the level `max_depth`.
+    ///
+    /// The `error_fn` measures how badly a leaf resolves whatever the tree approximates, for example the spread of a sampled field over its region.
+    /// Errors if the [`TreeConfig`](super::TreeConfig) of the tree does not allow dividing to `max_depth`.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::TpnTree;
+    /// let mut root = TpnTree::<(), 1>::root(1.0);
+    ///
+    /// // a step at 0.3 needs fine resolution around it only
+    /// let field = |x: f64| if x < 0.3 { 0.0_f64 } else { 1.0 };
+    /// let error = |tree: &TpnTree<(), 1>| {
+    ///     let [x] = tree.coordinates();
+    ///     let [span] = tree.span();
+    ///     (field(x + span) - field(x - span)).abs()
+    /// };
+    ///
+    /// root.refine_adaptively(error, 0.5, 4).expect("Couldn't divide.");
+    ///
+    /// // one leaf per level around the step, plus two at the deepest level
+    /// assert_eq!(root.iter_depth_first().filter(|tree| tree.is_leaf()).count(), 5);
+    /// ```
+    pub fn refine_adaptively(
+        &mut self,
+        error_fn: impl Fn(&Self) -> f64,
+        tolerance: f64,
+        max_depth: usize,
+    ) -> Result<(), TpnTreeError> {
+        self.refine_where(|tree| error_fn(tree) > tolerance, max_depth)
+    }
+
+    fn refine_recursive(
+        &mut self,
+        predicate: &dyn Fn(&Self) -> bool,
+        max_depth: usize,
+    ) -> Result<(), TpnTreeError> {
+        if self.is_leaf() {
+            if self.level >= max_depth || !predicate(self) {
+                return Ok(());
+            }
+            self.divide()?;
+        }
+
+        for child in &mut self.children {
+            child.refine_recursive(predicate, max_depth)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tpntree::TpnTree;
+    use crate::TpnTreeError;
+
+    #[test]
+    fn divide_to_depth_is_uniform() {
+        let mut tree = TpnTree::<(), 3>::root(1.0);
+
+        assert!(tree.divide().is_ok());
+        assert!(tree.get_child_mut(0).unwrap().divide_to_depth(3).is_ok());
+        assert!(tree.divide_to_depth(2).is_ok());
+
+        let leaf_levels = tree
+            .iter_depth_first()
+            .filter(|tree| tree.is_leaf())
+            .map(|tree| tree.level())
+            .collect::<Vec<_>>();
+
+        assert_eq!(leaf_levels.iter().filter(|&&level| level == 3).count(), 64);
+        assert_eq!(leaf_levels.iter().filter(|&&level| level == 2).count(), 56);
+        assert_eq!(leaf_levels.len(), 120);
+    }
+
+    #[test]
+    fn refine_respects_config() {
+        let mut tree = TpnTree::<(), 2>::builder().max_depth(1).build();
+
+        assert_eq!(
+            tree.divide_to_depth(2),
+            Err(TpnTreeError::MaxDepthExceeded { max_level: 1 })
+        );
+        assert!(tree.refine_where(|tree| tree.is_root(), 2).is_ok());
+        assert_eq!(tree.child_count(), 4);
+    }
+
+    #[test]
+    fn refine_adaptively_around_a_circle() {
+        let mut tree = TpnTree::<(), 2>::root(1.0);
+
+        // signed distance to a circle of radius 0.5
+        let distance = |x: f64, y: f64| (x * x + y * y).sqrt() - 0.5;
+        let error = |tree: &TpnTree<(), 2>| {
+            let [x, y] = tree.coordinates();
+            let [span_x, span_y] = tree.span();
+            // the circle crosses a tree if its center is closer than its half diagonal
+            let half_diagonal = (span_x * span_x + span_y * span_y).sqrt();
+            half_diagonal - distance(x, y).abs()
+        };
+
+        assert!(tree.refine_adaptively(error, 0.0, 4).is_ok());
+
+        let deepest = tree
+            .iter_depth_first()
+            .filter(|tree| tree.level() == 4)
+            .collect::<Vec<_>>();
+        assert!(!deepest.is_empty());
+        assert!(deepest
+            .iter()
+            .all(|tree| error(tree) > 0.0 || tree.is_leaf()));
+        // trees far from the circle stay coarse
+        assert!(tree
+            .iter_depth_first()
+            .filter(|tree| tree.is_leaf() && tree.level() < 4)
+            .all(|tree| error(tree) <= 0.0));
+    }
+}
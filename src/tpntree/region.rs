@@ -0,0 +1,339 @@
+use alloc::{vec, vec::Vec};
+
+use super::TpnTree;
+
+/// The region covered by a tree, without any children or data.
+///
+/// Regions describe children that have not been created by division, see [`TpnTree::virtual_child`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Region<const N: usize> {
+    coordinates: [f64; N],
+    span: [f64; N],
+    level: usize,
+}
+
+impl<const N: usize> Region<N> {
+    /// Creates a new region.
+    pub fn new(coordinates: [f64; N], span: [f64; N], level: usize) -> Self {
+        Self {
+            coordinates,
+            span,
+            level,
+        }
+    }
+
+    /// Returns the coordinates of the center of the region.
+    pub fn coordinates(&self) -> [f64; N] {
+        self.coordinates
+    }
+
+    /// Returns the span of the region.
+    pub fn span(&self) -> [f64; N] {
+        self.span
+    }
+
+    /// Returns the level a tree covering the region has.
+    pub fn level(&self) -> usize {
+        self.level
+    }
+
+    /// Returns the region of the child at the index, ordered like the children of a tree divided with [`TpnTree::divide`].
+    ///
+    /// A region does not know where a tree covering it would be split, so its children always split it at its center along all axes.
+    /// Use [`TpnTree::virtual_child`] for the children of trees divided in other ways.
+    ///
+    /// Panics if the index is not below 2^N.
+    pub fn virtual_child(&self, index: usize) -> Self {
+        let (coordinates, span) = self.as_tree().child_region(index);
+        Self::new(coordinates, span, self.level + 1)
+    }
+
+    /// Iterates the regions of all 2^N children, splitting the region at its center along all axes.
+    pub fn iter_virtual_children(&self) -> impl Iterator<Item = Self> + '_ {
+        (0..1 << N).map(move |index| self.virtual_child(index))
+    }
+
+    /// Returns the index of the child region covering the point, see [`TpnTree::child_index_for_point`].
+    pub fn child_index_for_point(&self, point: &[f64; N]) -> usize {
+        self.as_tree().child_index_for_point(point)
+    }
+
+    // checks for an overlap with non-zero volume, touching boxes do not overlap
+    pub(crate) fn overlaps_box(&self, center: &[f64; N], half_extent: &[f64; N]) -> bool {
+        (0..N).all(|i| (self.coordinates[i] - center[i]).abs() < self.span[i] + half_extent[i])
+    }
+
+    // a childless tree without data does not allocate
+    fn as_tree(&self) -> TpnTree<(), N> {
+        TpnTree::new(self.coordinates, self.span, self.level)
+    }
+}
+
+/// [`RegionNode`] gives access to the region and data of every kind of tree with N dimensions that stores its children itself.
+///
+/// It is implemented by [`TpnTree`] and [`SparseTree`](super::SparseTree), so code reading their nodes can be written once.
+/// [`TpnTree`] also provides all of these as inherent methods, which take precedence when the trait is in scope.
+///
+/// ```
+/// # use tpntree::tpntree::{RegionNode, SparseTree, TpnTree};
+/// fn describe<T>(tree: &impl RegionNode<T, 2>) -> (usize, [f64; 2], bool) {
+///     (tree.level(), tree.span(), tree.data().is_some())
+/// }
+///
+/// let mut dense = TpnTree::<u8, 2>::root(1.0);
+/// let mut sparse = SparseTree::<u8, 2>::root(1.0);
+/// *dense.data_mut() = Some(1);
+/// *sparse.data_mut() = Some(1);
+///
+/// assert_eq!(describe(&dense), describe(&sparse));
+/// ```
+pub trait RegionNode<T, const N: usize> {
+    /// Returns the region covered by the tree.
+    fn region(&self) -> Region<N>;
+
+    /// Returns the data by reference of the tree.
+    fn data(&self) -> Option<&T>;
+
+    /// Returns the data by mutable reference of the tree.
+    fn data_mut(&mut self) -> &mut Option<T>;
+
+    /// Returns the count of direct children.
+    fn child_count(&self) -> usize;
+
+    /// Returns the coordinates of the center of the tree.
+    fn coordinates(&self) -> [f64; N] {
+        self.region().coordinates()
+    }
+
+    /// Returns the span of the tree.
+    fn span(&self) -> [f64; N] {
+        self.region().span()
+    }
+
+    /// Returns the level of the tree.
+    fn level(&self) -> usize {
+        self.region().level()
+    }
+
+    /// Returns wheter the tree is a leaf.
+    fn is_leaf(&self) -> bool {
+        self.child_count() == 0
+    }
+}
+
+impl<T, const N: usize> RegionNode<T, N> for TpnTree<T, N> {
+    fn region(&self) -> Region<N> {
+        TpnTree::region(self)
+    }
+
+    fn data(&self) -> Option<&T> {
+        TpnTree::data(self)
+    }
+
+    fn data_mut(&mut self) -> &mut Option<T> {
+        TpnTree::data_mut(self)
+    }
+
+    fn child_count(&self) -> usize {
+        TpnTree::child_count(self)
+    }
+}
+
+impl<T, const N: usize> TpnTree<T, N> {
+    /// Returns the region covered by the tree.
+    pub fn region(&self) -> Region<N> {
+        Region::new(self.coordinates, self.span, self.level)
+    }
+
+    /// Returns the region the child at the index has or would have after division, without creating it.
+    ///
+    /// Like [`TpnTree::child_region`], a divided tree reports its actual children wherever and however it has been split,
+    /// while a leaf reports the children [`TpnTree::divide`] would create.
+    ///
+    /// Panics if the index is not below the child count of a divided tree, or not below 2^N for a leaf.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::TpnTree;
+    /// let mut root = TpnTree::<(), 2>::root(1.0);
+    ///
+    /// let child = root.virtual_child(3);
+    ///
+    /// assert_eq!(child.coordinates(), [-0.5, -0.5]);
+    /// assert_eq!(child.level(), 1);
+    /// assert!(root.is_leaf());
+    ///
+    /// root.divide_at([0.5, 0.5]).expect("Couldn't divide.");
+    /// assert_eq!(root.virtual_child(3).coordinates(), [-0.25, -0.25]);
+    /// ```
+    pub fn virtual_child(&self, index: usize) -> Region<N> {
+        let (coordinates, span) = self.child_region(index);
+        Region::new(coordinates, span, self.level + 1)
+    }
+
+    /// Iterates the regions all children have or would have after division, without creating them.
+    ///
+    /// A divided tree yields as many regions as it has children, a leaf yields the 2^N regions [`TpnTree::divide`] would create.
+    pub fn iter_virtual_children(&self) -> impl Iterator<Item = Region<N>> + '_ {
+        let count = if self.is_leaf() {
+            1 << N
+        } else {
+            self.children.len()
+        };
+        (0..count).map(move |index| self.virtual_child(index))
+    }
+
+    /// Returns the regions at exactly the level that overlap the box given by center and half extent, together with the tree covering each of them.
+    ///
+    /// Trees at the level cover their own region.
+    /// Regions below leaves above the level are created virtually and covered by the leaf, so the result is a fixed resolution grid of the box.
+    /// Regions only touching the box are skipped, levels above the level of the tree yield nothing.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::TpnTree;
+    /// let mut root = TpnTree::<u8, 2>::root(1.0);
+    /// root.divide().expect("Couldn't divide.");
+    /// root.get_child_mut(0).unwrap().divide_to_depth(3).expect("Couldn't divide.");
+    ///
+    /// let cells = root.query_region_at_level([0.0, 0.0], [1.0, 1.0], 2);
+    ///
+    /// assert_eq!(cells.len(), 16);
+    /// assert!(cells.iter().all(|(region, _)| region.level() == 2));
+    /// // the three undivided quadrants cover four cells each
+    /// assert_eq!(cells.iter().filter(|(_, tree)| tree.level() == 1).count(), 12);
+    /// ```
+    pub fn query_region_at_level(
+        &self,
+        center: [f64; N],
+        half_extent: [f64; N],
+        level: usize,
+    ) -> Vec<(Region<N>, &Self)> {
+        let mut cells = Vec::new();
+        self.query_region_at_level_recursive(&center, &half_extent, level, &mut cells);
+        cells
+    }
+
+    fn query_region_at_level_recursive<'a>(
+        &'a self,
+        center: &[f64; N],
+        half_extent: &[f64; N],
+        level: usize,
+        cells: &mut Vec<(Region<N>, &'a Self)>,
+    ) {
+        if self.level > level || !self.region().overlaps_box(center, half_extent) {
+            return;
+        }
+        if self.level == level {
+            cells.push((self.region(), self));
+        } else if self.is_leaf() {
+            let mut regions = vec![self.region()];
+            while let Some(region) = regions.pop() {
+                if region.level() == level {
+                    cells.push((region, self));
+                    continue;
+                }
+                regions.extend(
+                    region
+                        .iter_virtual_children()
+                        .filter(|child| child.overlaps_box(center, half_extent)),
+                );
+            }
+        } else {
+            for child in &self.children {
+                child.query_region_at_level_recursive(center, half_extent, level, cells);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tpntree::TpnTree;
+
+    #[test]
+    fn virtual_children_match_divided_children() {
+        let mut tree = TpnTree::<(), 3>::new([1.0, 0.0, -1.0], [2.0, 1.0, 0.5], 3);
+
+        let virtual_children = tree.iter_virtual_children().collect::<Vec<_>>();
+        assert!(tree.divide().is_ok());
+
+        assert_eq!(virtual_children.len(), 8);
+        for (region, child) in virtual_children.iter().zip(tree.iter_children()) {
+            assert_eq!(*region, child.region());
+        }
+    }
+
+    #[test]
+    fn virtual_children_follow_the_split() {
+        let mut off_center = TpnTree::<(), 2>::root(1.0);
+        let mut anisotropic = TpnTree::<(), 3>::root(1.0);
+        let mut branching = TpnTree::<(), 2>::root(1.0);
+
+        assert!(off_center.divide_at([0.5, -0.25]).is_ok());
+        assert!(anisotropic.divide_axes(&[1]).is_ok());
+        assert!(branching.divide_with_branching(3).is_ok());
+
+        assert_eq!(off_center.iter_virtual_children().count(), 4);
+        for (region, child) in off_center
+            .iter_virtual_children()
+            .zip(off_center.iter_children())
+        {
+            assert_eq!(region, child.region());
+        }
+        assert_eq!(anisotropic.iter_virtual_children().count(), 2);
+        for (region, child) in anisotropic
+            .iter_virtual_children()
+            .zip(anisotropic.iter_children())
+        {
+            assert_eq!(region, child.region());
+        }
+        assert_eq!(branching.iter_virtual_children().count(), 9);
+        for (region, child) in branching
+            .iter_virtual_children()
+            .zip(branching.iter_children())
+        {
+            assert_eq!(region, child.region());
+        }
+    }
+
+    #[test]
+    fn query_at_level_tiles_the_box() {
+        let mut tree = TpnTree::<(), 3>::root(1.0);
+        assert!(tree
+            .refine_where(|tree| tree.coordinates()[1] <= 0.0, 3)
+            .is_ok());
+
+        let (center, half_extent) = ([0.3, 0.0, -0.1], [0.2, 0.5, 0.3]);
+        let cells = tree.query_region_at_level(center, half_extent, 2);
+
+        // cells of level 2 have a width of 0.5, the box overlaps one along x and two along y and z
+        assert_eq!(cells.len(), 4);
+        for (region, covering) in cells.iter() {
+            assert_eq!(region.level(), 2);
+            assert!(covering
+                .region()
+                .overlaps_box(&region.coordinates(), &region.span()));
+            assert!(covering.level() == 2 || (covering.is_leaf() && covering.level() < 2));
+        }
+        assert_eq!(tree.query_region_at_level(center, half_extent, 0).len(), 1);
+        assert!(tree
+            .get_child(0)
+            .unwrap()
+            .query_region_at_level(center, half_extent, 0)
+            .is_empty());
+    }
+
+    #[test]
+    fn descend_virtually() {
+        let tree = TpnTree::<(), 2>::root(1.0);
+
+        let point = [0.3, -0.8];
+        let mut region = tree.region();
+        for _ in 0..4 {
+            region = region.virtual_child(region.child_index_for_point(&point));
+        }
+
+        assert_eq!(region.level(), 4);
+        assert_eq!(region.span(), [0.0625, 0.0625]);
+        assert!((0..2).all(|i| (region.coordinates()[i] - point[i]).abs() <= region.span()[i]));
+    }
+}
@@ -0,0 +1,157 @@
+use alloc::{string::String, string::ToString, vec::Vec};
+use core::fmt::Write;
+
+use super::TpnTree;
+
+/// The edges of the boxes of all leaves of a three dimensional tree.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WireframeMesh {
+    /// Corners of the boxes, eight per leaf.
+    pub vertices: Vec<[f64; 3]>,
+    /// Pairs of indices into `vertices`, twelve per leaf.
+    pub edges: Vec<[usize; 2]>,
+}
+
+impl<T> TpnTree<T, 2> {
+    /// Draws the rectangles of all trees as SVG.
+    ///
+    /// The y-axis points up, so the picture matches the coordinates of the tree.
+    pub fn to_svg(&self) -> String {
+        self.to_svg_with(|_| None)
+    }
+
+    /// Draws the rectangles of all trees as SVG, filling each with the color returned from `color_fn`, if any.
+    ///
+    /// Colors are used verbatim as SVG fill attribute, e.g. `"red"` or `"#ff0000"`.
+    /// Children are drawn after, i.e. on top of, their parent.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::TpnTree;
+    /// let mut root = TpnTree::<(), 2>::root(1.0);
+    /// root.divide().expect("Couldn't divide.");
+    ///
+    /// let svg = root.to_svg_with(|tree| tree.is_leaf().then(|| "red".to_string()));
+    ///
+    /// assert!(svg.starts_with("<svg"));
+    /// assert_eq!(svg.matches("<rect").count(), 5);
+    /// assert_eq!(svg.matches("fill=\"red\"").count(), 4);
+    /// ```
+    pub fn to_svg_with(&self, color_fn: impl Fn(&Self) -> Option<String>) -> String {
+        let [x, y] = self.coordinates;
+        let [span_x, span_y] = self.span;
+
+        let mut svg = String::new();
+        // writing into a String can not fail
+        let _ = writeln!(
+            svg,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{} {} {} {}\">",
+            x - span_x,
+            -(y + span_y),
+            2.0 * span_x,
+            2.0 * span_y
+        );
+        for tree in self.iter_depth_first() {
+            let [x, y] = tree.coordinates;
+            let [span_x, span_y] = tree.span;
+            let _ = writeln!(
+                svg,
+                "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\" stroke=\"black\" vector-effect=\"non-scaling-stroke\"/>",
+                x - span_x,
+                -(y + span_y),
+                2.0 * span_x,
+                2.0 * span_y,
+                color_fn(tree).unwrap_or_else(|| "none".to_string())
+            );
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+impl<T> TpnTree<T, 3> {
+    /// Collects the corners and edges of the boxes of all leaves.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::TpnTree;
+    /// let mut root = TpnTree::<(), 3>::root(1.0);
+    /// root.divide().expect("Couldn't divide.");
+    ///
+    /// let mesh = root.to_wireframe_mesh();
+    ///
+    /// assert_eq!(mesh.vertices.len(), 8 * 8);
+    /// assert_eq!(mesh.edges.len(), 8 * 12);
+    /// ```
+    pub fn to_wireframe_mesh(&self) -> WireframeMesh {
+        let mut mesh = WireframeMesh::default();
+        for leaf in self.iter_depth_first().filter(|tree| tree.is_leaf()) {
+            let offset = mesh.vertices.len();
+            // corner i lies above the center along axis a if bit a of i is set
+            for i in 0..8 {
+                let mut corner = leaf.coordinates;
+                for (axis, coordinate) in corner.iter_mut().enumerate() {
+                    if i & (1 << axis) != 0 {
+                        *coordinate += leaf.span[axis];
+                    } else {
+                        *coordinate -= leaf.span[axis];
+                    }
+                }
+                mesh.vertices.push(corner);
+            }
+            // corners connected by an edge differ in exactly one bit
+            for i in 0..8 {
+                for axis in 0..3 {
+                    if i & (1 << axis) == 0 {
+                        mesh.edges.push([offset + i, offset + (i | (1 << axis))]);
+                    }
+                }
+            }
+        }
+        mesh
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use crate::tpntree::TpnTree;
+
+    #[test]
+    fn svg_flips_y_axis() {
+        let tree = TpnTree::<(), 2>::new([1.0, 1.0], [1.0, 0.5], 0);
+
+        let svg = tree.to_svg();
+
+        assert!(svg.contains("viewBox=\"0 -1.5 2 1\""));
+        assert!(svg.contains("<rect x=\"0\" y=\"-1.5\" width=\"2\" height=\"1\" fill=\"none\""));
+    }
+
+    #[test]
+    fn svg_draws_children_after_parent() {
+        let mut tree = TpnTree::<(), 2>::root(1.0);
+
+        assert!(tree.divide().is_ok());
+
+        let svg = tree.to_svg_with(|tree| tree.is_root().then(|| "blue".to_string()));
+
+        let root = svg.find("fill=\"blue\"").unwrap();
+        let child = svg.find("fill=\"none\"").unwrap();
+        assert!(root < child);
+    }
+
+    #[test]
+    fn wireframe_of_single_box() {
+        let tree = TpnTree::<(), 3>::root(1.0);
+
+        let mesh = tree.to_wireframe_mesh();
+
+        assert_eq!(mesh.vertices.len(), 8);
+        assert_eq!(mesh.edges.len(), 12);
+        assert!(mesh.vertices.contains(&[1.0, 1.0, 1.0]));
+        assert!(mesh.vertices.contains(&[-1.0, -1.0, -1.0]));
+        // every edge has length two
+        assert!(mesh.edges.iter().all(|[a, b]| {
+            let (a, b) = (mesh.vertices[*a], mesh.vertices[*b]);
+            (0..3).map(|i| (a[i] - b[i]).abs()).sum::<f64>() == 2.0
+        }));
+    }
+}
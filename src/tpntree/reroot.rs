@@ -0,0 +1,124 @@
+use alloc::vec::Vec;
+
+use super::{SpatialTree, TpnTree};
+use crate::{errors::TpnTreeError, Coordinates};
+
+impl<T, const N: usize> TpnTree<T, N> {
+    /// Replaces the tree by its only child holding data, as long as the tree itself holds none, keeping the level of the tree.
+    ///
+    /// Use this on a root whose data has drifted into a small part of it.
+    /// Returns how many levels the tree shrunk by.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::TpnTree;
+    /// let mut root = TpnTree::<u8, 2>::root(1.0);
+    /// root.divide_to_depth(2).expect("Couldn't divide.");
+    /// *root.get_child_mut(1).unwrap().get_child_mut(2).unwrap().data_mut() = Some(1);
+    ///
+    /// assert_eq!(root.shrink_to_fit(), 2);
+    /// assert_eq!(root.coordinates(), [-0.25, 0.25]);
+    /// assert_eq!(root.level(), 0);
+    /// assert_eq!(root.data(), Some(&1));
+    /// ```
+    pub fn shrink_to_fit(&mut self) -> usize {
+        let mut shrunk = 0;
+        while self.data.is_none() {
+            let mut holding_data = self
+                .children
+                .iter()
+                .enumerate()
+                .filter(|(_, child)| child.iter_depth_first().any(|tree| tree.data.is_some()))
+                .map(|(index, _)| index);
+            let index = match (holding_data.next(), holding_data.next()) {
+                (Some(index), None) => index,
+                _ => break,
+            };
+
+            let mut child = self.children.swap_remove(index);
+            child.set_level(self.level);
+            *self = child;
+            shrunk += 1;
+        }
+        shrunk
+    }
+}
+
+impl<T: Coordinates<N>, const N: usize> SpatialTree<T, N> {
+    /// Moves the tree to new bounds by removing all children and inserting all data again, see [`SpatialTree::refit`].
+    ///
+    /// Errors if the new bounds do not span all data, the tree is left unchanged then.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::Tree3D;
+    /// let mut tree = Tree3D::builder().leaf_capacity(1).build();
+    /// tree.insert([0.5, 0.5, 0.5]).expect("Couldn't insert.");
+    /// tree.insert([0.6, 0.5, 0.5]).expect("Couldn't insert.");
+    ///
+    /// tree.reroot([0.5; 3], [0.25; 3]).expect("Couldn't reroot.");
+    ///
+    /// assert_eq!(tree.coordinates(), [0.5; 3]);
+    /// assert!(tree.reroot([-0.5; 3], [0.25; 3]).is_err());
+    /// assert_eq!(tree.coordinates(), [0.5; 3]);
+    /// ```
+    pub fn reroot(&mut self, coordinates: [f64; N], span: [f64; N]) -> Result<(), TpnTreeError> {
+        let bounds = TpnTree::<(), N>::new(coordinates, span, self.level);
+        if let Some(outside) = self
+            .iter_depth_first()
+            .filter_map(|tree| tree.data())
+            .flatten()
+            .find(|data| !bounds.spans_coordinates(data.coordinates()))
+        {
+            return Err(bounds.does_not_span(outside.coordinates()));
+        }
+
+        let mut data = Vec::new();
+        self.drain_into(&mut data);
+        self.coordinates = coordinates;
+        self.span = span;
+        for data in data {
+            self.insert(data)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tpntree::{SpatialTree, TpnTree};
+
+    #[test]
+    fn shrink_stops_at_data() {
+        let mut root = TpnTree::<u8, 1>::root(1.0);
+        root.divide_to_depth(3).expect("Couldn't divide.");
+        *root.get_descendant_mut(&[0, 1, 0]).unwrap().data_mut() = Some(1);
+        *root.get_descendant_mut(&[0, 1, 1]).unwrap().data_mut() = Some(2);
+
+        assert_eq!(root.shrink_to_fit(), 2);
+        assert_eq!(root.coordinates(), [0.25]);
+        assert_eq!(root.child_count(), 2);
+        assert!(root.iter_children().all(|child| child.level() == 1));
+        assert_eq!(root.shrink_to_fit(), 0);
+    }
+
+    #[test]
+    fn reroot_keeps_all_data() {
+        let mut tree = SpatialTree::<[f64; 2], 2>::builder()
+            .leaf_capacity(1)
+            .build();
+        let points = [[0.1, 0.1], [0.2, 0.3], [0.9, 0.8]];
+        for point in points.iter() {
+            tree.insert(*point).unwrap();
+        }
+
+        tree.reroot([0.5, 0.5], [0.5, 0.5]).unwrap();
+
+        for point in points.iter() {
+            assert!(tree
+                .find_by_coordinates(point)
+                .unwrap()
+                .data()
+                .is_some_and(|data| data.contains(point)));
+        }
+        assert!(tree.config().is_some());
+    }
+}
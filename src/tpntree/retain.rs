@@ -0,0 +1,104 @@
+use alloc::vec::Vec;
+
+use super::SpatialTree;
+
+impl<T, const N: usize> SpatialTree<T, N> {
+    /// Keeps only the items for which `f` returns true and collapses trees whose subtree holds no items anymore.
+    ///
+    /// Trees left without items hold no data afterwards, see [`SpatialTree::drain_filter`].
+    ///
+    /// ```
+    /// # use tpntree::tpntree::Tree3D;
+    /// let mut tree = Tree3D::builder().leaf_capacity(1).build();
+    /// tree.insert([0.5, 0.5, 0.5]).expect("Couldn't insert.");
+    /// tree.insert([-0.5, 0.5, 0.5]).expect("Couldn't insert.");
+    ///
+    /// tree.retain(|item| item[0] < 0.0);
+    ///
+    /// assert_eq!(tree.iter_data().collect::<Vec<_>>(), vec![&[-0.5, 0.5, 0.5]]);
+    /// ```
+    pub fn retain(&mut self, mut f: impl FnMut(&T) -> bool) {
+        self.drain_filter(|item| !f(item));
+    }
+
+    /// Removes and returns the items for which `f` returns true and collapses trees whose subtree holds no items anymore.
+    ///
+    /// The items are returned in the order of [`SpatialTree::iter_data`].
+    /// Trees left without items hold no data afterwards.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::Tree3D;
+    /// let mut tree = Tree3D::builder().leaf_capacity(1).build();
+    /// tree.insert([0.5, 0.5, 0.5]).expect("Couldn't insert.");
+    /// tree.insert([-0.5, 0.5, 0.5]).expect("Couldn't insert.");
+    ///
+    /// assert_eq!(tree.drain_filter(|item| item[0] < 0.0), vec![[-0.5, 0.5, 0.5]]);
+    /// assert_eq!(tree.drain_filter(|_| true), vec![[0.5, 0.5, 0.5]]);
+    /// assert!(tree.is_leaf());
+    /// ```
+    pub fn drain_filter(&mut self, mut f: impl FnMut(&T) -> bool) -> Vec<T> {
+        let mut drained = Vec::new();
+        self.drain_filter_into(&mut f, &mut drained);
+        drained
+    }
+
+    fn drain_filter_into(&mut self, f: &mut impl FnMut(&T) -> bool, drained: &mut Vec<T>) {
+        if let Some(items) = self.data.take() {
+            let (removed, kept): (Vec<_>, Vec<_>) = items.into_iter().partition(|item| f(item));
+            drained.extend(removed);
+            self.data = (!kept.is_empty()).then_some(kept);
+        }
+
+        // the depth first iteration visits the children in reverse order
+        for child in self.children.iter_mut().rev() {
+            child.drain_filter_into(f, drained);
+        }
+
+        let emptied = self
+            .children
+            .iter()
+            .all(|child| child.is_leaf() && child.data.is_none());
+        if !self.is_leaf() && emptied && self.data.is_none() {
+            self.clear_children();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tpntree::Tree3D;
+
+    #[test]
+    fn drained_branches_collapse() {
+        let mut tree = Tree3D::builder().leaf_capacity(2).max_depth(6).build();
+        let points = (0..40)
+            .map(|i| {
+                let t = i as f64;
+                [(t * 0.3).sin(), (t * 0.7).cos(), (t * 1.1).sin()]
+            })
+            .collect::<Vec<_>>();
+        tree.extend(points.clone());
+        let order = tree.iter_data().copied().collect::<Vec<_>>();
+
+        let drained = tree.drain_filter(|item| item[0] > 0.0);
+
+        assert_eq!(
+            drained,
+            order
+                .iter()
+                .filter(|item| item[0] > 0.0)
+                .copied()
+                .collect::<Vec<_>>()
+        );
+        assert!(tree.iter_data().all(|item| item[0] <= 0.0));
+        assert_eq!(drained.len() + tree.iter_data().count(), points.len());
+        assert!(tree
+            .iter_depth_first()
+            .filter(|tree| !tree.is_leaf())
+            .all(|tree| tree.iter_depth_first().any(|tree| tree.data().is_some())));
+
+        tree.retain(|_| false);
+        assert!(tree.is_leaf());
+        assert!(tree.data().is_none());
+    }
+}
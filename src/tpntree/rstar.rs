@@ -0,0 +1,62 @@
+use rstar::AABB;
+
+use super::TpnTree;
+
+impl<T, const N: usize> From<&TpnTree<T, N>> for AABB<[f64; N]> {
+    /// Converts the volume covered by the tree into an rstar bounding box.
+    fn from(tree: &TpnTree<T, N>) -> Self {
+        let (mut lower, mut upper) = (tree.coordinates(), tree.coordinates());
+        for i in 0..N {
+            lower[i] -= tree.span()[i];
+            upper[i] += tree.span()[i];
+        }
+        AABB::from_corners(lower, upper)
+    }
+}
+
+impl<T, const N: usize> From<AABB<[f64; N]>> for TpnTree<T, N> {
+    /// Creates a new root TpnTree covering the rstar bounding box.
+    fn from(aabb: AABB<[f64; N]>) -> Self {
+        let (lower, upper) = (aabb.lower(), aabb.upper());
+        let mut coordinates = [0.0; N];
+        let mut span = [0.0; N];
+        for i in 0..N {
+            coordinates[i] = (lower[i] + upper[i]) / 2.0;
+            span[i] = (upper[i] - lower[i]) / 2.0;
+        }
+        TpnTree::new(coordinates, span, 0)
+    }
+}
+
+impl<T, const N: usize> TpnTree<T, N> {
+    /// Returns the volume covered by the TpnTree as rstar bounding box.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::TpnTree;
+    /// use rstar::AABB;
+    ///
+    /// let root = TpnTree::<(), 3>::root(1.0);
+    ///
+    /// assert_eq!(root.aabb(), AABB::from_corners([-1.0; 3], [1.0; 3]));
+    /// ```
+    pub fn aabb(&self) -> AABB<[f64; N]> {
+        self.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstar::AABB;
+
+    use crate::tpntree::TpnTree;
+
+    #[test]
+    fn convert_between_trees_and_aabbs() {
+        let aabb = AABB::from_corners([0.0, -2.0, 1.0], [2.0, 2.0, 1.5]);
+        let tree = TpnTree::<(), 3>::from(aabb);
+
+        assert_eq!(tree.coordinates(), [1.0, 0.0, 1.25]);
+        assert_eq!(tree.span(), [1.0, 2.0, 0.25]);
+        assert_eq!(tree.aabb(), aabb);
+    }
+}
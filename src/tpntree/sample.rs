@@ -0,0 +1,136 @@
+use super::TpnTree;
+use crate::errors::TpnTreeError;
+
+/// How [`TpnTree::sample`] derives a value at a point from the data of the leaves.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Interpolation {
+    /// The value of the leaf containing the point.
+    Nearest,
+    /// The values at the centers of the leaf containing the point and its neighbors, blended linearly along each axis.
+    ///
+    /// Neighbors are looked up one leaf size away towards the point, so they are exact on uniformly divided regions.
+    /// Where there is no neighbor or it has no data, the value of the containing leaf is used instead.
+    Multilinear,
+}
+
+impl<T: Into<f64> + Copy, const N: usize> TpnTree<T, N> {
+    /// Samples the data of the leaves at the point, treating the tree as a field.
+    ///
+    /// Descends half-open to the leaf containing the point, just like [`SpatialTree::find_by_coordinates`](super::SpatialTree::find_by_coordinates).
+    /// Returns `None` if that leaf has no data.
+    /// Errors if the tree does not span the point.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::{Interpolation, TpnTree};
+    /// let mut root = TpnTree::<f64, 1>::root(1.0);
+    /// root.divide().expect("Couldn't divide.");
+    /// *root.get_child_mut(0).unwrap().data_mut() = Some(1.0);
+    /// *root.get_child_mut(1).unwrap().data_mut() = Some(3.0);
+    ///
+    /// assert_eq!(root.sample(&[0.0], Interpolation::Nearest), Ok(Some(1.0)));
+    /// assert_eq!(root.sample(&[0.0], Interpolation::Multilinear), Ok(Some(2.0)));
+    /// ```
+    pub fn sample(
+        &self,
+        point: &[f64; N],
+        interpolation: Interpolation,
+    ) -> Result<Option<f64>, TpnTreeError> {
+        if !self.spans_coordinates(point) {
+            return Err(self.does_not_span(point));
+        }
+
+        let leaf = self.leaf_at(point);
+        let value = match leaf.data {
+            Some(data) => data.into(),
+            None => return Ok(None),
+        };
+
+        match interpolation {
+            Interpolation::Nearest => Ok(Some(value)),
+            Interpolation::Multilinear => {
+                let mut blended = 0.0;
+                // corner bit i set means the neighbor along axis i towards the point
+                for corner in 0..1 << N {
+                    let mut weight = 1.0;
+                    let mut neighbor_center = leaf.coordinates;
+                    for i in 0..N {
+                        let offset = point[i] - leaf.coordinates[i];
+                        let t = offset.abs() / (2.0 * leaf.span[i]);
+                        if corner & (1 << i) == 0 {
+                            weight *= 1.0 - t;
+                        } else {
+                            weight *= t;
+                            neighbor_center[i] += 2.0 * leaf.span[i] * offset.signum();
+                        }
+                    }
+
+                    let neighbor_value = if self.spans_coordinates(&neighbor_center) {
+                        self.leaf_at(&neighbor_center).data.map(Into::into)
+                    } else {
+                        None
+                    };
+                    blended += weight * neighbor_value.unwrap_or(value);
+                }
+                Ok(Some(blended))
+            }
+        }
+    }
+}
+
+impl<T, const N: usize> TpnTree<T, N> {
+    // descends half-open to the leaf containing the point, assumes the tree spans it
+    pub(crate) fn leaf_at(&self, point: &[f64]) -> &Self {
+        let mut tree = self;
+        while !tree.is_leaf() {
+            tree = &tree.children[tree.child_index_for(point)];
+        }
+        tree
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tpntree::{Interpolation, TpnTree, VisitControl};
+
+    #[test]
+    fn multilinear_reproduces_linear_fields() {
+        let mut tree = TpnTree::<f64, 2>::root(1.0);
+        assert!(tree.divide_to_depth(2).is_ok());
+
+        let field = |[x, y]: [f64; 2]| x + 2.0 * y;
+        tree.visit_mut(&mut |tree: &mut TpnTree<f64, 2>| {
+            if tree.is_leaf() {
+                *tree.data_mut() = Some(field(tree.coordinates()));
+            }
+            VisitControl::Continue
+        });
+
+        for point in [[0.1, -0.2], [-0.6, 0.3], [0.25, 0.25], [0.7, -0.7]] {
+            let sampled = tree
+                .sample(&point, Interpolation::Multilinear)
+                .unwrap()
+                .unwrap();
+            assert!((sampled - field(point)).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn sample_at_boundaries_and_outside() {
+        let mut tree = TpnTree::<f64, 1>::root(1.0);
+        assert!(tree.divide().is_ok());
+        *tree.get_child_mut(0).unwrap().data_mut() = Some(1.0);
+
+        // no neighbor beyond the root, the leaf value is used
+        assert_eq!(
+            tree.sample(&[0.9], Interpolation::Multilinear),
+            Ok(Some(1.0))
+        );
+        // the neighbor has no data
+        assert_eq!(
+            tree.sample(&[0.1], Interpolation::Multilinear),
+            Ok(Some(1.0))
+        );
+        assert_eq!(tree.sample(&[-0.1], Interpolation::Nearest), Ok(None));
+        assert!(tree.sample(&[1.5], Interpolation::Nearest).is_err());
+    }
+}
@@ -0,0 +1,136 @@
+//! Unrolled fast paths for the common two and three dimensional trees.
+//!
+//! The generic tree methods dispatch here when `N` is 2 or 3.
+//! As `N` is a constant the dispatch is resolved at compile time and costs nothing.
+
+use super::TpnTree;
+
+/// A tree dividing into four quadrants.
+pub type QuadTree<T> = TpnTree<T, 2>;
+
+/// A tree dividing into eight octants.
+pub type OctTree<T> = TpnTree<T, 3>;
+
+#[inline(always)]
+pub(crate) fn child_index_2(center: &[f64], point: &[f64]) -> usize {
+    (point[0] < center[0]) as usize | ((point[1] < center[1]) as usize) << 1
+}
+
+#[inline(always)]
+pub(crate) fn child_index_3(center: &[f64], point: &[f64]) -> usize {
+    (point[0] < center[0]) as usize
+        | ((point[1] < center[1]) as usize) << 1
+        | ((point[2] < center[2]) as usize) << 2
+}
+
+#[inline(always)]
+fn half(center: f64, span: f64, lower: bool) -> (f64, f64) {
+    let span = span / 2.0;
+    if lower {
+        (center - span, span)
+    } else {
+        (center + span, span)
+    }
+}
+
+#[inline(always)]
+pub(crate) fn child_region_2(center: &mut [f64], span: &mut [f64], index: usize) {
+    (center[0], span[0]) = half(center[0], span[0], index & 1 != 0);
+    (center[1], span[1]) = half(center[1], span[1], index & 2 != 0);
+}
+
+#[inline(always)]
+pub(crate) fn child_region_3(center: &mut [f64], span: &mut [f64], index: usize) {
+    (center[0], span[0]) = half(center[0], span[0], index & 1 != 0);
+    (center[1], span[1]) = half(center[1], span[1], index & 2 != 0);
+    (center[2], span[2]) = half(center[2], span[2], index & 4 != 0);
+}
+
+#[inline(always)]
+fn within(center: f64, span: f64, coordinate: f64) -> bool {
+    coordinate <= center + span && coordinate >= center - span
+}
+
+#[inline(always)]
+pub(crate) fn spans_2(center: &[f64], span: &[f64], point: &[f64]) -> bool {
+    within(center[0], span[0], point[0]) && within(center[1], span[1], point[1])
+}
+
+#[inline(always)]
+pub(crate) fn spans_3(center: &[f64], span: &[f64], point: &[f64]) -> bool {
+    within(center[0], span[0], point[0])
+        && within(center[1], span[1], point[1])
+        && within(center[2], span[2], point[2])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{OctTree, QuadTree};
+
+    // points on and around the centers and faces of the root and its children
+    fn probes<const N: usize>() -> Vec<[f64; N]> {
+        let values = [-1.5, -1.0, -0.5, -0.25, 0.0, 0.25, 0.5, 1.0, 1.5];
+        (0..values.len().pow(N as u32))
+            .map(|mut index| {
+                let mut point = [0.0; N];
+                for coordinate in point.iter_mut() {
+                    *coordinate = values[index % values.len()];
+                    index /= values.len();
+                }
+                point
+            })
+            .collect()
+    }
+
+    #[test]
+    fn quad_tree_fast_paths_match_generic() {
+        let mut tree = QuadTree::<()>::root(1.0);
+        tree.divide().expect("Couldn't divide.");
+        let tree = tree.get_child(1).unwrap();
+
+        for index in 0..4 {
+            assert_eq!(
+                tree.child_region(index),
+                tree.child_region_generic(index),
+                "child {}",
+                index
+            );
+        }
+        for point in probes::<2>() {
+            assert_eq!(
+                tree.child_index_for(&point),
+                tree.child_index_generic(&point)
+            );
+            assert_eq!(
+                tree.spans_coordinates(&point),
+                tree.spans_coordinates_generic(&point)
+            );
+        }
+    }
+
+    #[test]
+    fn oct_tree_fast_paths_match_generic() {
+        let mut tree = OctTree::<()>::root(1.0);
+        tree.divide().expect("Couldn't divide.");
+        let tree = tree.get_child(5).unwrap();
+
+        for index in 0..8 {
+            assert_eq!(
+                tree.child_region(index),
+                tree.child_region_generic(index),
+                "child {}",
+                index
+            );
+        }
+        for point in probes::<3>() {
+            assert_eq!(
+                tree.child_index_for(&point),
+                tree.child_index_generic(&point)
+            );
+            assert_eq!(
+                tree.spans_coordinates(&point),
+                tree.spans_coordinates_generic(&point)
+            );
+        }
+    }
+}
@@ -0,0 +1,116 @@
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use num_traits::Float;
+
+use super::TpnTree;
+use crate::errors::TpnTreeError;
+
+impl<T, const N: usize> TpnTree<T, N> {
+    /// Creates a new TpnTree at level zero covering the hyperrectangle between the min and max corner with a binary aligned region.
+    ///
+    /// The region is the smallest one covering the corners whose span is a power of two and whose center is a multiple of its span along every axis, see [`TpnTree::is_binary_aligned`].
+    /// Errors with [`TpnTreeError::InvalidBounds`] just like [`TpnTree::from_bounds`].
+    ///
+    /// ```
+    /// # use tpntree::tpntree::TpnTree;
+    /// let root = TpnTree::<(), 2>::from_bounds_snapped([0.1, 0.0], [2.9, 0.3]).expect("Valid bounds.");
+    ///
+    /// assert_eq!(root.coordinates(), [2.0, 0.25]);
+    /// assert_eq!(root.span(), [2.0, 0.25]);
+    /// assert!(root.is_binary_aligned());
+    /// ```
+    pub fn from_bounds_snapped(min: [f64; N], max: [f64; N]) -> Result<Self, TpnTreeError> {
+        let bounds = Self::from_bounds(min, max)?;
+        let (coordinates, span) = binary_region(&bounds.coordinates, &bounds.span);
+        Ok(Self::new(coordinates, span, 0))
+    }
+
+    /// Checks if the span of the tree is a power of two and its center a multiple of the span along every axis.
+    ///
+    /// Dividing a binary aligned tree is exact, all descendants are binary aligned and tile their parents without floating-point drift,
+    /// as long as the centers remain representable, i.e. for about 52 levels below the ratio of center to span.
+    pub fn is_binary_aligned(&self) -> bool {
+        (0..N).all(|axis| {
+            let span = self.span[axis];
+            span > 0.0
+                && span.is_finite()
+                && span.log2().fract() == 0.0
+                && (self.coordinates[axis] / span).fract() == 0.0
+        })
+    }
+}
+
+// the smallest binary aligned region covering the region with the center and span
+pub(crate) fn binary_region<const N: usize>(
+    coordinates: &[f64; N],
+    span: &[f64; N],
+) -> ([f64; N], [f64; N]) {
+    let mut snapped_coordinates = [0.0; N];
+    let mut snapped_span = [0.0; N];
+    for axis in 0..N {
+        let (center, half) = (coordinates[axis], span[axis]);
+        let mut power = if half > 0.0 {
+            half.log2().ceil().exp2()
+        } else {
+            1.0
+        };
+        while power < half {
+            power *= 2.0;
+        }
+        // multiples of the power are exact, so are the comparisons
+        let mut snapped = (center / power).round() * power;
+        while snapped - power > center - half || snapped + power < center + half {
+            power *= 2.0;
+            snapped = (center / power).round() * power;
+        }
+        snapped_coordinates[axis] = snapped;
+        snapped_span[axis] = power;
+    }
+    (snapped_coordinates, snapped_span)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tpntree::TpnTree;
+
+    #[test]
+    fn snapped_trees_tile_exactly_when_deep() {
+        let min = [0.1, -3.7, 0.3];
+        let max = [0.2, 5.1, 0.35];
+        let mut tree = TpnTree::<(), 3>::from_bounds_snapped(min, max).unwrap();
+
+        for axis in 0..3 {
+            assert!(tree.coordinates()[axis] - tree.span()[axis] <= min[axis]);
+            assert!(tree.coordinates()[axis] + tree.span()[axis] >= max[axis]);
+        }
+
+        // descend along one path far beyond the depth where drift shows
+        let mut current = &mut tree;
+        for level in 0..45 {
+            assert!(current.is_binary_aligned(), "level {}", level);
+            current.divide().unwrap();
+            let (coordinates, span) = (current.coordinates(), current.span());
+            for (index, child) in current.iter_children().enumerate() {
+                for axis in 0..3 {
+                    let offset = if index & 1 << axis == 0 { 1.0 } else { -1.0 };
+                    assert_eq!(
+                        child.coordinates()[axis] + offset * child.span()[axis],
+                        coordinates[axis] + offset * span[axis]
+                    );
+                    assert_eq!(
+                        child.coordinates()[axis] - offset * child.span()[axis],
+                        coordinates[axis]
+                    );
+                }
+            }
+            current = current.get_child_mut(5).unwrap();
+        }
+    }
+
+    #[test]
+    fn unaligned_trees() {
+        assert!(!TpnTree::<(), 1>::root(0.1).is_binary_aligned());
+        assert!(!TpnTree::<(), 1>::new([0.5], [1.0], 0).is_binary_aligned());
+        assert!(TpnTree::<(), 1>::new([-3.0], [1.0], 0).is_binary_aligned());
+    }
+}
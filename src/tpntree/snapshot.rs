@@ -0,0 +1,90 @@
+use alloc::sync::Arc;
+use core::ops::Deref;
+
+use super::TpnTree;
+
+/// A frozen, read-only copy of a tree, see [`TpnTree::snapshot`].
+///
+/// Snapshots dereference to the tree, so all read-only methods are available.
+/// Cloning a snapshot shares the copy, snapshots are [`Send`] and [`Sync`] if the data is.
+/// A tree that is not mutated anymore or already lives in an [`Arc`] can be converted into a snapshot without copying it.
+#[derive(Debug, PartialEq)]
+pub struct TreeSnapshot<T, const N: usize>(Arc<TpnTree<T, N>>);
+
+impl<T, const N: usize> Clone for TreeSnapshot<T, N> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<T, const N: usize> Deref for TreeSnapshot<T, N> {
+    type Target = TpnTree<T, N>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T, const N: usize> From<TpnTree<T, N>> for TreeSnapshot<T, N> {
+    /// Freezes the tree without copying it.
+    fn from(tree: TpnTree<T, N>) -> Self {
+        Self(Arc::new(tree))
+    }
+}
+
+impl<T, const N: usize> From<Arc<TpnTree<T, N>>> for TreeSnapshot<T, N> {
+    /// Wraps an already shared tree.
+    fn from(tree: Arc<TpnTree<T, N>>) -> Self {
+        Self(tree)
+    }
+}
+
+impl<T, const N: usize> TreeSnapshot<T, N> {
+    /// Returns the shared tree.
+    pub fn into_arc(self) -> Arc<TpnTree<T, N>> {
+        self.0
+    }
+}
+
+impl<T: Clone, const N: usize> TpnTree<T, N> {
+    /// Freezes the current state of the tree into a snapshot that stays valid while the tree is mutated further.
+    ///
+    /// Taking a snapshot copies the tree once, handing it to other threads or cloning it afterwards is cheap.
+    /// Use a [`PersistentTree`](super::PersistentTree) if snapshots are needed after every small change.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::TpnTree;
+    /// let mut tree = TpnTree::<u8, 2>::root(1.0);
+    /// let snapshot = tree.snapshot();
+    ///
+    /// tree.divide().expect("Couldn't divide.");
+    ///
+    /// let reader = std::thread::spawn(move || snapshot.is_leaf());
+    /// assert!(reader.join().unwrap());
+    /// ```
+    pub fn snapshot(&self) -> TreeSnapshot<T, N> {
+        TreeSnapshot(Arc::new(self.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tpntree::TpnTree;
+
+    #[test]
+    fn snapshot_is_unaffected_by_mutation() {
+        let mut tree = TpnTree::<u8, 2>::root(1.0);
+        tree.divide().unwrap();
+        *tree.get_child_mut(0).unwrap().data_mut() = Some(1);
+
+        let snapshot = tree.snapshot();
+        let shared = snapshot.clone();
+        *tree.get_child_mut(0).unwrap().data_mut() = Some(2);
+        tree.get_child_mut(1).unwrap().divide().unwrap();
+
+        assert_eq!(snapshot.get_child(0).unwrap().data(), Some(&1));
+        assert_eq!(snapshot.iter_depth_first().count(), 5);
+        assert_eq!(shared, snapshot);
+        assert_ne!(*snapshot, tree);
+    }
+}
@@ -0,0 +1,291 @@
+use alloc::vec::Vec;
+use core::iter::once;
+
+use super::{Region, RegionNode};
+use crate::{errors::TpnTreeError, Coordinates};
+
+/// A tree creating only the children that are actually used instead of all 2^N children on division.
+///
+/// Children are kept in a vector sorted by their index, see [`TpnTree::child_index_for_point`](super::TpnTree::child_index_for_point).
+/// This allows trees of many dimensions, where materializing all 2^N children is infeasible.
+/// Child indices must fit into an `usize`, i.e. N has to be below the bit width of `usize`.
+///
+/// It is a type of its own instead of a storage policy type parameter of [`TpnTree`](super::TpnTree),
+/// as most of the [`TpnTree`](super::TpnTree) API relies on all children being present in a slice indexed by their child index,
+/// e.g. [`TpnTree::children`](super::TpnTree::children), [`NodeKey`](super::NodeKey), the linear and arena representations and [`TreeNode`](crate::TreeNode).
+/// A type parameter would make all of them generic over the storage or restrict them to dense trees.
+/// Accessing the region and the data is shared with [`TpnTree`](super::TpnTree) through [`RegionNode`].
+///
+/// ```
+/// # use tpntree::tpntree::{RegionNode, SparseTree};
+/// let mut tree = SparseTree::<u32, 20>::root(1.0);
+///
+/// *tree.child_or_insert(5).data_mut() = Some(1);
+///
+/// assert_eq!(tree.child_count(), 1);
+/// assert_eq!(tree.get_child(5).and_then(|child| child.data()), Some(&1));
+/// assert!(tree.get_child(4).is_none());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SparseTree<T, const N: usize> {
+    region: Region<N>,
+    children: Vec<(usize, Self)>,
+    data: Option<T>,
+}
+
+/// A helper type to work with spatial data bins in a [`SparseTree`].
+pub type SparseSpatialTree<T, const N: usize> = SparseTree<Vec<T>, N>;
+
+impl<T, const N: usize> RegionNode<T, N> for SparseTree<T, N> {
+    fn region(&self) -> Region<N> {
+        self.region
+    }
+
+    fn data(&self) -> Option<&T> {
+        self.data.as_ref()
+    }
+
+    fn data_mut(&mut self) -> &mut Option<T> {
+        &mut self.data
+    }
+
+    /// Returns the count of created children.
+    fn child_count(&self) -> usize {
+        self.children.len()
+    }
+}
+
+impl<T, const N: usize> SparseTree<T, N> {
+    /// Creates a new tree without children.
+    pub fn new(coordinates: [f64; N], span: [f64; N], level: usize) -> Self {
+        Self {
+            region: Region::new(coordinates, span, level),
+            children: Vec::new(),
+            data: None,
+        }
+    }
+
+    /// Creates a new tree with equal span in all dimension at the center of the space, see [`TpnTree::root`](super::TpnTree::root).
+    pub fn root(span: f64) -> Self {
+        Self::new([0.0; N], [span; N], 0)
+    }
+
+    /// Returns the count of trees in the tree, including itself.
+    pub fn node_count(&self) -> usize {
+        1 + self
+            .children
+            .iter()
+            .map(|(_, child)| child.node_count())
+            .sum::<usize>()
+    }
+
+    /// Get a reference to the child at the index if it has been created.
+    pub fn get_child(&self, index: usize) -> Option<&Self> {
+        self.position(index)
+            .ok()
+            .map(|position| &self.children[position].1)
+    }
+
+    /// Get a mutable reference to the child at the index if it has been created.
+    pub fn get_child_mut(&mut self, index: usize) -> Option<&mut Self> {
+        self.position(index)
+            .ok()
+            .map(move |position| &mut self.children[position].1)
+    }
+
+    /// Get a mutable reference to the child at the index, creating it if necessary.
+    ///
+    /// Panics if the index is not below 2^N.
+    pub fn child_or_insert(&mut self, index: usize) -> &mut Self {
+        let position = match self.position(index) {
+            Ok(position) => position,
+            Err(position) => {
+                let region = self.region.virtual_child(index);
+                let child = Self::new(region.coordinates(), region.span(), region.level());
+                self.children.insert(position, (index, child));
+                position
+            }
+        };
+        &mut self.children[position].1
+    }
+
+    /// Iterate the created children together with their index, ordered by index.
+    pub fn iter_children(&self) -> impl Iterator<Item = (usize, &Self)> {
+        self.children.iter().map(|(index, child)| (*index, child))
+    }
+
+    /// Returns the deepest created tree covering the point, descending half-open like insertion.
+    ///
+    /// Errors if the tree does not span the point.
+    pub fn find_by_point(&self, point: &[f64; N]) -> Result<&Self, TpnTreeError> {
+        self.check_spans(point)?;
+        let mut tree = self;
+        while let Some(child) = tree.get_child(tree.region.child_index_for_point(point)) {
+            tree = child;
+        }
+        Ok(tree)
+    }
+
+    fn position(&self, index: usize) -> Result<usize, usize> {
+        self.children
+            .binary_search_by_key(&index, |(child_index, _)| *child_index)
+    }
+
+    fn check_spans(&self, point: &[f64]) -> Result<(), TpnTreeError> {
+        let coordinates = self.coordinates();
+        let span = self.span();
+        if (0..N).all(|axis| (point[axis] - coordinates[axis]).abs() <= span[axis]) {
+            Ok(())
+        } else {
+            Err(TpnTreeError::does_not_span(point, &coordinates, &span))
+        }
+    }
+}
+
+impl<T: Coordinates<N>, const N: usize> SparseSpatialTree<T, N> {
+    /// Inserts data like [`SpatialTree::insert_by_coordinates`](super::SpatialTree::insert_by_coordinates), creating only the children that receive data.
+    ///
+    /// Errors if the tree does not span the data or the data has other than N coordinates.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::{RegionNode, SparseSpatialTree};
+    /// let mut tree = SparseSpatialTree::<[f64; 16], 16>::root(1.0);
+    ///
+    /// tree.insert_by_coordinates([0.5; 16], &|tree| tree.data().is_some()).unwrap();
+    /// tree.insert_by_coordinates([-0.5; 16], &|tree| tree.data().is_some()).unwrap();
+    ///
+    /// assert_eq!(tree.child_count(), 2);
+    /// ```
+    pub fn insert_by_coordinates(
+        &mut self,
+        data: T,
+        division_condition: &dyn Fn(&Self) -> bool,
+    ) -> Result<(), TpnTreeError> {
+        let coordinates = data.try_coordinates()?;
+
+        if self.level() == 0 {
+            self.check_spans(coordinates)?;
+        }
+
+        if self.is_leaf() {
+            if division_condition(self) {
+                for data in self
+                    .data
+                    .take()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .chain(once(data))
+                {
+                    self.insert_into_child(data, division_condition)?
+                }
+                Ok(())
+            } else {
+                self.data.get_or_insert(Vec::new()).push(data);
+                Ok(())
+            }
+        } else {
+            self.insert_into_child(data, division_condition)
+        }
+    }
+
+    fn insert_into_child(
+        &mut self,
+        data: T,
+        division_condition: &dyn Fn(&Self) -> bool,
+    ) -> Result<(), TpnTreeError> {
+        let index = self.child_index_for(data.coordinates());
+        self.child_or_insert(index)
+            .insert_by_coordinates(data, division_condition)
+    }
+
+    fn child_index_for(&self, coordinates: &[f64]) -> usize {
+        let center = self.coordinates();
+        (0..N).fold(0, |index, axis| {
+            index | ((coordinates[axis] < center[axis]) as usize) << axis
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tpntree::{RegionNode, SparseSpatialTree, SparseTree, SpatialTree};
+    use crate::TpnTreeError;
+
+    #[test]
+    fn insert_matches_dense_tree() {
+        let points = [
+            [0.5, 0.5, 0.5],
+            [0.6, 0.6, 0.6],
+            [-0.5, 0.2, 0.1],
+            [0.55, 0.52, 0.6],
+            [0.0, 0.0, 0.0],
+        ];
+        let division_condition = |len: Option<usize>| len.is_some_and(|len| len >= 1);
+
+        let mut sparse = SparseSpatialTree::<[f64; 3], 3>::root(1.0);
+        let mut dense = SpatialTree::<[f64; 3], 3>::root(1.0);
+        for point in points {
+            sparse
+                .insert_by_coordinates(point, &|tree| division_condition(tree.data().map(Vec::len)))
+                .unwrap();
+            dense
+                .insert_by_coordinates(point, &|tree| division_condition(tree.data().map(Vec::len)))
+                .unwrap();
+        }
+
+        for point in points {
+            let sparse_leaf = sparse.find_by_point(&point).unwrap();
+            let dense_leaf = dense.find_by_coordinates(&point).unwrap();
+            assert_eq!(sparse_leaf.region(), dense_leaf.region());
+            assert_eq!(sparse_leaf.data(), dense_leaf.data());
+        }
+        assert!(sparse.node_count() < dense.iter_depth_first().count());
+    }
+
+    #[test]
+    fn many_dimensions_create_few_children() {
+        let mut tree = SparseSpatialTree::<Vec<f64>, 40>::root(1.0);
+
+        for i in 0..8 {
+            let point = (0..40)
+                .map(|axis| if axis == i { -0.5 } else { 0.5 })
+                .collect::<Vec<_>>();
+            tree.insert_by_coordinates(point, &|tree| {
+                tree.data().is_some_and(|data| data.len() >= 2)
+            })
+            .unwrap();
+        }
+
+        assert!(tree.node_count() <= 1 + 2 * 8);
+        assert_eq!(
+            tree.insert_by_coordinates(vec![2.0; 40], &|_| false),
+            Err(TpnTreeError::does_not_span(
+                &[2.0; 40], &[0.0; 40], &[1.0; 40]
+            ))
+        );
+        assert_eq!(
+            tree.insert_by_coordinates(vec![0.0; 3], &|_| false),
+            Err(TpnTreeError::DimensionMismatch {
+                expected: 40,
+                got: 3
+            })
+        );
+    }
+
+    #[test]
+    fn children_stay_sorted() {
+        let mut tree = SparseTree::<(), 4>::root(1.0);
+
+        for index in [9, 3, 15, 0, 3] {
+            tree.child_or_insert(index);
+        }
+
+        assert_eq!(
+            tree.iter_children()
+                .map(|(index, _)| index)
+                .collect::<Vec<_>>(),
+            vec![0, 3, 9, 15]
+        );
+        assert_eq!(tree.get_child(15).unwrap().coordinates(), [-0.5; 4]);
+    }
+}
@@ -1,6 +1,9 @@
-use std::iter::once;
+use alloc::vec::Vec;
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use num_traits::Float;
 
-use super::TpnTree;
+use super::{DuplicatePolicy, NodePath, TpnTree};
 use crate::{errors::TpnTreeError, Coordinates};
 
 /// A helper type to work with spatial data bins.
@@ -12,6 +15,11 @@ pub type Tree3D<T = [f64; 3]> = SpatialTree<T, 3>;
 impl<T: Coordinates<N>, const N: usize> SpatialTree<T, N> {
     /// Checks if the tree spans over the coordinates of the provided data.
     ///
+    /// The bounds are inclusive on both sides, so neighboring trees both span the coordinates on their shared face.
+    /// Insertion and lookup descend half-open instead, see [`SpatialTree::insert_by_coordinates`].
+    /// With an epsilon in the [`TreeConfig`](super::TreeConfig) the bounds are widened by it.
+    /// Data with other than N coordinates is never spanned.
+    ///
     /// ```
     /// # use tpntree::tpntree::Tree3D;
     /// # use tpntree::TpnTreeError;
@@ -21,23 +29,18 @@ impl<T: Coordinates<N>, const N: usize> SpatialTree<T, N> {
     ///  assert_eq!(tree.spans(&[1.5,0.5,0.5]), false);
     /// ```
     pub fn spans(&self, data: &T) -> bool {
-        let data_coordinates = data.coordinates();
-
-        // checks if tpn tree contains the data coordinates
-        // children overlap on their edges
-        self.coordinates
-            .iter()
-            .enumerate()
-            .all(|(dimension, &coordinate)| {
-                data_coordinates[dimension] <= coordinate + self.span()[dimension]
-                    && data_coordinates[dimension] >= coordinate - self.span()[dimension]
-            })
+        data.try_coordinates()
+            .is_ok_and(|coordinates| self.spans_coordinates(coordinates))
     }
 
     /// Inserts data in the tree with its center closest to the data given the constrains of the `division_condition`.
     ///
     /// The `division condition` determines when a tree divides and inserts its data into its children.
-    /// Errors if the tree does not span the data.
+    /// Errors if the tree does not span the data or the data has other than N coordinates.
+    ///
+    /// Children are chosen half-open, i.e. each child covers `[min, max)` along every axis except on the max face of the root.
+    /// Data exactly on a face shared by children therefore always ends up in the child above it.
+    /// The child is found by [`TpnTree::child_index_for_point`] without looking at the other children.
     ///
     /// ```
     /// # use tpntree::tpntree::Tree3D;
@@ -50,50 +53,211 @@ impl<T: Coordinates<N>, const N: usize> SpatialTree<T, N> {
         data: T,
         division_condition: &dyn Fn(&Self) -> bool,
     ) -> Result<(), TpnTreeError> {
+        self.insert_into_leaf(data, division_condition).map(|_| ())
+    }
+
+    /// Inserts data just like [`SpatialTree::insert_by_coordinates`] and returns the leaf that received it.
+    ///
+    /// Lets callers inspect or update the bin of the data without finding it again.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::Tree3D;
+    /// let mut tree = Tree3D::root(1.0);
+    /// tree.insert_by_coordinates([0.5, 0.5, 0.5], &|_| false).expect("Couldn't insert.");
+    ///
+    /// let leaf = tree
+    ///     .insert_into_leaf([-0.5, 0.5, 0.5], &|tree| tree.is_root())
+    ///     .expect("Couldn't insert.");
+    ///
+    /// assert_eq!(leaf.coordinates(), [-0.5, 0.5, 0.5]);
+    /// assert_eq!(leaf.data(), Some(&vec![[-0.5, 0.5, 0.5]]));
+    /// ```
+    pub fn insert_into_leaf(
+        &mut self,
+        data: T,
+        division_condition: &dyn Fn(&Self) -> bool,
+    ) -> Result<&mut Self, TpnTreeError> {
+        data.try_coordinates()?;
+
         // if the root tree does not span over the data, it can not be inserted
         if self.is_root() && !self.spans(&data) {
-            return Err(TpnTreeError::DoesNotSpan);
+            return Err(self.does_not_span(data.coordinates()));
         }
 
         if self.is_leaf() {
-            if division_condition(self) {
-                self.divide()?;
-
-                for data in self
-                    .data
-                    .take()
-                    .unwrap_or_default()
-                    .into_iter()
-                    .chain(once(data))
-                {
-                    self.insert_into_children(data, division_condition)?
-                }
-                Ok(())
-            } else {
+            if !division_condition(self) {
                 self.data.get_or_insert(Vec::new()).push(data);
-                Ok(())
+                return Ok(self);
+            }
+            self.divide()?;
+
+            for data in self.data.take().unwrap_or_default() {
+                self.insert_into_children(data, division_condition)?;
             }
-        } else {
-            self.insert_into_children(data, division_condition)
         }
+        self.insert_into_children(data, division_condition)
+    }
+
+    /// Inserts data in the tree, dividing leaves according to the [`TreeConfig`](super::TreeConfig) of the tree.
+    ///
+    /// A leaf divides once it holds `leaf_capacity` items, unless it is at `max_depth`.
+    /// Without a max depth leaves divide down to [`TreeConfig::DEFAULT_MAX_DEPTH`](super::TreeConfig::DEFAULT_MAX_DEPTH), so coincident items end up sharing a leaf.
+    /// Without a leaf capacity leaves never divide.
+    /// With a [`Spillover`](super::Spillover) policy in the config, full leaves are handled by the policy instead.
+    /// Items at the coordinates of an item in the tree are handled according to the [`DuplicatePolicy`].
+    /// Errors just like [`SpatialTree::insert_by_coordinates`].
+    ///
+    /// ```
+    /// # use tpntree::tpntree::Tree3D;
+    /// let mut tree = Tree3D::builder().leaf_capacity(1).build();
+    ///
+    /// assert!(tree.insert([0.5, 0.5, 0.5]).is_ok());
+    /// assert!(tree.insert([-0.5, -0.5, -0.5]).is_ok());
+    /// assert_eq!(tree.child_count(), 8);
+    /// ```
+    pub fn insert(&mut self, data: T) -> Result<(), TpnTreeError> {
+        let duplicates = self.config().and_then(|config| {
+            config
+                .duplicate_policy()
+                .map(|policy| (policy, config.duplicate_tolerance().unwrap_or(0.0)))
+        });
+        if let Some((policy, tolerance)) = duplicates {
+            if policy != DuplicatePolicy::KeepAll {
+                let coordinates = data.try_coordinates()?;
+                if self.is_root() && !self.spans(&data) {
+                    return Err(self.does_not_span(coordinates));
+                }
+                if let Some((path, position)) = self.find_duplicate(coordinates, tolerance) {
+                    if policy == DuplicatePolicy::Reject {
+                        return Err(TpnTreeError::Duplicate {
+                            point: coordinates.to_vec(),
+                        });
+                    }
+                    let tree = self
+                        .get_descendant_mut(&path)
+                        .expect("Found paths lead to existing trees.");
+                    if let Some(items) = tree.data.as_mut() {
+                        items.remove(position);
+                        if items.is_empty() {
+                            tree.data = None;
+                        }
+                    }
+                }
+            }
+        }
+        match self.config().and_then(|config| config.spillover()) {
+            Some(spillover) => self.insert_with_spillover(data, spillover),
+            None => self.insert_by_coordinates(data, &|tree: &Self| tree.is_at_capacity()),
+        }
+    }
+
+    // path to the tree holding an item within the tolerance and the position of the item in its data
+    fn find_duplicate(&self, coordinates: &[f64], tolerance: f64) -> Option<(NodePath, usize)> {
+        let near = (0..N).all(|axis| {
+            (self.coordinates[axis] - coordinates[axis]).abs() <= self.span[axis] + tolerance
+        });
+        if !near {
+            return None;
+        }
+        let position = self.data.as_ref().and_then(|items| {
+            items.iter().position(|item| {
+                item.coordinates()
+                    .iter()
+                    .zip(coordinates)
+                    .map(|(a, b)| (a - b).powi(2))
+                    .sum::<f64>()
+                    .sqrt()
+                    <= tolerance
+            })
+        });
+        if let Some(position) = position {
+            return Some((Vec::new(), position));
+        }
+        self.children.iter().enumerate().find_map(|(index, child)| {
+            child
+                .find_duplicate(coordinates, tolerance)
+                .map(|(mut path, position)| {
+                    path.insert(0, index);
+                    (path, position)
+                })
+        })
+    }
+
+    /// Removes all children and inserts all data again according to the [`TreeConfig`](super::TreeConfig) of the tree.
+    ///
+    /// Use this after changing the configuration with [`TpnTree::set_config`] or when data was moved by hand.
+    /// Data is inserted in depth first order, see [`SpatialTree::insert`].
+    /// On error the tree holds all data inserted so far, the data that failed to insert and the remaining data are dropped.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::{Tree3D, TreeConfig};
+    /// let mut tree = Tree3D::root(1.0);
+    /// tree.insert([0.5, 0.5, 0.5]).expect("Couldn't insert.");
+    /// tree.insert([-0.5, -0.5, -0.5]).expect("Couldn't insert.");
+    ///
+    /// tree.set_config(TreeConfig::default().with_leaf_capacity(1));
+    /// tree.refit().expect("Couldn't refit.");
+    ///
+    /// assert_eq!(tree.child_count(), 8);
+    /// ```
+    pub fn refit(&mut self) -> Result<(), TpnTreeError> {
+        let mut data = Vec::new();
+        self.drain_into(&mut data);
+
+        for data in data {
+            self.insert(data)?;
+        }
+        Ok(())
+    }
+
+    // moves all data of the tree and its descendants into the vector and removes all children
+    pub(crate) fn drain_into(&mut self, data: &mut Vec<T>) {
+        data.extend(self.data.take().unwrap_or_default());
+        for child in &mut self.children {
+            child.drain_into(data);
+        }
+        self.clear_children();
     }
 
     fn insert_into_children(
         &mut self,
         data: T,
         division_condition: &dyn Fn(&Self) -> bool,
-    ) -> Result<(), TpnTreeError> {
-        self.children
-            .iter_mut()
-            // we can savely unwrap here as dimn=ensions are checked before
-            .find(|child| child.spans(&data))
-            .map(|child| child.insert_by_coordinates(data, division_condition))
-            .unwrap()
+    ) -> Result<&mut Self, TpnTreeError> {
+        let index = self.child_index_for(data.coordinates());
+        self.children[index].insert_into_leaf(data, division_condition)
+    }
+
+    /// Returns the min and max corner of the tightest box around the coordinates of all data in the tree, or `None` if it holds no data.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::Tree3D;
+    /// let mut tree = Tree3D::builder().leaf_capacity(1).build();
+    /// tree.insert([0.5, -0.2, 0.1]).expect("Couldn't insert.");
+    /// tree.insert([-0.3, 0.4, 0.1]).expect("Couldn't insert.");
+    ///
+    /// assert_eq!(tree.data_bounds(), Some(([-0.3, -0.2, 0.1], [0.5, 0.4, 0.1])));
+    /// ```
+    pub fn data_bounds(&self) -> Option<([f64; N], [f64; N])> {
+        self.iter_depth_first()
+            .filter_map(|tree| tree.data())
+            .flatten()
+            .map(|data| data.coordinates())
+            .fold(None, |bounds, coordinates| {
+                let (mut min, mut max) =
+                    bounds.unwrap_or(([f64::INFINITY; N], [f64::NEG_INFINITY; N]));
+                for axis in 0..N {
+                    min[axis] = min[axis].min(coordinates[axis]);
+                    max[axis] = max[axis].max(coordinates[axis]);
+                }
+                Some((min, max))
+            })
     }
 
     /// Return the tree closest to the given data coordinates.
     ///
-    /// Errors if the tree does not span the data.
+    /// Errors if the tree does not span the data or the data has other than N coordinates.
+    /// Descends half-open just like [`SpatialTree::insert_by_coordinates`].
     ///
     /// ```
     /// # use tpntree::tpntree::Tree3D;
@@ -107,22 +271,107 @@ impl<T: Coordinates<N>, const N: usize> SpatialTree<T, N> {
     ///   .unwrap());
     /// ```
     pub fn find_by_coordinates(&self, data: &T) -> Result<&Self, TpnTreeError> {
+        data.try_coordinates()?;
+
         if self.is_root() && !self.spans(data) {
-            return Err(TpnTreeError::DoesNotSpan);
+            return Err(self.does_not_span(data.coordinates()));
         }
 
-        for child in &self.children {
-            if child.spans(data) {
-                return child.find_by_coordinates(data);
-            }
+        if self.is_leaf() {
+            Ok(self)
+        } else {
+            self.children[self.child_index_for(data.coordinates())].find_by_coordinates(data)
+        }
+    }
+
+    /// Return the tree closest to the given data coordinates by mutable reference.
+    ///
+    /// Errors if the tree does not span the data or the data has other than N coordinates.
+    /// Descends half-open just like [`SpatialTree::insert_by_coordinates`].
+    ///
+    /// ```
+    /// # use tpntree::tpntree::Tree3D;
+    ///  let mut tree = Tree3D::root(1.0);
+    ///
+    /// tree.find_by_coordinates_mut(&[0.0, 0.0, 0.0])
+    ///   .expect("Couldn't find.")
+    ///   .data_mut()
+    ///   .get_or_insert(Vec::new())
+    ///   .push([0.5, 0.5, 0.5]);
+    ///
+    /// assert_eq!(tree.data(), Some(&vec![[0.5, 0.5, 0.5]]));
+    /// ```
+    pub fn find_by_coordinates_mut(&mut self, data: &T) -> Result<&mut Self, TpnTreeError> {
+        data.try_coordinates()?;
+
+        if self.is_root() && !self.spans(data) {
+            return Err(self.does_not_span(data.coordinates()));
+        }
+
+        if self.is_leaf() {
+            Ok(self)
+        } else {
+            let index = self.child_index_for(data.coordinates());
+            self.children[index].find_by_coordinates_mut(data)
         }
-        Ok(self)
+    }
+}
+
+impl<T, const N: usize> TpnTree<T, N> {
+    pub(crate) fn spans_coordinates(&self, coordinates: &[f64]) -> bool {
+        if let Some(epsilon) = self.config().and_then(|config| config.epsilon()) {
+            return self.spans_coordinates_within(coordinates, epsilon);
+        }
+        match N {
+            2 => super::small::spans_2(&self.coordinates, &self.span, coordinates),
+            3 => super::small::spans_3(&self.coordinates, &self.span, coordinates),
+            _ => self.spans_coordinates_generic(coordinates),
+        }
+    }
+
+    pub(crate) fn spans_coordinates_generic(&self, coordinates: &[f64]) -> bool {
+        // checks if tpn tree contains the coordinates
+        // children overlap on their edges
+        self.coordinates
+            .iter()
+            .enumerate()
+            .all(|(dimension, &coordinate)| {
+                coordinates[dimension] <= coordinate + self.span[dimension]
+                    && coordinates[dimension] >= coordinate - self.span[dimension]
+            })
+    }
+
+    // checks if the coordinates lie within the tree grown by epsilon
+    fn spans_coordinates_within(&self, coordinates: &[f64], epsilon: f64) -> bool {
+        (0..N).all(|axis| {
+            (coordinates[axis] - self.coordinates[axis]).abs() <= self.span[axis] + epsilon
+        })
+    }
+
+    // checks if the configured leaf capacity is reached and the tree may still divide
+    pub(crate) fn is_at_capacity<U>(&self) -> bool
+    where
+        T: AsRef<[U]>,
+    {
+        self.config()
+            .and_then(|config| config.leaf_capacity())
+            .is_some_and(|capacity| {
+                self.data
+                    .as_ref()
+                    .is_some_and(|data| data.as_ref().len() >= capacity)
+            })
+            && self.below_max_depth()
+    }
+
+    pub(crate) fn does_not_span(&self, coordinates: &[f64]) -> TpnTreeError {
+        TpnTreeError::does_not_span(coordinates, &self.coordinates, &self.span)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::tpntree::Tree3D;
+    use crate::tpntree::{DuplicatePolicy, SpatialTree, Tree3D, TreeConfig};
+    use crate::TpnTreeError;
 
     #[test]
     fn tree_contains_coordinates() {
@@ -185,4 +434,284 @@ mod tests {
         assert!(tree.data().is_none());
         assert!(tree.child_count() == 8);
     }
+
+    #[test]
+    fn insert_on_shared_faces_is_deterministic() {
+        let mut tree = Tree3D::root(1.0);
+
+        let division_condition = |tree: &Tree3D| tree.is_root() && tree.data().is_some();
+
+        let data_center = [0.0, 0.0, 0.0];
+        let data_max = [1.0, 1.0, 1.0];
+        let data_min = [-1.0, -1.0, -1.0];
+        let data_mixed = [0.0, -1.0, 1.0];
+
+        for data in [data_center, data_max, data_min, data_mixed] {
+            assert!(tree
+                .insert_by_coordinates(data, &division_condition)
+                .is_ok());
+        }
+
+        let upper = tree.find_by_coordinates(&[0.5, 0.5, 0.5]).unwrap();
+        assert!(upper.data().map(|vec| vec.contains(&data_center)).unwrap());
+        assert!(upper.data().map(|vec| vec.contains(&data_max)).unwrap());
+
+        let lower = tree.find_by_coordinates(&[-0.5, -0.5, -0.5]).unwrap();
+        assert_eq!(lower.data(), Some(&vec![data_min]));
+
+        let mixed = tree.find_by_coordinates(&[0.5, -0.5, 0.5]).unwrap();
+        assert_eq!(mixed.data(), Some(&vec![data_mixed]));
+
+        assert_eq!(
+            tree.iter_depth_first()
+                .filter_map(|tree| tree.data())
+                .map(|vec| vec.len())
+                .sum::<usize>(),
+            4
+        );
+    }
+
+    #[test]
+    fn find_mut_and_modify_leaf() {
+        let mut tree = Tree3D::root(1.0);
+
+        let division_condition = |tree: &Tree3D| tree.data().is_some();
+
+        assert!(tree
+            .insert_by_coordinates([0.5, 0.5, 0.5], &division_condition)
+            .is_ok());
+        assert!(tree
+            .insert_by_coordinates([-0.5, -0.5, -0.5], &division_condition)
+            .is_ok());
+
+        let leaf = tree.find_by_coordinates_mut(&[0.7, 0.7, 0.7]).unwrap();
+        assert_eq!(leaf.level(), 1);
+        leaf.data_mut()
+            .get_or_insert(Vec::new())
+            .push([0.7, 0.7, 0.7]);
+
+        assert_eq!(
+            tree.find_by_coordinates(&[0.5, 0.5, 0.5])
+                .ok()
+                .and_then(|tree| tree.data())
+                .map(|vec| vec.len()),
+            Some(2)
+        );
+        assert!(tree.find_by_coordinates_mut(&[1.5, 0.0, 0.0]).is_err());
+    }
+
+    #[test]
+    fn insert_outside_reports_context() {
+        let mut tree = Tree3D::root(1.0);
+
+        assert_eq!(
+            tree.insert_by_coordinates([2.0, 0.0, 0.0], &|_| false),
+            Err(TpnTreeError::DoesNotSpan {
+                point: vec![2.0, 0.0, 0.0],
+                bounds: (vec![-1.0, -1.0, -1.0], vec![1.0, 1.0, 1.0])
+            })
+        );
+    }
+
+    #[test]
+    fn refit_forgets_how_the_tree_was_split() {
+        let mut tree = Tree3D::root(1.0);
+        assert!(tree.divide_axes(&[0]).is_ok());
+        assert!(tree
+            .get_child_mut(0)
+            .unwrap()
+            .divide_with_branching(3)
+            .is_ok());
+        assert!(tree
+            .insert_by_coordinates([0.5, 0.5, 0.5], &|_| false)
+            .is_ok());
+
+        assert!(tree.refit().is_ok());
+
+        assert!(tree.is_leaf());
+        assert_eq!((tree.split_axes, tree.branching), (None, 2));
+        assert_eq!(tree.data(), Some(&vec![[0.5, 0.5, 0.5]]));
+    }
+
+    #[test]
+    fn refit_follows_changed_config() {
+        let mut tree = Tree3D::builder().leaf_capacity(1).max_depth(2).build();
+
+        for data in [[0.5, 0.5, 0.5], [-0.5, -0.5, -0.5], [0.7, 0.7, 0.7]] {
+            assert!(tree.insert(data).is_ok());
+        }
+        assert_eq!(
+            tree.iter_depth_first()
+                .map(|tree| tree.level())
+                .max()
+                .unwrap(),
+            2
+        );
+
+        tree.set_config(TreeConfig::default().with_leaf_capacity(2));
+        assert!(tree.refit().is_ok());
+
+        assert_eq!(
+            tree.iter_depth_first()
+                .map(|tree| tree.level())
+                .max()
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            tree.iter_depth_first()
+                .filter_map(|tree| tree.data())
+                .map(|vec| vec.len())
+                .sum::<usize>(),
+            3
+        );
+    }
+
+    #[test]
+    fn coincident_items_stop_dividing() {
+        let mut tree = Tree3D::builder().leaf_capacity(1).build();
+
+        for _ in 0..3 {
+            assert!(tree.insert([0.3, 0.3, 0.3]).is_ok());
+        }
+
+        let leaf = tree.find_by_coordinates(&[0.3, 0.3, 0.3]).unwrap();
+        assert_eq!(leaf.level(), TreeConfig::DEFAULT_MAX_DEPTH);
+        assert_eq!(leaf.data().map(Vec::len), Some(3));
+    }
+
+    #[test]
+    fn data_bounds_cover_all_levels() {
+        let mut tree = SpatialTree::<[f64; 2], 2>::root(1.0);
+        assert_eq!(tree.data_bounds(), None);
+
+        tree.insert_by_coordinates([0.25, 0.5], &|_| false).unwrap();
+        assert_eq!(tree.data_bounds(), Some(([0.25, 0.5], [0.25, 0.5])));
+
+        // data may be kept in inner trees, e.g. by the split policy
+        tree.divide().unwrap();
+        tree.get_child_mut(3)
+            .unwrap()
+            .data_mut()
+            .get_or_insert(Vec::new())
+            .push([-0.75, -0.5]);
+        assert_eq!(tree.data_bounds(), Some(([-0.75, -0.5], [0.25, 0.5])));
+    }
+
+    #[test]
+    fn duplicate_policies() {
+        let build = |policy| {
+            let mut tree = SpatialTree::<[f64; 2], 2>::builder()
+                .leaf_capacity(1)
+                .max_depth(4)
+                .duplicate_policy(policy)
+                .build();
+            for point in [[0.5, 0.5], [-0.5, 0.5], [0.25, 0.25]].iter() {
+                tree.insert(*point).unwrap();
+            }
+            tree
+        };
+        let count = |tree: &SpatialTree<[f64; 2], 2>| {
+            tree.iter_depth_first()
+                .filter_map(|tree| tree.data())
+                .map(Vec::len)
+                .sum::<usize>()
+        };
+
+        let mut keep_all = build(DuplicatePolicy::KeepAll);
+        assert!(keep_all.insert([0.25, 0.25]).is_ok());
+        assert_eq!(count(&keep_all), 4);
+
+        let mut reject = build(DuplicatePolicy::Reject);
+        assert_eq!(
+            reject.insert([0.25, 0.25]),
+            Err(TpnTreeError::Duplicate {
+                point: vec![0.25, 0.25]
+            })
+        );
+        assert!(reject.insert([0.25, 0.2500001]).is_ok());
+        assert_eq!(count(&reject), 4);
+
+        let mut replace = SpatialTree::<Vec<f64>, 2>::builder()
+            .duplicate_policy(DuplicatePolicy::Replace)
+            .duplicate_tolerance(0.1)
+            .build();
+        replace.insert(vec![0.0, 0.0]).unwrap();
+        replace.insert(vec![0.05, 0.0]).unwrap();
+        assert_eq!(replace.data(), Some(&vec![vec![0.05, 0.0]]));
+    }
+
+    #[test]
+    fn mismatching_dimensions_are_errors() {
+        let mut tree = SpatialTree::<Vec<f64>, 3>::root(1.0);
+
+        let data = vec![0.5, 0.5];
+
+        assert!(!tree.spans(&data));
+        assert_eq!(
+            tree.insert_by_coordinates(data.clone(), &|_| false),
+            Err(TpnTreeError::DimensionMismatch {
+                expected: 3,
+                got: 2
+            })
+        );
+        assert!(tree.find_by_coordinates(&data).is_err());
+        assert!(tree.find_by_coordinates_mut(&data).is_err());
+        assert!(tree.data().is_none());
+    }
+
+    #[test]
+    fn find_descends_directly_in_ten_dimensions() {
+        let mut tree = SpatialTree::<[f64; 10], 10>::root(1.0);
+        tree.divide().expect("Couldn't divide.");
+        tree.get_child_mut(0b1010011001)
+            .unwrap()
+            .divide()
+            .expect("Couldn't divide.");
+
+        let mut point = [0.75; 10];
+        for axis in [0, 3, 4, 7, 9] {
+            point[axis] = -0.75;
+        }
+        let leaf = tree.find_by_coordinates(&point).unwrap();
+
+        assert_eq!(leaf.level(), 2);
+        assert_eq!(leaf.coordinates(), point);
+    }
+
+    #[test]
+    fn insert_returns_leaf_after_cascading_divisions() {
+        let mut tree = Tree3D::root(1.0);
+        let division_condition = |tree: &Tree3D| tree.data().is_some();
+
+        tree.insert_by_coordinates([0.9, 0.9, 0.9], &division_condition)
+            .unwrap();
+        let leaf = tree
+            .insert_into_leaf([0.6, 0.6, 0.6], &division_condition)
+            .unwrap();
+
+        assert_eq!(leaf.level(), 3);
+        assert_eq!(leaf.data(), Some(&vec![[0.6, 0.6, 0.6]]));
+        leaf.data_mut().as_mut().unwrap().push([0.7, 0.7, 0.7]);
+        assert_eq!(tree.iter_data().count(), 3);
+    }
+
+    #[test]
+    fn epsilon_accepts_coordinates_just_outside() {
+        let mut strict = Tree3D::builder().leaf_capacity(1).build();
+        let mut tolerant = Tree3D::builder().leaf_capacity(1).epsilon(1e-9).build();
+        let point = [1.0 + 1e-12, 0.1, -1.0 - 1e-12];
+
+        assert!(matches!(
+            strict.insert(point),
+            Err(TpnTreeError::DoesNotSpan { .. })
+        ));
+        tolerant.insert([0.5, 0.5, 0.5]).unwrap();
+        tolerant.insert(point).unwrap();
+        assert!(tolerant.insert([1.0 + 1e-6, 0.0, 0.0]).is_err());
+
+        let leaf = tolerant.find_by_coordinates(&point).unwrap();
+        assert_eq!(leaf.data(), Some(&vec![point]));
+        assert_eq!(tolerant.check_spatial_invariants(), Ok(()));
+    }
 }
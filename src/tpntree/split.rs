@@ -0,0 +1,180 @@
+use alloc::vec::Vec;
+use core::iter::once;
+
+use super::{Region, SpatialTree};
+use crate::{errors::TpnTreeError, Coordinates};
+
+/// How the items of a leaf are redistributed when it divides during [`SpatialTree::insert_with_policy`].
+///
+/// The item causing the division always moves into the child covering its coordinates.
+pub enum SplitPolicy<'a, T, const N: usize> {
+    /// Every item moves into the child covering its coordinates, like with [`SpatialTree::insert_by_coordinates`].
+    MoveToChildren,
+    /// The items stay in the dividing tree, which holds data and children from then on.
+    KeepInParent,
+    /// Every child receives what the hook returns for each item and the region of the child, if anything.
+    ///
+    /// Use this for items with an extent, e.g. clipping a shape to the child or copying a per-cell average.
+    /// The pieces are put into the new children without dividing them further.
+    DuplicateToOverlapping(&'a dyn Fn(&T, &Region<N>) -> Option<T>),
+}
+
+impl<T, const N: usize> Clone for SplitPolicy<'_, T, N> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, const N: usize> Copy for SplitPolicy<'_, T, N> {}
+
+impl<T: Coordinates<N>, const N: usize> SpatialTree<T, N> {
+    /// Inserts data like [`SpatialTree::insert_by_coordinates`], redistributing the items of dividing leaves according to the `policy`.
+    ///
+    /// Errors if the tree does not span the data or the data has other than N coordinates.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::{SplitPolicy, Tree3D};
+    /// let mut tree = Tree3D::root(1.0);
+    /// let full = |tree: &Tree3D| tree.data().is_some();
+    ///
+    /// tree.insert_with_policy([0.5, 0.5, 0.5], &full, SplitPolicy::KeepInParent).unwrap();
+    /// tree.insert_with_policy([-0.5, -0.5, -0.5], &full, SplitPolicy::KeepInParent).unwrap();
+    ///
+    /// assert_eq!(tree.data(), Some(&vec![[0.5, 0.5, 0.5]]));
+    /// assert_eq!(tree.child_count(), 8);
+    /// ```
+    pub fn insert_with_policy(
+        &mut self,
+        data: T,
+        division_condition: &dyn Fn(&Self) -> bool,
+        policy: SplitPolicy<'_, T, N>,
+    ) -> Result<(), TpnTreeError> {
+        data.try_coordinates()?;
+
+        if self.is_root() && !self.spans(&data) {
+            return Err(self.does_not_span(data.coordinates()));
+        }
+
+        if !self.is_leaf() {
+            return self.insert_into_children_with_policy(data, division_condition, policy);
+        }
+        if !division_condition(self) {
+            self.data.get_or_insert(Vec::new()).push(data);
+            return Ok(());
+        }
+
+        self.divide()?;
+        match policy {
+            SplitPolicy::MoveToChildren => {
+                for data in self
+                    .data
+                    .take()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .chain(once(data))
+                {
+                    self.insert_into_children_with_policy(data, division_condition, policy)?
+                }
+                Ok(())
+            }
+            SplitPolicy::KeepInParent => {
+                self.insert_into_children_with_policy(data, division_condition, policy)
+            }
+            SplitPolicy::DuplicateToOverlapping(split) => {
+                for item in self.data.take().unwrap_or_default() {
+                    for child in self.children.iter_mut() {
+                        if let Some(piece) = split(&item, &child.region()) {
+                            child.data.get_or_insert(Vec::new()).push(piece);
+                        }
+                    }
+                }
+                self.insert_into_children_with_policy(data, division_condition, policy)
+            }
+        }
+    }
+
+    fn insert_into_children_with_policy(
+        &mut self,
+        data: T,
+        division_condition: &dyn Fn(&Self) -> bool,
+        policy: SplitPolicy<'_, T, N>,
+    ) -> Result<(), TpnTreeError> {
+        let index = self.child_index_for(data.coordinates());
+        self.children[index].insert_with_policy(data, division_condition, policy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tpntree::{Region, SpatialTree, SplitPolicy};
+    use crate::Coordinates;
+
+    // a value averaged over a square cell
+    #[derive(Debug, Clone, PartialEq)]
+    struct Cell {
+        center: [f64; 2],
+        value: f64,
+    }
+
+    impl Coordinates<2> for Cell {
+        fn coordinates(&self) -> &[f64] {
+            &self.center
+        }
+    }
+
+    #[test]
+    fn policies_redistribute_differently() {
+        let full =
+            |tree: &SpatialTree<[f64; 2], 2>| tree.data().is_some_and(|data| data.len() >= 2);
+        let points = [[0.5, 0.5], [0.6, 0.6], [-0.5, -0.5]];
+
+        let mut moved = SpatialTree::<[f64; 2], 2>::root(1.0);
+        let mut kept = SpatialTree::<[f64; 2], 2>::root(1.0);
+        for point in points.iter() {
+            moved
+                .insert_with_policy(*point, &full, SplitPolicy::MoveToChildren)
+                .unwrap();
+            kept.insert_with_policy(*point, &full, SplitPolicy::KeepInParent)
+                .unwrap();
+        }
+
+        let mut expected = SpatialTree::<[f64; 2], 2>::root(1.0);
+        for point in points.iter() {
+            expected.insert_by_coordinates(*point, &full).unwrap();
+        }
+        assert_eq!(moved, expected);
+
+        assert_eq!(kept.data(), Some(&vec![[0.5, 0.5], [0.6, 0.6]]));
+        assert_eq!(kept.get_child(3).unwrap().data(), Some(&vec![[-0.5, -0.5]]));
+    }
+
+    #[test]
+    fn duplicate_copies_field_values_to_children() {
+        let mut tree = SpatialTree::<Cell, 2>::root(1.0);
+        let full = |tree: &SpatialTree<Cell, 2>| tree.is_root() && tree.data().is_some();
+        let split = |cell: &Cell, region: &Region<2>| {
+            Some(Cell {
+                center: region.coordinates(),
+                value: cell.value,
+            })
+        };
+
+        for (center, value) in [([0.0, 0.0], 1.0), ([0.5, 0.5], 2.0)].iter() {
+            tree.insert_with_policy(
+                Cell {
+                    center: *center,
+                    value: *value,
+                },
+                &full,
+                SplitPolicy::DuplicateToOverlapping(&split),
+            )
+            .unwrap();
+        }
+
+        assert!(tree.data().is_none());
+        assert_eq!(tree.get_child(0).unwrap().data().map(Vec::len), Some(2));
+        assert!(tree
+            .iter_children()
+            .all(|child| child.data().unwrap()[0].value == 1.0));
+    }
+}
@@ -0,0 +1,111 @@
+use alloc::vec::Vec;
+
+use super::SpatialTree;
+
+/// Figures describing the shape of a [`SpatialTree`], see [`SpatialTree::stats`].
+///
+/// Depths count levels below the tree the statistics were taken of.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TreeStats {
+    /// Number of trees including the root.
+    pub node_count: usize,
+    /// Number of trees without children.
+    pub leaf_count: usize,
+    /// Number of stored items.
+    pub item_count: usize,
+    /// Depth of the deepest leaf.
+    pub max_depth: usize,
+    /// Average depth of the leaves.
+    pub mean_depth: f64,
+    /// Number of leaves by the number of items they hold, index `i` counts the leaves holding `i` items.
+    pub items_per_leaf: Vec<usize>,
+    /// Fraction of leaves holding at least one item.
+    pub occupancy: f64,
+    /// Estimated bytes used by the trees and their items, see [`SpatialTree::memory_usage`].
+    pub memory_bytes: usize,
+}
+
+impl<T, const N: usize> SpatialTree<T, N> {
+    /// Collects statistics of the tree and its descendants.
+    ///
+    /// Useful to tune the leaf capacity and depth of a [`TreeConfig`](super::TreeConfig).
+    ///
+    /// ```
+    /// # use tpntree::tpntree::Tree3D;
+    /// let mut tree = Tree3D::builder().leaf_capacity(1).build();
+    /// tree.insert([0.5, 0.5, 0.5]).expect("Couldn't insert.");
+    /// tree.insert([-0.5, 0.5, 0.5]).expect("Couldn't insert.");
+    ///
+    /// let stats = tree.stats();
+    ///
+    /// assert_eq!(stats.node_count, 9);
+    /// assert_eq!(stats.leaf_count, 8);
+    /// assert_eq!(stats.items_per_leaf, vec![6, 2]);
+    /// assert_eq!(stats.occupancy, 0.25);
+    /// ```
+    pub fn stats(&self) -> TreeStats {
+        let mut stats = TreeStats {
+            node_count: 0,
+            leaf_count: 0,
+            item_count: 0,
+            max_depth: 0,
+            mean_depth: 0.0,
+            items_per_leaf: Vec::new(),
+            occupancy: 0.0,
+            memory_bytes: self.memory_usage().total(),
+        };
+        let mut depth_sum = 0;
+
+        for tree in self.iter_depth_first() {
+            let items = tree.data.as_ref().map_or(0, Vec::len);
+            stats.node_count += 1;
+            stats.item_count += items;
+
+            if tree.is_leaf() {
+                let depth = tree.level - self.level;
+                stats.leaf_count += 1;
+                stats.max_depth = stats.max_depth.max(depth);
+                depth_sum += depth;
+                if stats.items_per_leaf.len() <= items {
+                    stats.items_per_leaf.resize(items + 1, 0);
+                }
+                stats.items_per_leaf[items] += 1;
+            }
+        }
+
+        stats.mean_depth = depth_sum as f64 / stats.leaf_count as f64;
+        let empty_leaves = stats.items_per_leaf.first().copied().unwrap_or(0);
+        stats.occupancy = (stats.leaf_count - empty_leaves) as f64 / stats.leaf_count as f64;
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tpntree::SpatialTree;
+
+    #[test]
+    fn stats_of_uneven_tree() {
+        let mut tree = SpatialTree::<[f64; 2], 2>::root(1.0);
+        tree.divide().unwrap();
+        tree.get_child_mut(0).unwrap().divide().unwrap();
+        tree.insert_by_coordinates([0.9, 0.9], &|_| false).unwrap();
+        tree.insert_by_coordinates([0.8, 0.8], &|_| false).unwrap();
+        tree.insert_by_coordinates([-0.9, -0.9], &|_| false)
+            .unwrap();
+
+        let stats = tree.stats();
+
+        assert_eq!(stats.node_count, 9);
+        assert_eq!(stats.leaf_count, 7);
+        assert_eq!(stats.item_count, 3);
+        assert_eq!(stats.max_depth, 2);
+        assert_eq!(stats.mean_depth, 11.0 / 7.0);
+        assert_eq!(stats.items_per_leaf, vec![5, 1, 1]);
+        assert_eq!(stats.occupancy, 2.0 / 7.0);
+        assert_eq!(
+            tree.get_child(0).unwrap().stats().items_per_leaf,
+            vec![3, 0, 1]
+        );
+    }
+}
@@ -0,0 +1,138 @@
+use super::{TpnTree, VisitControl};
+use alloc::vec::Vec;
+
+impl<T, const N: usize> TpnTree<T, N> {
+    /// Computes a new value for every leaf holding data from its data and the data of its neighbors.
+    ///
+    /// The neighbors are passed in order of axis, first the one above the leaf, then the one below, so there are 2 * N of them.
+    /// A neighbor is the tree of the same level next to the leaf or, where the tree is coarser, the leaf containing that region.
+    /// Neighbors outside the tree or without data are `None`.
+    /// Along periodic axes the neighbors wrap around the tree, see [`TpnTree::wrap`].
+    ///
+    /// All new values are computed from the old ones before any of them is written back.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::TpnTree;
+    /// let mut root = TpnTree::<f64, 1>::root(1.0);
+    /// root.divide_to_depth(2).expect("Couldn't divide.");
+    /// for (i, child) in [0, 1].iter().flat_map(|&i| [(i, 0), (i, 1)]).enumerate() {
+    ///     let (outer, inner) = child;
+    ///     *root.get_child_mut(outer).unwrap().get_child_mut(inner).unwrap().data_mut() = Some(i as f64);
+    /// }
+    ///
+    /// // average with the neighbors, like a diffusion step
+    /// root.apply_stencil(|&value, neighbors| {
+    ///     let values = neighbors.iter().flatten().map(|&&value| value).collect::<Vec<_>>();
+    ///     (value + values.iter().sum::<f64>()) / (1 + values.len()) as f64
+    /// });
+    ///
+    /// assert_eq!(root.get_child(0).unwrap().get_child(1).unwrap().data(), Some(&1.0));
+    /// ```
+    pub fn apply_stencil(&mut self, mut f: impl FnMut(&T, &[Option<&T>]) -> T) {
+        let mut values = Vec::new();
+        for leaf in self.iter_depth_first().filter(|tree| tree.is_leaf()) {
+            if let Some(data) = &leaf.data {
+                let neighbors = self
+                    .neighbor_centers(leaf)
+                    .iter()
+                    .map(|center| {
+                        self.find_at_level(center, leaf.level)
+                            .and_then(|neighbor| neighbor.data.as_ref())
+                    })
+                    .collect::<Vec<_>>();
+                values.push(f(data, &neighbors));
+            }
+        }
+
+        // the depth first iteration visits the children in reverse order, so the values are popped in visiting order
+        self.visit_mut(&mut |tree: &mut Self| {
+            if tree.is_leaf() && tree.data.is_some() {
+                tree.data = values.pop();
+            }
+            VisitControl::Continue
+        });
+    }
+
+    // centers of the trees of the same size next to the tree, above then below along each axis
+    fn neighbor_centers(&self, tree: &Self) -> Vec<[f64; N]> {
+        let mut centers = Vec::with_capacity(2 * N);
+        for axis in 0..N {
+            for direction in [1.0, -1.0] {
+                let mut center = tree.coordinates;
+                center[axis] += direction * 2.0 * tree.span[axis];
+                centers.push(self.wrap(&center));
+            }
+        }
+        centers
+    }
+
+    // descends towards the point no deeper than the level, assuming the point is not on a face of the tree at that level
+    pub(crate) fn find_at_level(&self, point: &[f64], level: usize) -> Option<&Self> {
+        if !self.spans_coordinates(point) {
+            return None;
+        }
+
+        let mut tree = self;
+        while tree.level < level && !tree.is_leaf() {
+            tree = &tree.children[tree.child_index_for(point)];
+        }
+        Some(tree)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use crate::tpntree::{TpnTree, VisitControl};
+
+    #[test]
+    fn stencil_reads_old_values_only() {
+        let mut tree = TpnTree::<f64, 2>::root(1.0);
+        assert!(tree.divide().is_ok());
+        for (index, child) in tree.iter_children_mut().enumerate() {
+            *child.data_mut() = Some(index as f64);
+        }
+
+        tree.apply_stencil(|&value, neighbors| {
+            10.0 * value + neighbors.iter().flatten().map(|&&value| value).sum::<f64>()
+        });
+
+        // child 0 is above 1 along the first axis and above 2 along the second
+        assert_eq!(tree.get_child(0).unwrap().data(), Some(&3.0));
+        assert_eq!(tree.get_child(1).unwrap().data(), Some(&13.0));
+        assert_eq!(tree.get_child(2).unwrap().data(), Some(&23.0));
+        assert_eq!(tree.get_child(3).unwrap().data(), Some(&33.0));
+    }
+
+    #[test]
+    fn coarser_neighbors_and_missing_data() {
+        let mut tree = TpnTree::<f64, 2>::root(1.0);
+        assert!(tree.divide().is_ok());
+        assert!(tree.get_child_mut(0).unwrap().divide().is_ok());
+        *tree.get_child_mut(1).unwrap().data_mut() = Some(10.0);
+        tree.get_child_mut(0)
+            .unwrap()
+            .visit_mut(&mut |tree: &mut TpnTree<f64, 2>| {
+                if tree.is_leaf() {
+                    *tree.data_mut() = Some(1.0);
+                }
+                VisitControl::Continue
+            });
+
+        let mut seen = Vec::new();
+        tree.apply_stencil(|&value, neighbors| {
+            seen.push(
+                neighbors
+                    .iter()
+                    .map(|neighbor| neighbor.copied())
+                    .collect::<Vec<_>>(),
+            );
+            value
+        });
+
+        // the child at [0.25, 0.25] of child 0 sees the coarser child 1 below along the first axis
+        assert!(seen.contains(&vec![Some(1.0), Some(10.0), Some(1.0), None]));
+        // child 1 sees no finer trees, but child 0 above along the first axis, which holds no data
+        assert!(seen.contains(&vec![None, None, None, None]));
+    }
+}
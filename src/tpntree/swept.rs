@@ -0,0 +1,181 @@
+use alloc::vec::Vec;
+
+use super::{Bounded, LooseTree};
+
+impl<T, const N: usize> LooseTree<T, N> {
+    /// Returns all objects whose bounding boxes are hit by the box given by center and half size while it moves by the displacement.
+    ///
+    /// Each object comes with the fraction of the displacement, between 0.0 and 1.0, after which the moving box first touches it.
+    /// Objects are ordered by that time of impact, objects already intersecting the box at the start have time 0.0.
+    /// Trees whose loose bounds the swept box misses are skipped together with their children.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::LooseTree;
+    /// let mut tree = LooseTree::<&str, 2>::builder().looseness(2.0).build();
+    /// tree.insert_with_extent("far", [0.75, 0.0], [0.125, 0.125], &|_| false)
+    ///     .expect("Couldn't insert.");
+    /// tree.insert_with_extent("near", [0.0, 0.0], [0.125, 0.125], &|_| false)
+    ///     .expect("Couldn't insert.");
+    /// tree.insert_with_extent("aside", [0.0, 0.75], [0.125, 0.125], &|_| false)
+    ///     .expect("Couldn't insert.");
+    ///
+    /// let hits = tree.query_swept_aabb(&[-0.75, 0.0], &[0.125, 0.125], &[2.0, 0.0]);
+    ///
+    /// assert_eq!(
+    ///     hits.iter().map(|(bounded, _)| bounded.data).collect::<Vec<_>>(),
+    ///     vec!["near", "far"]
+    /// );
+    /// assert_eq!((hits[0].1, hits[1].1), (0.25, 0.625));
+    /// ```
+    pub fn query_swept_aabb(
+        &self,
+        center: &[f64; N],
+        half_size: &[f64; N],
+        displacement: &[f64; N],
+    ) -> Vec<(&Bounded<T, N>, f64)> {
+        let mut hits = Vec::new();
+        self.query_swept_recursive(center, half_size, displacement, &mut hits);
+        hits.sort_by(|(_, a), (_, b)| a.total_cmp(b));
+        hits
+    }
+
+    fn query_swept_recursive<'a>(
+        &'a self,
+        center: &[f64; N],
+        half_size: &[f64; N],
+        displacement: &[f64; N],
+        hits: &mut Vec<(&'a Bounded<T, N>, f64)>,
+    ) {
+        let loose_span = self.loose_span();
+        if time_of_impact(
+            center,
+            half_size,
+            displacement,
+            &self.coordinates,
+            &loose_span,
+        )
+        .is_none()
+        {
+            return;
+        }
+
+        hits.extend(self.data.iter().flatten().filter_map(|bounded| {
+            time_of_impact(
+                center,
+                half_size,
+                displacement,
+                &bounded.center,
+                &bounded.half_size,
+            )
+            .map(|time| (bounded, time))
+        }));
+
+        for child in &self.children {
+            child.query_swept_recursive(center, half_size, displacement, hits);
+        }
+    }
+}
+
+// the earliest fraction of the displacement at which the moving box touches the target box, if it does
+fn time_of_impact<const N: usize>(
+    center: &[f64; N],
+    half_size: &[f64; N],
+    displacement: &[f64; N],
+    target_center: &[f64; N],
+    target_half_size: &[f64; N],
+) -> Option<f64> {
+    let mut enter = 0.0_f64;
+    let mut exit = 1.0_f64;
+    for i in 0..N {
+        // the moving center touches the target box grown by the half size of the moving box
+        let offset = target_center[i] - center[i];
+        let extent = target_half_size[i] + half_size[i];
+        if displacement[i] == 0.0 {
+            if offset.abs() > extent {
+                return None;
+            }
+            continue;
+        }
+        let near = (offset - extent) / displacement[i];
+        let far = (offset + extent) / displacement[i];
+        enter = enter.max(near.min(far));
+        exit = exit.min(near.max(far));
+        if enter > exit {
+            return None;
+        }
+    }
+    Some(enter)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::time_of_impact;
+    use crate::tpntree::LooseTree;
+
+    #[test]
+    fn impact_times_of_boxes() {
+        let moving = ([0.0, 0.0], [0.5, 0.5]);
+
+        // straight ahead, reached halfway
+        assert_eq!(
+            time_of_impact(&moving.0, &moving.1, &[4.0, 0.0], &[3.0, 0.0], &[0.5, 0.5]),
+            Some(0.5)
+        );
+        // already intersecting
+        assert_eq!(
+            time_of_impact(&moving.0, &moving.1, &[4.0, 0.0], &[0.5, 0.5], &[0.5, 0.5]),
+            Some(0.0)
+        );
+        // beyond the end of the displacement
+        assert_eq!(
+            time_of_impact(&moving.0, &moving.1, &[1.0, 0.0], &[3.0, 0.0], &[0.5, 0.5]),
+            None
+        );
+        // passed diagonally
+        assert_eq!(
+            time_of_impact(&moving.0, &moving.1, &[4.0, 4.0], &[4.0, 0.0], &[0.5, 0.5]),
+            None
+        );
+        // moving away
+        assert_eq!(
+            time_of_impact(&moving.0, &moving.1, &[-4.0, 0.0], &[3.0, 0.0], &[0.5, 0.5]),
+            None
+        );
+    }
+
+    #[test]
+    fn swept_query_matches_brute_force() {
+        let mut tree = LooseTree::<usize, 2>::builder().looseness(2.0).build();
+        let division_condition = |tree: &LooseTree<usize, 2>| tree.level() < 3;
+        let objects = (0..40)
+            .map(|i| {
+                let t = i as f64;
+                ([0.8 * (t * 0.9).sin(), 0.8 * (t * 1.7).cos()], [0.05, 0.02])
+            })
+            .collect::<Vec<_>>();
+        for (i, (center, half_size)) in objects.iter().enumerate() {
+            tree.insert_with_extent(i, *center, *half_size, &division_condition)
+                .unwrap();
+        }
+        let (center, half_size, displacement) = ([-0.9, -0.7], [0.1, 0.1], [1.6, 1.2]);
+
+        let hits = tree.query_swept_aabb(&center, &half_size, &displacement);
+
+        let expected = objects
+            .iter()
+            .filter(|(target_center, target_half_size)| {
+                time_of_impact(
+                    &center,
+                    &half_size,
+                    &displacement,
+                    target_center,
+                    target_half_size,
+                )
+                .is_some()
+            })
+            .count();
+        assert!(expected > 0);
+        assert_eq!(hits.len(), expected);
+        assert!(hits.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+    }
+}
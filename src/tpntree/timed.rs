@@ -0,0 +1,107 @@
+use alloc::vec::Vec;
+
+use super::{SpatialTree, TpnTree};
+use crate::{errors::TpnTreeError, Coordinates};
+
+/// An item stored in a [`TimedTree`] together with the time it was inserted at.
+///
+/// The timestamp can be anything ordered, e.g. an [`Instant`](std::time::Instant) or the sequence number of a sensor frame.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Timestamped<T, S> {
+    pub item: T,
+    pub timestamp: S,
+}
+
+impl<T: Coordinates<N>, S, const N: usize> Coordinates<N> for Timestamped<T, S> {
+    fn coordinates(&self) -> &[f64] {
+        self.item.coordinates()
+    }
+}
+
+/// A helper type to maintain rolling windows of spatial data.
+pub type TimedTree<T, S, const N: usize> = SpatialTree<Timestamped<T, S>, N>;
+
+impl<T: Coordinates<N>, S: PartialOrd, const N: usize> TimedTree<T, S, N> {
+    /// Inserts the item with its timestamp like [`SpatialTree::insert_by_coordinates`].
+    ///
+    /// Errors if the tree does not span the item or the item has other than N coordinates.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::TimedTree;
+    /// let mut tree = TimedTree::<[f64; 2], u64, 2>::root(1.0);
+    ///
+    /// tree.insert_with_timestamp([0.5, 0.5], 1, &|_| false).unwrap();
+    /// tree.insert_with_timestamp([-0.5, 0.5], 2, &|_| false).unwrap();
+    ///
+    /// assert_eq!(tree.expire_older_than(&2), 1);
+    /// assert_eq!(tree.data().unwrap()[0].item, [-0.5, 0.5]);
+    /// ```
+    pub fn insert_with_timestamp(
+        &mut self,
+        item: T,
+        timestamp: S,
+        division_condition: &dyn Fn(&Self) -> bool,
+    ) -> Result<(), TpnTreeError> {
+        self.insert_by_coordinates(Timestamped { item, timestamp }, division_condition)
+    }
+
+    /// Removes all items with a timestamp before `timestamp` and collapses trees whose subtree holds no items anymore.
+    ///
+    /// Leaves left without items hold no data afterwards.
+    /// Returns the count of removed items.
+    pub fn expire_older_than(&mut self, timestamp: &S) -> usize {
+        self.drain_filter(|item| item.timestamp < *timestamp).len()
+    }
+}
+
+impl<T, S: PartialOrd, const N: usize> TpnTree<Vec<Timestamped<T, S>>, N> {
+    /// Returns the latest timestamp of all items in the tree, if there are any.
+    pub fn latest_timestamp(&self) -> Option<&S> {
+        self.iter_depth_first()
+            .filter_map(|tree| tree.data())
+            .flatten()
+            .map(|item| &item.timestamp)
+            .fold(None, |latest, timestamp| match latest {
+                Some(latest) if latest >= timestamp => Some(latest),
+                _ => Some(timestamp),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tpntree::TimedTree;
+
+    #[test]
+    fn rolling_window_collapses_branches() {
+        let mut tree = TimedTree::<[f64; 2], u64, 2>::root(1.0);
+        let division_condition =
+            |tree: &TimedTree<[f64; 2], u64, 2>| tree.data().is_some_and(|data| data.len() >= 2);
+
+        for (time, point) in [
+            [0.5, 0.5],
+            [0.6, 0.6],
+            [0.7, 0.4],
+            [-0.5, -0.5],
+            [-0.6, 0.5],
+        ]
+        .iter()
+        .enumerate()
+        {
+            tree.insert_with_timestamp(*point, time as u64, &division_condition)
+                .unwrap();
+        }
+        assert!(!tree.get_child(0).unwrap().is_leaf());
+        assert_eq!(tree.latest_timestamp(), Some(&4));
+
+        // the upper right quadrant only holds the first three items
+        assert_eq!(tree.expire_older_than(&3), 3);
+        assert!(tree.get_child(0).unwrap().is_leaf());
+        assert!(tree.get_child(0).unwrap().data().is_none());
+        assert!(!tree.is_leaf());
+
+        assert_eq!(tree.expire_older_than(&5), 2);
+        assert!(tree.is_leaf());
+        assert_eq!(tree.latest_timestamp(), None);
+    }
+}
@@ -0,0 +1,280 @@
+use alloc::vec::Vec;
+
+use super::{SpatialTree, TpnTree};
+use crate::{errors::TpnTreeError, Coordinates};
+
+/// An affine transform placing a tree in world space, e.g. translated, rotated and scaled within a scene.
+///
+/// Maps a point `p` in tree space to `linear * p + translation` in world space.
+/// Attach it to a tree with [`TreeConfig::with_world_transform`](super::TreeConfig::with_world_transform).
+///
+/// ```
+/// # use tpntree::tpntree::WorldTransform;
+/// // a quarter turn followed by a shift
+/// let transform = WorldTransform::new([[0.0, -1.0], [1.0, 0.0]], [10.0, 0.0]).expect("Invertible.");
+///
+/// assert_eq!(transform.tree_to_world(&[1.0, 2.0]), [8.0, 1.0]);
+/// assert_eq!(transform.world_to_tree(&[8.0, 1.0]), [1.0, 2.0]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorldTransform {
+    // row-major, tree to world space
+    linear: Vec<f64>,
+    // row-major, world to tree space
+    inverse: Vec<f64>,
+    translation: Vec<f64>,
+}
+
+impl WorldTransform {
+    /// Creates a transform from its linear part, given as rows, and its translation.
+    ///
+    /// Errors with [`TpnTreeError::SingularTransform`] if the linear part is not invertible.
+    pub fn new<const N: usize>(
+        linear: [[f64; N]; N],
+        translation: [f64; N],
+    ) -> Result<Self, TpnTreeError> {
+        Self::from_parts(linear.concat(), translation.to_vec())
+    }
+
+    /// Creates a transform only shifting points.
+    pub fn from_translation<const N: usize>(translation: [f64; N]) -> Self {
+        let identity =
+            core::array::from_fn(|row| core::array::from_fn(|column| (row == column) as u8 as f64));
+        Self::new(identity, translation).expect("The identity is invertible.")
+    }
+
+    // creates the transform from the row-major linear part and the translation
+    pub(crate) fn from_parts(
+        linear: Vec<f64>,
+        translation: Vec<f64>,
+    ) -> Result<Self, TpnTreeError> {
+        let dimension = translation.len();
+        if linear.len() != dimension * dimension {
+            return Err(TpnTreeError::DimensionMismatch {
+                expected: dimension * dimension,
+                got: linear.len(),
+            });
+        }
+        let inverse = invert(&linear, dimension).ok_or(TpnTreeError::SingularTransform)?;
+        Ok(Self {
+            linear,
+            inverse,
+            translation,
+        })
+    }
+
+    /// Returns the number of dimensions the transform acts on.
+    pub fn dimension(&self) -> usize {
+        self.translation.len()
+    }
+
+    /// Returns the row-major linear part.
+    pub fn linear(&self) -> &[f64] {
+        &self.linear
+    }
+
+    /// Returns the translation.
+    pub fn translation(&self) -> &[f64] {
+        &self.translation
+    }
+
+    /// Maps the point from tree space to world space.
+    ///
+    /// Panics if the transform is not of dimension N.
+    pub fn tree_to_world<const N: usize>(&self, point: &[f64; N]) -> [f64; N] {
+        self.check_dimension(N);
+        core::array::from_fn(|row| {
+            self.translation[row]
+                + (0..N)
+                    .map(|column| self.linear[row * N + column] * point[column])
+                    .sum::<f64>()
+        })
+    }
+
+    /// Maps the point from world space to tree space.
+    ///
+    /// Panics if the transform is not of dimension N.
+    pub fn world_to_tree<const N: usize>(&self, point: &[f64; N]) -> [f64; N] {
+        self.check_dimension(N);
+        core::array::from_fn(|row| {
+            (0..N)
+                .map(|column| {
+                    self.inverse[row * N + column] * (point[column] - self.translation[column])
+                })
+                .sum::<f64>()
+        })
+    }
+
+    fn check_dimension(&self, dimension: usize) {
+        assert_eq!(
+            self.dimension(),
+            dimension,
+            "Expected a transform of dimension {}, got {}.",
+            dimension,
+            self.dimension()
+        );
+    }
+}
+
+// inverts the row-major square matrix by Gauss-Jordan elimination with partial pivoting
+fn invert(matrix: &[f64], dimension: usize) -> Option<Vec<f64>> {
+    let mut matrix = matrix.to_vec();
+    let mut inverse = (0..dimension * dimension)
+        .map(|i| (i / dimension == i % dimension) as u8 as f64)
+        .collect::<Vec<_>>();
+
+    for column in 0..dimension {
+        let pivot = (column..dimension).max_by(|&a, &b| {
+            matrix[a * dimension + column]
+                .abs()
+                .total_cmp(&matrix[b * dimension + column].abs())
+        })?;
+        if matrix[pivot * dimension + column] == 0.0 {
+            return None;
+        }
+        for i in 0..dimension {
+            matrix.swap(column * dimension + i, pivot * dimension + i);
+            inverse.swap(column * dimension + i, pivot * dimension + i);
+        }
+
+        let factor = matrix[column * dimension + column];
+        for i in 0..dimension {
+            matrix[column * dimension + i] /= factor;
+            inverse[column * dimension + i] /= factor;
+        }
+        for row in (0..dimension).filter(|&row| row != column) {
+            let factor = matrix[row * dimension + column];
+            for i in 0..dimension {
+                matrix[row * dimension + i] -= factor * matrix[column * dimension + i];
+                inverse[row * dimension + i] -= factor * inverse[column * dimension + i];
+            }
+        }
+    }
+    inverse
+        .iter()
+        .all(|value| value.is_finite())
+        .then_some(inverse)
+}
+
+impl<T, const N: usize> TpnTree<T, N> {
+    /// Returns the transform placing the tree in world space, if its [`TreeConfig`](super::TreeConfig) has one.
+    pub fn world_transform(&self) -> Option<&WorldTransform> {
+        self.config().and_then(|config| config.world_transform())
+    }
+
+    /// Maps the point from world space to the space of the tree, leaving it as it is without a world transform.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::{TpnTree, WorldTransform};
+    /// let tree = TpnTree::<(), 2>::builder()
+    ///     .world_transform(WorldTransform::from_translation([5.0, 0.0]))
+    ///     .build();
+    ///
+    /// assert_eq!(tree.world_to_tree(&[5.5, 0.5]), [0.5, 0.5]);
+    /// assert_eq!(tree.tree_to_world(&[0.5, 0.5]), [5.5, 0.5]);
+    /// ```
+    pub fn world_to_tree(&self, point: &[f64; N]) -> [f64; N] {
+        self.world_transform()
+            .map_or(*point, |transform| transform.world_to_tree(point))
+    }
+
+    /// Maps the point from the space of the tree to world space, leaving it as it is without a world transform.
+    pub fn tree_to_world(&self, point: &[f64; N]) -> [f64; N] {
+        self.world_transform()
+            .map_or(*point, |transform| transform.tree_to_world(point))
+    }
+}
+
+impl<T: Coordinates<N>, const N: usize> SpatialTree<T, N> {
+    /// Returns the leaf covering the point given in world space, descending half-open like [`SpatialTree::find_by_coordinates`].
+    ///
+    /// Errors if the tree does not span the point mapped to tree space.
+    pub fn find_by_world_point(&self, point: &[f64; N]) -> Result<&Self, TpnTreeError> {
+        let point = self.world_to_tree(point);
+        if !self.spans_coordinates(&point) {
+            return Err(self.does_not_span(&point));
+        }
+        let mut tree = self;
+        while !tree.is_leaf() {
+            tree = &tree.children[tree.child_index_for(&point)];
+        }
+        Ok(tree)
+    }
+
+    /// Returns the k items closest to the point given in world space, see [`SpatialTree::nearest_neighbors`].
+    ///
+    /// Distances are measured in tree space, which orders items like world space for transforms that only rotate, shift and scale uniformly.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::{Tree3D, WorldTransform};
+    /// let mut tree = Tree3D::builder()
+    ///     .world_transform(WorldTransform::from_translation([0.0, 0.0, 100.0]))
+    ///     .build();
+    /// tree.insert([0.5, 0.5, 0.5]).expect("Couldn't insert.");
+    /// tree.insert([-0.5, -0.5, -0.5]).expect("Couldn't insert.");
+    ///
+    /// assert_eq!(tree.nearest_neighbors_world(&[0.4, 0.4, 100.4], 1), vec![&[0.5, 0.5, 0.5]]);
+    /// ```
+    pub fn nearest_neighbors_world(&self, point: &[f64; N], k: usize) -> Vec<&T> {
+        self.nearest_neighbors(&self.world_to_tree(point), k)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tpntree::{Tree3D, TreeConfig, WorldTransform};
+    use crate::TpnTreeError;
+
+    #[test]
+    fn round_trip_through_world_space() {
+        let (sin, cos) = 0.7_f64.sin_cos();
+        let transform = WorldTransform::new(
+            [
+                [2.0 * cos, -2.0 * sin, 0.0],
+                [2.0 * sin, 2.0 * cos, 0.0],
+                [0.0, 0.0, 2.0],
+            ],
+            [3.0, -1.0, 0.5],
+        )
+        .unwrap();
+        let point = [0.3, -0.8, 0.25];
+
+        let back = transform.world_to_tree(&transform.tree_to_world(&point));
+        for axis in 0..3 {
+            assert!((back[axis] - point[axis]).abs() < 1e-12);
+        }
+        assert_eq!(
+            WorldTransform::new([[1.0, 2.0], [2.0, 4.0]], [0.0, 0.0]),
+            Err(TpnTreeError::SingularTransform)
+        );
+    }
+
+    #[test]
+    fn queries_take_world_points() {
+        let transform = WorldTransform::new(
+            [[0.0, -4.0, 0.0], [4.0, 0.0, 0.0], [0.0, 0.0, 4.0]],
+            [10.0, 10.0, 10.0],
+        )
+        .unwrap();
+        let mut tree = Tree3D::root(1.0);
+        tree.set_config(
+            TreeConfig::default()
+                .with_leaf_capacity(1)
+                .with_world_transform(transform.clone()),
+        );
+        tree.insert([0.5, 0.5, 0.5]).unwrap();
+        tree.insert([-0.5, 0.5, 0.5]).unwrap();
+
+        let world = transform.tree_to_world(&[0.5, 0.5, 0.5]);
+        assert_eq!(world, [8.0, 12.0, 12.0]);
+        assert_eq!(
+            tree.find_by_world_point(&world).unwrap().data(),
+            Some(&vec![[0.5, 0.5, 0.5]])
+        );
+        assert_eq!(
+            tree.nearest_neighbors_world(&[8.0, 8.0, 12.0], 1),
+            vec![&[-0.5, 0.5, 0.5]]
+        );
+        assert!(tree.find_by_world_point(&[0.0, 0.0, 0.0]).is_err());
+    }
+}
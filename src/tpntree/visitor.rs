@@ -0,0 +1,175 @@
+use super::TpnTree;
+
+/// Decides how a traversal proceeds after a node has been visited.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum VisitControl {
+    /// Descend into the children of the visited node.
+    Continue,
+    /// Do not descend into the children of the visited node, but continue with its siblings.
+    SkipChildren,
+    /// End the traversal immediately.
+    Stop,
+}
+
+/// A visitor called for every node of a tree during [`TpnTree::visit`].
+///
+/// Any closure `FnMut(&TpnTree<T, N>) -> VisitControl` is a visitor.
+pub trait TpnTreeVisitor<T, const N: usize> {
+    fn visit(&mut self, tree: &TpnTree<T, N>) -> VisitControl;
+}
+
+/// A visitor called for every node of a tree during [`TpnTree::visit_mut`].
+///
+/// Any closure `FnMut(&mut TpnTree<T, N>) -> VisitControl` is a mutable visitor.
+pub trait TpnTreeVisitorMut<T, const N: usize> {
+    fn visit_mut(&mut self, tree: &mut TpnTree<T, N>) -> VisitControl;
+}
+
+impl<T, F, const N: usize> TpnTreeVisitor<T, N> for F
+where
+    F: FnMut(&TpnTree<T, N>) -> VisitControl,
+{
+    fn visit(&mut self, tree: &TpnTree<T, N>) -> VisitControl {
+        self(tree)
+    }
+}
+
+impl<T, F, const N: usize> TpnTreeVisitorMut<T, N> for F
+where
+    F: FnMut(&mut TpnTree<T, N>) -> VisitControl,
+{
+    fn visit_mut(&mut self, tree: &mut TpnTree<T, N>) -> VisitControl {
+        self(tree)
+    }
+}
+
+impl<T, const N: usize> TpnTree<T, N> {
+    /// Visits the tree depth first, starting with the root, letting the visitor control the traversal.
+    ///
+    /// Children are visited in the order of their index.
+    ///
+    /// ```
+    /// # use tpntree::tpntree::{TpnTree, VisitControl};
+    /// let mut root = TpnTree::<(), 2>::root(1.0);
+    /// root.divide().expect("Couldn't divide.");
+    ///
+    /// let mut visited = 0;
+    /// root.visit(&mut |_: &TpnTree<(), 2>| {
+    ///     visited += 1;
+    ///     VisitControl::SkipChildren
+    /// });
+    ///
+    /// assert_eq!(visited, 1);
+    /// ```
+    pub fn visit(&self, visitor: &mut impl TpnTreeVisitor<T, N>) {
+        self.visit_recursive(visitor);
+    }
+
+    /// Visits the tree depth first by mutable reference, starting with the root, letting the visitor control the traversal.
+    ///
+    /// The children are looked at after the visitor returns, so a visitor dividing a node will see the new children next.
+    pub fn visit_mut(&mut self, visitor: &mut impl TpnTreeVisitorMut<T, N>) {
+        self.visit_mut_recursive(visitor);
+    }
+
+    // returns false if the traversal has been stopped
+    fn visit_recursive(&self, visitor: &mut impl TpnTreeVisitor<T, N>) -> bool {
+        match visitor.visit(self) {
+            VisitControl::Continue => self
+                .children
+                .iter()
+                .all(|child| child.visit_recursive(visitor)),
+            VisitControl::SkipChildren => true,
+            VisitControl::Stop => false,
+        }
+    }
+
+    fn visit_mut_recursive(&mut self, visitor: &mut impl TpnTreeVisitorMut<T, N>) -> bool {
+        match visitor.visit_mut(self) {
+            VisitControl::Continue => self
+                .children
+                .iter_mut()
+                .all(|child| child.visit_mut_recursive(visitor)),
+            VisitControl::SkipChildren => true,
+            VisitControl::Stop => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tpntree::{TpnTree, VisitControl};
+
+    #[test]
+    fn visit_all_nodes() {
+        let mut tree = TpnTree::<(), 2>::root(1.0);
+
+        assert!(tree.divide().is_ok());
+        assert!(tree.get_child_mut(0).unwrap().divide().is_ok());
+
+        let mut visited = 0;
+        tree.visit(&mut |_: &TpnTree<(), 2>| {
+            visited += 1;
+            VisitControl::Continue
+        });
+
+        assert_eq!(visited, 9);
+    }
+
+    #[test]
+    fn visit_skips_children() {
+        let mut tree = TpnTree::<(), 2>::root(1.0);
+
+        assert!(tree.divide().is_ok());
+        assert!(tree.get_child_mut(0).unwrap().divide().is_ok());
+
+        let mut levels = Vec::new();
+        tree.visit(&mut |tree: &TpnTree<(), 2>| {
+            levels.push(tree.level());
+            if tree.level() == 1 {
+                VisitControl::SkipChildren
+            } else {
+                VisitControl::Continue
+            }
+        });
+
+        assert_eq!(levels, vec![0, 1, 1, 1, 1]);
+    }
+
+    #[test]
+    fn visit_stops() {
+        let mut tree = TpnTree::<(), 2>::root(1.0);
+
+        assert!(tree.divide().is_ok());
+
+        let mut visited = 0;
+        tree.visit(&mut |tree: &TpnTree<(), 2>| {
+            visited += 1;
+            if tree.level() == 1 {
+                VisitControl::Stop
+            } else {
+                VisitControl::Continue
+            }
+        });
+
+        assert_eq!(visited, 2);
+    }
+
+    #[test]
+    fn visit_mut_divides_to_level() {
+        let mut tree = TpnTree::<usize, 2>::root(1.0);
+
+        tree.visit_mut(&mut |tree: &mut TpnTree<usize, 2>| {
+            *tree.data_mut() = Some(tree.level());
+            if tree.level() < 2 {
+                assert!(tree.divide().is_ok());
+            }
+            VisitControl::Continue
+        });
+
+        assert_eq!(tree.iter_depth_first().count(), 1 + 4 + 16);
+        assert!(tree
+            .iter_depth_first()
+            .all(|tree| tree.data() == Some(&tree.level())));
+    }
+}
@@ -0,0 +1,208 @@
+use super::TpnTree;
+use crate::{errors::TpnTreeError, Coordinates};
+use alloc::vec::Vec;
+
+/// [`Weighted`] is required for a type to be used inside a [`WeightedTree`].
+pub trait Weighted {
+    fn weight(&self) -> f64;
+}
+
+/// The data of every tree in a [`WeightedTree`].
+///
+/// Every tree accumulates the weight of all items in its subtree, only leaves hold the items.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeightedBin<T, const N: usize> {
+    items: Vec<T>,
+    weight: f64,
+    weighted_coordinates: [f64; N],
+}
+
+impl<T, const N: usize> Default for WeightedBin<T, N> {
+    fn default() -> Self {
+        Self {
+            items: Vec::new(),
+            weight: 0.0,
+            weighted_coordinates: [0.0; N],
+        }
+    }
+}
+
+impl<T: Coordinates<N> + Weighted, const N: usize> WeightedBin<T, N> {
+    fn accumulate(&mut self, item: &T) {
+        let weight = item.weight();
+        self.weight += weight;
+        for (sum, coordinate) in self.weighted_coordinates.iter_mut().zip(item.coordinates()) {
+            *sum += weight * coordinate;
+        }
+    }
+}
+
+/// A helper type to track the total weight and weighted centroid of spatial data.
+pub type WeightedTree<T, const N: usize> = TpnTree<WeightedBin<T, N>, N>;
+
+impl<T: Coordinates<N> + Weighted, const N: usize> WeightedTree<T, N> {
+    /// Inserts data in the tree with its center closest to the data given the constrains of the `division_condition`, like [`super::SpatialTree::insert_by_coordinates`].
+    ///
+    /// Every tree on the way accumulates the weight and weighted coordinates of the data.
+    ///
+    /// ```
+    /// # use tpntree::{Coordinates, tpntree::{Weighted, WeightedTree}};
+    /// struct Body([f64; 2], f64);
+    ///
+    /// impl Coordinates<2> for Body {
+    ///     fn coordinates(&self) -> &[f64] {
+    ///         &self.0
+    ///     }
+    /// }
+    ///
+    /// impl Weighted for Body {
+    ///     fn weight(&self) -> f64 {
+    ///         self.1
+    ///     }
+    /// }
+    ///
+    /// let mut tree = WeightedTree::<Body, 2>::root(1.0);
+    /// tree.insert_weighted(Body([0.5, 0.5], 3.0), &|_| false).expect("Couldn't insert.");
+    /// tree.insert_weighted(Body([-0.5, -0.5], 1.0), &|_| false).expect("Couldn't insert.");
+    ///
+    /// assert_eq!(tree.total_weight(), 4.0);
+    /// assert_eq!(tree.centroid(), Some([0.25, 0.25]));
+    /// ```
+    pub fn insert_weighted(
+        &mut self,
+        data: T,
+        division_condition: &dyn Fn(&Self) -> bool,
+    ) -> Result<(), TpnTreeError> {
+        let coordinates = data.try_coordinates()?;
+
+        if self.is_root() && !self.spans_coordinates(coordinates) {
+            return Err(self.does_not_span(coordinates));
+        }
+
+        self.insert_weighted_recursive(data, division_condition);
+        Ok(())
+    }
+
+    fn insert_weighted_recursive(&mut self, data: T, division_condition: &dyn Fn(&Self) -> bool) {
+        if self.is_leaf() && division_condition(self) && self.divide().is_ok() {
+            let items = self
+                .data
+                .as_mut()
+                .map(|bin| core::mem::take(&mut bin.items))
+                .unwrap_or_default();
+            for item in items {
+                let index = self.child_index_for(item.coordinates());
+                let bin = self.children[index]
+                    .data
+                    .get_or_insert_with(Default::default);
+                bin.accumulate(&item);
+                bin.items.push(item);
+            }
+        }
+
+        self.data
+            .get_or_insert_with(Default::default)
+            .accumulate(&data);
+
+        if self.is_leaf() {
+            if let Some(bin) = self.data.as_mut() {
+                bin.items.push(data);
+            }
+        } else {
+            let index = self.child_index_for(data.coordinates());
+            self.children[index].insert_weighted_recursive(data, division_condition);
+        }
+    }
+
+    /// Returns the total weight of all data in the subtree.
+    pub fn total_weight(&self) -> f64 {
+        self.data.as_ref().map_or(0.0, |bin| bin.weight)
+    }
+
+    /// Returns the weighted centroid of all data in the subtree, if the total weight is not zero.
+    pub fn centroid(&self) -> Option<[f64; N]> {
+        self.data
+            .as_ref()
+            .filter(|bin| bin.weight != 0.0)
+            .map(|bin| {
+                let mut centroid = bin.weighted_coordinates;
+                for coordinate in centroid.iter_mut() {
+                    *coordinate /= bin.weight;
+                }
+                centroid
+            })
+    }
+
+    /// Returns the data held by the tree, which is empty unless the tree is a leaf.
+    pub fn items(&self) -> &[T] {
+        self.data.as_ref().map_or(&[], |bin| &bin.items)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use super::{Weighted, WeightedTree};
+    use crate::Coordinates;
+
+    #[derive(Debug, PartialEq)]
+    struct Charge([f64; 2], f64);
+
+    impl Coordinates<2> for Charge {
+        fn coordinates(&self) -> &[f64] {
+            &self.0
+        }
+    }
+
+    impl Weighted for Charge {
+        fn weight(&self) -> f64 {
+            self.1
+        }
+    }
+
+    #[test]
+    fn empty_tree_has_no_centroid() {
+        let tree = WeightedTree::<Charge, 2>::root(1.0);
+
+        assert_eq!(tree.total_weight(), 0.0);
+        assert_eq!(tree.centroid(), None);
+        assert!(tree.items().is_empty());
+    }
+
+    #[test]
+    fn track_weight_through_divisions() {
+        let mut tree = WeightedTree::<Charge, 2>::root(1.0);
+
+        let division_condition =
+            |tree: &WeightedTree<Charge, 2>| tree.is_leaf() && !tree.items().is_empty();
+
+        assert!(tree
+            .insert_weighted(Charge([0.5, 0.5], 1.0), &division_condition)
+            .is_ok());
+        assert!(tree
+            .insert_weighted(Charge([0.75, 0.75], 1.0), &division_condition)
+            .is_ok());
+        assert!(tree
+            .insert_weighted(Charge([-0.5, -0.5], 2.0), &division_condition)
+            .is_ok());
+
+        assert_eq!(tree.total_weight(), 4.0);
+        assert_eq!(tree.centroid(), Some([0.0625, 0.0625]));
+        assert!(tree.items().is_empty());
+
+        let upper = tree.get_child(0).unwrap();
+        assert_eq!(upper.total_weight(), 2.0);
+        assert_eq!(upper.centroid(), Some([0.625, 0.625]));
+        assert!(upper.iter_children().all(|child| child.items().len() <= 1));
+    }
+
+    #[test]
+    fn insert_outside() {
+        let mut tree = WeightedTree::<Charge, 2>::root(1.0);
+
+        assert!(tree
+            .insert_weighted(Charge([1.5, 0.5], 1.0), &|_| false)
+            .is_err());
+        assert_eq!(tree.total_weight(), 0.0);
+    }
+}
@@ -1,7 +1,8 @@
-use crate::{impl_breadth_first_iterator, impl_depth_first_iterator};
+use crate::{impl_breadth_first_iterator, impl_depth_first_iterator, impl_levels_iterator};
 
 impl_breadth_first_iterator!();
 impl_depth_first_iterator!();
+impl_levels_iterator!();
 
 #[cfg(test)]
 mod tests {
@@ -12,11 +13,11 @@ mod tests {
         let mut tree = TpnTree::<f64>::root(1.0, 2);
 
         tree.data = Some(1.0);
-        tree.divide();
+        assert!(tree.divide().is_ok());
 
         tree.get_child_mut(3).and_then::<(), _>(|child| {
             child.data = Some(2.0);
-            child.divide();
+            assert!(child.divide().is_ok());
             child.get_child_mut(3).and_then::<(), _>(|childchild| {
                 childchild.data = Some(3.0);
                 None
@@ -36,16 +37,16 @@ mod tests {
         let mut tree = TpnTree::<f64>::root(1.0, 2);
 
         tree.data = Some(1.0);
-        tree.divide();
+        assert!(tree.divide().is_ok());
 
         tree.get_child_mut(0).and_then::<(), _>(|child| {
             *child.data_mut() = Some(2.0);
-            child.divide();
+            assert!(child.divide().is_ok());
             None
         });
         tree.get_child_mut(1).and_then::<(), _>(|child| {
             *child.data_mut() = Some(3.0);
-            child.divide();
+            assert!(child.divide().is_ok());
             None
         });
 
@@ -55,4 +56,23 @@ mod tests {
         assert_eq!(iter.next().and_then(|t| t.data().as_ref()), Some(&2.0));
         assert_eq!(iter.next().and_then(|t| t.data().as_ref()), Some(&3.0));
     }
+
+    #[test]
+    fn iterate_levels() {
+        let mut tree = TpnTree::<f64>::root(1.0, 2);
+
+        assert!(tree.divide().is_ok());
+        assert!(tree.get_child_mut(1).unwrap().divide().is_ok());
+        assert!(tree.get_child_mut(2).unwrap().divide().is_ok());
+
+        let levels = tree
+            .iter_levels()
+            .map(|(level, trees)| (level, trees.len()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(levels, vec![(0, 1), (1, 4), (2, 8)]);
+        assert!(tree
+            .iter_levels()
+            .all(|(level, trees)| trees.iter().all(|tree| tree.level() == level)));
+    }
 }
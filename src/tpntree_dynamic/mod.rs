@@ -1,20 +1,25 @@
 mod iterators;
+#[cfg(feature = "nalgebra")]
 mod nalgebra;
+mod spatial;
 
-use bitvec::bitvec;
+pub use spatial::SpatialTreeDyn;
 
-#[derive(Debug, Clone)]
+use crate::errors::TpnTreeError;
+use alloc::{vec, vec::Vec};
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct TpnTree<T> {
     /// Coordinates of the N-dimensional hyperrectangle center.
-    coordinates: Vec<f64>,
+    pub(crate) coordinates: Vec<f64>,
     /// Length of the normals from center of N-dimensional hyperrectangle to its faces.
-    span: Vec<f64>,
+    pub(crate) span: Vec<f64>,
     /// Height in tree.
-    level: usize,
+    pub(crate) level: usize,
     /// There are zero or 2^N children, one times two per axis.
-    children: Vec<Self>,
+    pub(crate) children: Vec<Self>,
     /// Any potential data the tree might hold.
-    data: Option<T>,
+    pub(crate) data: Option<T>,
 }
 
 impl<T> TpnTree<T> {
@@ -28,11 +33,11 @@ impl<T> TpnTree<T> {
     /// Here we create a one dimensional tree, i.e. with one axis sitting on the center `[0.0]` with a span of 1.0 in each direction `[1.0]`.
     /// This is equal to a line segment spanning from -1.0 to 1.0 with its midpoint at 0.0.
     /// The `level` of the root is usually zero.
-    /// The length of the vectors must match.
+    /// The length of the vectors must match, otherwise [`TpnTreeError::DimensionMismatch`] is returned.
     /// ```
     /// # use tpntree::tpntree_dynamic::TpnTree;
     ///
-    /// let root = TpnTree::<()>::new(vec![0.0], vec![1.0], 0);
+    /// let root = TpnTree::<()>::new(vec![0.0], vec![1.0], 0).expect("Dimensions match.");
     /// ```
     ///
     /// Now lets create a two dimensional tree.
@@ -40,13 +45,20 @@ impl<T> TpnTree<T> {
     /// The edges equate to twice the span as it originates from the midpoint.
     /// ```
     /// # use tpntree::tpntree_dynamic::TpnTree;
-    /// let root = TpnTree::<()>::new(vec![1.0, 1.0], vec![2.0, 0.5], 0);
+    /// let root = TpnTree::<()>::new(vec![1.0, 1.0], vec![2.0, 0.5], 0).expect("Dimensions match.");
     /// ```
-    pub fn new(coordinates: Vec<f64>, span: Vec<f64>, level: usize) -> Self {
-        assert!(
-            coordinates.len() == span.len(),
-            "coordinates must match span dimensions"
-        );
+    pub fn new(coordinates: Vec<f64>, span: Vec<f64>, level: usize) -> Result<Self, TpnTreeError> {
+        if coordinates.len() != span.len() {
+            return Err(TpnTreeError::DimensionMismatch {
+                expected: coordinates.len(),
+                got: span.len(),
+            });
+        }
+        Ok(Self::from_parts(coordinates, span, level))
+    }
+
+    // creates a tree from coordinates and span known to be of equal length
+    fn from_parts(coordinates: Vec<f64>, span: Vec<f64>, level: usize) -> Self {
         Self {
             coordinates,
             span,
@@ -69,12 +81,51 @@ impl<T> TpnTree<T> {
     /// let root = TpnTree::<()>::root(1.0, 3);
     /// ```
     pub fn root(span: f64, dimensions: usize) -> Self {
-        Self::new(vec![0.0; dimensions], vec![span; dimensions], 0)
+        Self::from_parts(vec![0.0; dimensions], vec![span; dimensions], 0)
+    }
+
+    /// Creates a new TpnTree at level zero covering the hyperrectangle between the min and max corner.
+    ///
+    /// Errors with [`TpnTreeError::DimensionMismatch`] if the corners differ in length
+    /// and with [`TpnTreeError::InvalidBounds`] if any coordinate of the min corner exceeds the one of the max corner.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tpntree::tpntree_dynamic::TpnTree;
+    /// let root = TpnTree::<()>::from_bounds(vec![0.0, 0.0], vec![4.0, 2.0]).expect("Valid bounds.");
+    ///
+    /// assert_eq!(root.coordinates(), &vec![2.0, 1.0]);
+    /// assert_eq!(root.span(), &vec![2.0, 1.0]);
+    /// ```
+    pub fn from_bounds(min: Vec<f64>, max: Vec<f64>) -> Result<Self, TpnTreeError> {
+        if min.len() != max.len() {
+            return Err(TpnTreeError::DimensionMismatch {
+                expected: min.len(),
+                got: max.len(),
+            });
+        }
+        // also rejects NaN
+        if !min.iter().zip(&max).all(|(min, max)| min <= max) {
+            return Err(TpnTreeError::InvalidBounds { min, max });
+        }
+
+        let coordinates = min
+            .iter()
+            .zip(&max)
+            .map(|(min, max)| (min + max) / 2.0)
+            .collect();
+        let span = min
+            .iter()
+            .zip(&max)
+            .map(|(min, max)| (max - min) / 2.0)
+            .collect();
+        Ok(Self::from_parts(coordinates, span, 0))
     }
 
     /// Divides the TpnTree into subregions creating new TpnTrees as children.
     ///
-    /// Returns true if division happened, returns false when already divided.
+    /// Errors if the TpnTree has been divided before.
     ///
     /// Each created child has its center moved by half the parents span up or down along the axis.
     /// Every child is equal to one unique combination of such half span moves.
@@ -83,54 +134,41 @@ impl<T> TpnTree<T> {
     ///
     /// Dividing in the 2D case is creating four smaller squares.
     ///
-    /// +---+    +-+-+
-    /// |   | => +-+-+
-    /// +---+    +-+-+
     /// ```
+    /// // +---+    +-+-+
+    /// // |   | => +-+-+
+    /// // +---+    +-+-+
     /// # use tpntree::tpntree_dynamic::TpnTree;
     /// let mut root = TpnTree::<()>::root(1.0, 2);
     ///
-    /// assert!(root.divide());
+    /// assert!(root.divide().is_ok());
     /// assert_eq!(root.child_count(), 4);
     /// ```
-    pub fn divide(&mut self) -> bool {
-        if self.children.is_empty() {
-            let mut children = Vec::<Self>::new();
-            let mut pattern = bitvec![0; self.coordinates.len()];
-
-            // iterate for 2^N to generate all children
-            for _ in 0..2usize.pow(self.coordinates.len() as u32) {
-                let mut coordinates = self.coordinates.clone();
-                let mut span = self.span.clone();
-                // generate sign pattern from bits
-                for i in 0..self.coordinates.len() {
-                    span[i] = self.span[i] / 2.0;
-                    coordinates[i] += span[i] - self.span[i] * pattern[i] as usize as f64;
-                }
-
-                children.push(Self::new(coordinates, span, self.level + 1));
-
-                let mut carry = pattern.clone();
-                carry.set_elements(0);
-                let mut one = carry.clone();
-                one.set(0, true);
-
-                // "add one" to the pattern, loop to accomodate for carry
-                while one.any() {
-                    carry = pattern.clone() & one.clone();
-                    pattern ^= one;
-                    one = carry.clone();
-                    // push so we can shift
-                    one.push(false);
-                    one.shift_right(1);
-                    // pop to have an overflowing shift
-                    one.pop();
-                }
-            }
-            self.children = children;
-            true
+    pub fn divide(&mut self) -> Result<(), TpnTreeError> {
+        if self.is_leaf() {
+            let span = self.span.iter().map(|span| span / 2.0).collect::<Vec<_>>();
+            // bit i of the child index selects the lower half along axis i
+            self.children = (0..1_usize << self.dimensions())
+                .map(|index| {
+                    let coordinates = self
+                        .coordinates
+                        .iter()
+                        .zip(&span)
+                        .enumerate()
+                        .map(|(axis, (coordinate, span))| {
+                            if index & (1 << axis) == 0 {
+                                coordinate + span
+                            } else {
+                                coordinate - span
+                            }
+                        })
+                        .collect();
+                    Self::from_parts(coordinates, span.clone(), self.level + 1)
+                })
+                .collect();
+            Ok(())
         } else {
-            false
+            Err(TpnTreeError::CanNotDivide)
         }
     }
 
@@ -143,6 +181,72 @@ impl<T> TpnTree<T> {
         self.children.get_mut(index)
     }
 
+    /// Removes the child at the index and returns it together with its descendants.
+    ///
+    /// An empty leaf covering the same region takes its place, so the tree keeps all its children.
+    /// Returns `None` if there is no child at the index.
+    ///
+    /// ```
+    /// # use tpntree::tpntree_dynamic::TpnTree;
+    /// let mut root = TpnTree::<()>::root(1.0, 2);
+    /// root.divide().expect("Couldn't divide.");
+    /// root.get_child_mut(0).unwrap().divide().expect("Couldn't divide.");
+    ///
+    /// let child = root.take_child(0).expect("Child exists.");
+    ///
+    /// assert!(!child.is_leaf());
+    /// assert!(root.get_child(0).unwrap().is_leaf());
+    /// ```
+    pub fn take_child(&mut self, index: usize) -> Option<Self> {
+        let child = self.children.get_mut(index)?;
+        let empty = Self::from_parts(child.coordinates.clone(), child.span.clone(), child.level);
+        Some(core::mem::replace(child, empty))
+    }
+
+    /// Replaces the child at the index with the subtree.
+    ///
+    /// The subtree must cover exactly the region of the child it replaces.
+    /// Levels of the subtree are adjusted to its new position.
+    /// Errors if there is no child at the index or the regions do not match, the tree is left unchanged then.
+    ///
+    /// ```
+    /// # use tpntree::tpntree_dynamic::TpnTree;
+    /// let mut root = TpnTree::<()>::root(1.0, 2);
+    /// root.divide().expect("Couldn't divide.");
+    ///
+    /// let mut child = root.take_child(1).expect("Child exists.");
+    /// child.divide().expect("Couldn't divide.");
+    ///
+    /// assert!(root.attach_child(1, child.clone()).is_ok());
+    /// assert_eq!(root.get_child(1).unwrap().child_count(), 4);
+    /// assert!(root.attach_child(0, child).is_err());
+    /// ```
+    pub fn attach_child(&mut self, index: usize, mut subtree: Self) -> Result<(), TpnTreeError> {
+        let child = self
+            .children
+            .get_mut(index)
+            .ok_or(TpnTreeError::NoSuchChild { index })?;
+
+        if child.coordinates != subtree.coordinates || child.span != subtree.span {
+            return Err(TpnTreeError::RegionMismatch {
+                expected: (child.coordinates.to_vec(), child.span.to_vec()),
+                got: (subtree.coordinates.to_vec(), subtree.span.to_vec()),
+            });
+        }
+
+        subtree.set_level(child.level);
+        *child = subtree;
+        Ok(())
+    }
+
+    // moves the tree and its descendants to the level
+    fn set_level(&mut self, level: usize) {
+        self.level = level;
+        for child in &mut self.children {
+            child.set_level(level + 1);
+        }
+    }
+
     /// Returns the count of direct children.
     pub fn child_count(&self) -> usize {
         self.children.len()
@@ -183,38 +287,165 @@ impl<T> TpnTree<T> {
         self.level
     }
 
+    /// Returns the number of dimensions of the tree.
+    pub fn dimensions(&self) -> usize {
+        self.coordinates.len()
+    }
+
+    // errors if the coordinates have other dimension than the tree
+    pub(crate) fn check_dimensions(&self, coordinates: &[f64]) -> Result<(), TpnTreeError> {
+        if coordinates.len() == self.dimensions() {
+            Ok(())
+        } else {
+            Err(TpnTreeError::DimensionMismatch {
+                expected: self.dimensions(),
+                got: coordinates.len(),
+            })
+        }
+    }
+
+    /// Returns wheter the tree is a root.
+    pub fn is_root(&self) -> bool {
+        self.level == 0
+    }
+
+    /// Returns wheter the tree is a leaf.
+    pub fn is_leaf(&self) -> bool {
+        self.children.is_empty()
+    }
+
     /// Returns a list of adjacent trees along each dimension.
     ///
     /// The trees appear in order of dimension and that first the tree above self, then the one below.
     /// Thereby there will be dimension times two TpnTrees returned.
+    /// The adjacent trees are detached from any tree but have the same level as self.
+    /// Use [`TpnTree::adjacent_trees_in`] to find the neighbors within a tree.
     pub fn adjacent_trees(&self) -> Vec<Self> {
         let mut adjacent_trees = Vec::new();
         for i in 0..self.coordinates.len() {
             let mut coordinates_above = self.coordinates.clone();
             coordinates_above[i] += self.span[i] * 2.0;
-            let tree_above = Self::new(coordinates_above, self.span.clone(), 0);
+            let tree_above = Self::from_parts(coordinates_above, self.span.clone(), self.level);
 
             let mut coordinates_below = self.coordinates.clone();
             coordinates_below[i] -= self.span[i] * 2.0;
-            let tree_below = Self::new(coordinates_below, self.span.clone(), 0);
+            let tree_below = Self::from_parts(coordinates_below, self.span.clone(), self.level);
 
             adjacent_trees.push(tree_above);
             adjacent_trees.push(tree_below);
         }
         adjacent_trees
     }
+
+    /// Returns the trees of `root` adjacent to self along each dimension, in the same order as [`TpnTree::adjacent_trees`].
+    ///
+    /// Each neighbor is the deepest tree covering the adjacent region that is not deeper than self.
+    /// So it is either the tree of the same level next to self or a larger leaf containing that region.
+    /// Neighbors outside of `root` are `None`.
+    /// Errors if the tree has other dimension than `root`.
+    ///
+    /// ```
+    /// # use tpntree::tpntree_dynamic::TpnTree;
+    /// let mut root = TpnTree::<()>::root(1.0, 2);
+    /// root.divide().expect("Couldn't divide.");
+    /// root.get_child_mut(0).unwrap().divide().expect("Couldn't divide.");
+    ///
+    /// // the child at [0.25, 0.75] of the child at [0.5, 0.5]
+    /// let tree = root.get_child(0).unwrap().get_child(1).unwrap();
+    /// let neighbors = root.adjacent_trees_in(tree).expect("Same dimension.");
+    ///
+    /// // above along the first axis is its sibling
+    /// assert_eq!(neighbors[0].map(|tree| tree.coordinates()), Some(&vec![0.75, 0.75]));
+    /// // below along the first axis is a larger leaf
+    /// assert_eq!(neighbors[1].map(|tree| tree.coordinates()), Some(&vec![-0.5, 0.5]));
+    /// // above along the second axis is outside the root
+    /// assert!(neighbors[2].is_none());
+    /// ```
+    pub fn adjacent_trees_in<'a>(
+        &'a self,
+        tree: &Self,
+    ) -> Result<Vec<Option<&'a Self>>, TpnTreeError> {
+        self.check_dimensions(&tree.coordinates)?;
+        Ok(tree
+            .adjacent_trees()
+            .iter()
+            .map(|adjacent| self.find_at_level(&adjacent.coordinates, adjacent.level))
+            .collect())
+    }
+
+    // descends towards the point no deeper than the level, assuming the point is not on a face of the tree at that level
+    fn find_at_level(&self, point: &[f64], level: usize) -> Option<&Self> {
+        let spans = self
+            .coordinates
+            .iter()
+            .zip(&self.span)
+            .zip(point)
+            .all(|((coordinate, span), point)| (point - coordinate).abs() <= *span);
+        if !spans {
+            return None;
+        }
+
+        let mut tree = self;
+        while tree.level < level && !tree.is_leaf() {
+            tree = &tree.children[tree.child_index_for(point)];
+        }
+        Some(tree)
+    }
+
+    // index of the child covering the coordinates, bit i is set if the child lies below the center on axis i
+    pub(crate) fn child_index_for(&self, coordinates: &[f64]) -> usize {
+        self.coordinates
+            .iter()
+            .zip(coordinates.iter())
+            .enumerate()
+            .fold(0, |index, (axis, (center, coordinate))| {
+                index | ((coordinate < center) as usize) << axis
+            })
+    }
 }
 
 #[cfg(test)]
 #[allow(clippy::float_cmp)]
 mod tests {
     use super::TpnTree;
+    use crate::TpnTreeError;
+
+    #[test]
+    pub fn new_with_mismatching_dimensions() {
+        assert_eq!(
+            TpnTree::<()>::new(vec![0.0, 0.0], vec![1.0], 0),
+            Err(TpnTreeError::DimensionMismatch {
+                expected: 2,
+                got: 1
+            })
+        );
+    }
+
+    #[test]
+    pub fn from_bounds_validates() {
+        assert_eq!(
+            TpnTree::<()>::from_bounds(vec![0.0], vec![1.0, 1.0]),
+            Err(TpnTreeError::DimensionMismatch {
+                expected: 1,
+                got: 2
+            })
+        );
+        assert!(matches!(
+            TpnTree::<()>::from_bounds(vec![0.0, 2.0], vec![1.0, 1.0]),
+            Err(TpnTreeError::InvalidBounds { .. })
+        ));
+        assert_eq!(
+            TpnTree::<()>::from_bounds(vec![-2.0, 0.0, 1.0], vec![0.0, 2.0, 1.0])
+                .map(|tree| (tree.min_corner(), tree.max_corner())),
+            Ok((vec![-2.0, 0.0, 1.0], vec![0.0, 2.0, 1.0]))
+        );
+    }
 
     #[test]
     pub fn divide_into_subregions_dim_1() {
         let mut root = TpnTree::<()>::root(2.0, 1);
 
-        assert!(root.divide());
+        assert!(root.divide().is_ok());
         assert_eq!(root.child_count(), 2);
 
         assert_eq!(root.get_child(0).map(|c| c.coordinates()), Some(&vec![1.0]));
@@ -223,14 +454,14 @@ mod tests {
             Some(&vec![-1.0])
         );
 
-        assert!(!root.divide());
+        assert!(root.divide().is_err());
     }
 
     #[test]
     pub fn divide_into_subregions_dim_2() {
         let mut root = TpnTree::<()>::root(1.0, 2);
 
-        assert!(root.divide());
+        assert!(root.divide().is_ok());
         assert_eq!(root.child_count(), 4);
 
         assert!(root
@@ -246,14 +477,14 @@ mod tests {
             .iter_children()
             .any(|c| c.coordinates() == &vec![-0.5, -0.5]));
 
-        assert!(!root.divide());
+        assert!(root.divide().is_err());
     }
 
     #[test]
     pub fn divide_into_subregions_dim_3() {
         let mut root = TpnTree::<()>::root(1.0, 3);
 
-        assert!(root.divide());
+        assert!(root.divide().is_ok());
         assert_eq!(root.child_count(), 8);
 
         assert!(root
@@ -281,7 +512,27 @@ mod tests {
             .iter_children()
             .any(|c| c.coordinates() == &vec![-0.5, -0.5, -0.5]));
 
-        assert!(!root.divide());
+        assert!(root.divide().is_err());
+    }
+
+    #[test]
+    pub fn take_and_attach_child() {
+        let mut root = TpnTree::<u8>::root(1.0, 2);
+
+        assert!(root.divide().is_ok());
+
+        let mut child = root.take_child(3).unwrap();
+        child.data = Some(1);
+        assert!(child.divide().is_ok());
+        assert!(root.get_child(3).unwrap().is_leaf());
+
+        assert!(matches!(
+            root.attach_child(2, child.clone()),
+            Err(TpnTreeError::RegionMismatch { .. })
+        ));
+        assert!(root.attach_child(3, child).is_ok());
+        assert_eq!(root.get_child(3).unwrap().data(), &Some(1));
+        assert_eq!(root.get_child(3).unwrap().child_count(), 4);
     }
 
     #[test]
@@ -296,6 +547,53 @@ mod tests {
             .any(|c| c.coordinates() == &vec![-2.0]));
     }
 
+    #[test]
+    pub fn adjacent_trees_keep_level() {
+        let mut root = TpnTree::<()>::root(1.0, 2);
+        assert!(root.divide().is_ok());
+
+        let child = root.get_child(3).unwrap();
+
+        assert!(child.adjacent_trees().iter().all(|tree| tree.level() == 1));
+    }
+
+    #[test]
+    pub fn adjacent_trees_in_tree() {
+        let mut root = TpnTree::<()>::root(1.0, 1);
+        assert!(root.divide().is_ok());
+        assert!(root.get_child_mut(0).unwrap().divide().is_ok());
+        assert!(root.get_child_mut(1).unwrap().divide().is_ok());
+
+        // the tree at [0.25] between the trees at [0.75] and [-0.25]
+        let tree = root.get_child(0).unwrap().get_child(1).unwrap();
+        let neighbors = root.adjacent_trees_in(tree).unwrap();
+
+        assert_eq!(neighbors.len(), 2);
+        assert_eq!(
+            neighbors[0].map(|tree| tree.coordinates()),
+            Some(&vec![0.75])
+        );
+        assert_eq!(
+            neighbors[1].map(|tree| tree.coordinates()),
+            Some(&vec![-0.25])
+        );
+        assert!(neighbors
+            .iter()
+            .flatten()
+            .all(|neighbor| neighbor.level() == 2));
+
+        // neighbors of the root are outside of it
+        assert_eq!(root.adjacent_trees_in(&root), Ok(vec![None, None]));
+        assert_eq!(
+            root.adjacent_trees_in(&TpnTree::root(1.0, 2)),
+            Err(TpnTreeError::DimensionMismatch {
+                expected: 1,
+                got: 2
+            })
+        );
+        assert_eq!(root.dimensions(), 1);
+    }
+
     #[test]
     pub fn get_adjacent_trees_dimension_two() {
         let root = TpnTree::<()>::root(1.0, 2);
@@ -1,41 +1,132 @@
 use nalgebra::DVector;
 
 use super::TpnTree;
+use crate::{errors::TpnTreeError, Coordinates, CoordinatesDyn};
 
-#[cfg(feature = "nalgebra")]
-impl TpnTree<f64> {
-    /// Calculate the variance of TpnTrees with f64 data.
-    pub fn variance(&self) -> f64 {
-        DVector::from_iterator(
-            self.children.len(),
-            self.children.iter().map(|c| c.data.unwrap_or(0.0)),
-        )
-        .variance()
+impl<const N: usize> Coordinates<N> for DVector<f64> {
+    /// Implementation for nalgebra vectors of dynamic dimension.
+    ///
+    /// The length of the vector is not checked here, see [`Coordinates::try_coordinates`].
+    fn coordinates(&self) -> &[f64] {
+        self.as_slice()
+    }
+}
+
+impl CoordinatesDyn for DVector<f64> {
+    /// Implementation for nalgebra vectors, to store them in a [`SpatialTreeDyn`](super::SpatialTreeDyn).
+    fn coordinates(&self) -> &[f64] {
+        self.as_slice()
+    }
+}
+
+impl<T> TpnTree<T> {
+    /// Creates a new TpnTree from a nalgebra vector as center and another one as span.
+    ///
+    /// Errors with [`TpnTreeError::DimensionMismatch`] if the vectors differ in length, see [`TpnTree::new`].
+    ///
+    /// ```
+    /// # use tpntree::tpntree_dynamic::TpnTree;
+    /// use nalgebra::DVector;
+    ///
+    /// let root = TpnTree::<()>::from_nalgebra(
+    ///     DVector::from_vec(vec![1.0, 1.0]),
+    ///     DVector::from_vec(vec![2.0, 0.5]),
+    ///     0,
+    /// )
+    /// .expect("Dimensions match.");
+    ///
+    /// assert_eq!(root.coordinates(), &vec![1.0, 1.0]);
+    /// assert_eq!(root.span_vector(), DVector::from_vec(vec![2.0, 0.5]));
+    /// ```
+    pub fn from_nalgebra(
+        center: DVector<f64>,
+        span: DVector<f64>,
+        level: usize,
+    ) -> Result<Self, TpnTreeError> {
+        Self::new(center.as_slice().to_vec(), span.as_slice().to_vec(), level)
+    }
+
+    /// Returns the center of the TpnTree as nalgebra vector.
+    pub fn center_vector(&self) -> DVector<f64> {
+        DVector::from_column_slice(&self.coordinates)
+    }
+
+    /// Returns the span of the TpnTree as nalgebra vector.
+    pub fn span_vector(&self) -> DVector<f64> {
+        DVector::from_column_slice(&self.span)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::TpnTree;
+    use nalgebra::DVector;
+
+    use crate::tpntree::SpatialTree;
+    use crate::tpntree_dynamic::{SpatialTreeDyn, TpnTree};
+    use crate::TpnTreeError;
 
     #[test]
-    fn calculate_variance_alone() {
-        let tree = TpnTree::<f64>::root(1.0, 2);
+    fn construct_from_and_convert_to_nalgebra() {
+        let tree = TpnTree::<()>::from_nalgebra(
+            DVector::from_vec(vec![1.0, 2.0, 3.0]),
+            DVector::from_vec(vec![0.5, 0.5, 0.5]),
+            0,
+        )
+        .unwrap();
 
-        assert!(tree.variance() < f64::EPSILON);
+        assert_eq!(tree.center_vector(), DVector::from_vec(vec![1.0, 2.0, 3.0]));
+        assert_eq!(tree.span_vector(), DVector::from_vec(vec![0.5, 0.5, 0.5]));
+        assert!(tree
+            .contains_point(tree.center_vector().as_slice())
+            .unwrap());
+
+        assert_eq!(
+            TpnTree::<()>::from_nalgebra(DVector::zeros(2), DVector::zeros(3), 0),
+            Err(TpnTreeError::DimensionMismatch {
+                expected: 2,
+                got: 3
+            })
+        );
     }
 
     #[test]
-    fn calculate_variance_with_children() {
-        let mut tree = TpnTree::<f64>::root(1.0, 2);
-
-        tree.divide();
+    fn insert_dynamic_vectors_into_spatial_tree() {
+        let mut tree = SpatialTree::<DVector<f64>, 3>::root(1.0);
 
-        for (i, c) in tree.iter_children_mut().enumerate() {
-            c.data = Some(i as f64)
-        }
+        assert!(tree
+            .insert_by_coordinates(DVector::from_vec(vec![0.5, 0.5, 0.5]), &|_| false)
+            .is_ok());
+        assert_eq!(
+            tree.insert_by_coordinates(DVector::from_vec(vec![0.5, 0.5]), &|_| false),
+            Err(TpnTreeError::DimensionMismatch {
+                expected: 3,
+                got: 2
+            })
+        );
+        assert_eq!(
+            tree.nearest_one(&[1.0, 1.0, 1.0])
+                .map(|(_, data)| data.len()),
+            Some(3)
+        );
+    }
+    #[test]
+    fn insert_and_find_dynamic_vectors_in_dynamic_tree() {
+        let mut tree = SpatialTreeDyn::<DVector<f64>>::root(1.0, 3);
+        let point = DVector::from_vec(vec![0.5, -0.5, 0.5]);
 
-        // population variance of 0,1,2,3
-        assert!((tree.variance() - 1.25).abs() < f64::EPSILON);
+        assert!(tree
+            .insert_by_coordinates(point.clone(), &|tree| tree.is_root())
+            .is_ok());
+        assert_eq!(
+            tree.find_by_coordinates(&point).unwrap().data(),
+            &Some(vec![point])
+        );
+        assert_eq!(
+            tree.insert_by_coordinates(DVector::zeros(2), &|_| false),
+            Err(TpnTreeError::DimensionMismatch {
+                expected: 3,
+                got: 2
+            })
+        );
     }
 }
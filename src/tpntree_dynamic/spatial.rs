@@ -0,0 +1,186 @@
+use alloc::vec::Vec;
+use core::iter::once;
+
+use super::TpnTree;
+use crate::{errors::TpnTreeError, CoordinatesDyn};
+
+/// A helper type to work with spatial data bins of a dimension only known at runtime.
+///
+/// Any data implementing [`CoordinatesDyn`] can be stored, e.g. `Vec<f64>`, arrays or nalgebra `DVector<f64>`.
+/// The count of coordinates is checked against the tree at runtime, mismatches are reported as [`TpnTreeError::DimensionMismatch`].
+pub type SpatialTreeDyn<T> = TpnTree<Vec<T>>;
+
+impl<T: CoordinatesDyn> SpatialTreeDyn<T> {
+    /// Checks if the tree spans over the coordinates of the provided data.
+    ///
+    /// The bounds are inclusive on both sides, like for [`SpatialTree::spans`](crate::tpntree::SpatialTree::spans).
+    /// Data with other dimension than the tree is never spanned.
+    ///
+    /// ```
+    /// # use tpntree::tpntree_dynamic::SpatialTreeDyn;
+    /// let tree = SpatialTreeDyn::<Vec<f64>>::root(1.0, 2);
+    ///
+    /// assert!(tree.spans(&vec![0.5, 1.0]));
+    /// assert!(!tree.spans(&vec![1.5, 0.5]));
+    /// assert!(!tree.spans(&vec![0.5, 0.5, 0.5]));
+    /// ```
+    pub fn spans(&self, data: &T) -> bool {
+        self.contains_point(data.coordinates()).unwrap_or(false)
+    }
+
+    /// Inserts data in the tree with its center closest to the data given the constrains of the `division_condition`.
+    ///
+    /// Works like [`SpatialTree::insert_by_coordinates`](crate::tpntree::SpatialTree::insert_by_coordinates), descending half-open.
+    /// Errors if the tree does not span the data or the data has other dimension than the tree.
+    ///
+    /// ```
+    /// # use tpntree::tpntree_dynamic::SpatialTreeDyn;
+    /// # use tpntree::TpnTreeError;
+    /// let mut tree = SpatialTreeDyn::root(1.0, 4);
+    ///
+    /// assert!(tree.insert_by_coordinates(vec![0.5; 4], &|_| false).is_ok());
+    /// assert_eq!(
+    ///     tree.insert_by_coordinates(vec![0.5; 3], &|_| false),
+    ///     Err(TpnTreeError::DimensionMismatch { expected: 4, got: 3 })
+    /// );
+    /// ```
+    pub fn insert_by_coordinates(
+        &mut self,
+        data: T,
+        division_condition: &dyn Fn(&Self) -> bool,
+    ) -> Result<(), TpnTreeError> {
+        self.check_dimensions(data.coordinates())?;
+
+        if self.is_root() && !self.spans(&data) {
+            return Err(self.does_not_span(data.coordinates()));
+        }
+
+        if self.is_leaf() {
+            if division_condition(self) {
+                self.divide()?;
+
+                for data in self
+                    .data
+                    .take()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .chain(once(data))
+                {
+                    let index = self.child_index_for(data.coordinates());
+                    self.children[index].insert_by_coordinates(data, division_condition)?;
+                }
+                Ok(())
+            } else {
+                self.data.get_or_insert(Vec::new()).push(data);
+                Ok(())
+            }
+        } else {
+            let index = self.child_index_for(data.coordinates());
+            self.children[index].insert_by_coordinates(data, division_condition)
+        }
+    }
+
+    /// Return the leaf covering the given data coordinates.
+    ///
+    /// Errors if the tree does not span the data or the data has other dimension than the tree.
+    /// Descends half-open just like [`SpatialTreeDyn::insert_by_coordinates`].
+    pub fn find_by_coordinates(&self, data: &T) -> Result<&Self, TpnTreeError> {
+        self.check_dimensions(data.coordinates())?;
+
+        if self.is_root() && !self.spans(data) {
+            return Err(self.does_not_span(data.coordinates()));
+        }
+
+        let mut tree = self;
+        while !tree.is_leaf() {
+            tree = &tree.children[tree.child_index_for(data.coordinates())];
+        }
+        Ok(tree)
+    }
+
+    /// Return the leaf covering the given data coordinates by mutable reference.
+    ///
+    /// Errors if the tree does not span the data or the data has other dimension than the tree.
+    pub fn find_by_coordinates_mut(&mut self, data: &T) -> Result<&mut Self, TpnTreeError> {
+        self.check_dimensions(data.coordinates())?;
+
+        if self.is_root() && !self.spans(data) {
+            return Err(self.does_not_span(data.coordinates()));
+        }
+
+        let mut tree = self;
+        while !tree.is_leaf() {
+            let index = tree.child_index_for(data.coordinates());
+            tree = &mut tree.children[index];
+        }
+        Ok(tree)
+    }
+}
+
+impl<T> TpnTree<T> {
+    fn does_not_span(&self, coordinates: &[f64]) -> TpnTreeError {
+        TpnTreeError::does_not_span(coordinates, &self.coordinates, &self.span)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tpntree::SpatialTree;
+    use crate::tpntree_dynamic::SpatialTreeDyn;
+    use crate::TpnTreeError;
+
+    #[test]
+    fn dynamic_insertion_matches_static() {
+        let points = (0..50)
+            .map(|i| {
+                let t = i as f64;
+                [(t * 0.3).sin(), (t * 0.7).cos(), (t * 1.1).sin()]
+            })
+            .collect::<Vec<_>>();
+        let mut dynamic = SpatialTreeDyn::root(1.0, 3);
+        let mut fixed = SpatialTree::<[f64; 3], 3>::root(1.0);
+
+        for point in points.iter() {
+            dynamic
+                .insert_by_coordinates(*point, &|tree: &SpatialTreeDyn<[f64; 3]>| {
+                    tree.data().as_ref().is_some_and(|data| data.len() >= 3)
+                })
+                .unwrap();
+            fixed
+                .insert_by_coordinates(*point, &|tree| {
+                    tree.data().is_some_and(|data| data.len() >= 3)
+                })
+                .unwrap();
+        }
+
+        for point in points.iter() {
+            let leaf = dynamic.find_by_coordinates(point).unwrap();
+            assert_eq!(
+                leaf.data().as_ref(),
+                fixed.find_by_coordinates(point).unwrap().data()
+            );
+        }
+        assert_eq!(
+            dynamic.iter_depth_first().count(),
+            fixed.iter_depth_first().count()
+        );
+    }
+
+    #[test]
+    fn dimensions_are_checked_at_runtime() {
+        let mut tree = SpatialTreeDyn::<Vec<f64>>::root(1.0, 2);
+
+        assert_eq!(
+            tree.find_by_coordinates(&vec![0.0]),
+            Err(TpnTreeError::DimensionMismatch {
+                expected: 2,
+                got: 1
+            })
+        );
+        assert!(matches!(
+            tree.insert_by_coordinates(vec![2.0, 0.0], &|_| false),
+            Err(TpnTreeError::DoesNotSpan { .. })
+        ));
+        assert!(tree.find_by_coordinates_mut(&vec![0.0, 0.0]).is_ok());
+    }
+}
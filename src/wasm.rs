@@ -0,0 +1,172 @@
+//! A minimal C ABI around a two dimensional [`SpatialTree`], to use a quadtree from JavaScript without further dependencies.
+//!
+//! The `wasm` example links these functions into a module, build it for the browser without the default nalgebra feature:
+//!
+//! ```text
+//! cargo build --release --target wasm32-unknown-unknown --no-default-features --features wasm --example wasm
+//! ```
+//!
+//! The module is written to `target/wasm32-unknown-unknown/release/examples/wasm.wasm`.
+//! The exported functions are called on the instantiated module, query results are read from the module memory:
+//!
+//! ```text
+//! const { instance } = await WebAssembly.instantiateStreaming(fetch("wasm.wasm"));
+//! const api = instance.exports;
+//!
+//! const tree = api.tpntree_quadtree_new(0, 0, 800, 600, 8, 12);
+//! api.tpntree_quadtree_insert(tree, 120, 80);
+//!
+//! const capacity = 64;
+//! const buffer = api.tpntree_alloc_f64(2 * capacity);
+//! const count = api.tpntree_quadtree_query(tree, 100, 100, 50, buffer, capacity);
+//! const points = new Float64Array(api.memory.buffer, buffer, 2 * Math.min(count, capacity));
+//!
+//! api.tpntree_free_f64(buffer, 2 * capacity);
+//! api.tpntree_quadtree_free(tree);
+//! ```
+
+use alloc::{boxed::Box, vec};
+
+use crate::tpntree::{SpatialTree, TpnTree, TreeConfig};
+
+/// An opaque quadtree of points handed out to JavaScript as pointer.
+pub struct Quadtree(SpatialTree<[f64; 2], 2>);
+
+/// Creates a quadtree covering the rectangle between the min and max corner, returning null for invalid bounds.
+///
+/// Leaves above `max_depth` divide once they hold `leaf_capacity` points, zero means they never divide.
+#[no_mangle]
+pub extern "C" fn tpntree_quadtree_new(
+    min_x: f64,
+    min_y: f64,
+    max_x: f64,
+    max_y: f64,
+    leaf_capacity: u32,
+    max_depth: u32,
+) -> *mut Quadtree {
+    match TpnTree::from_bounds([min_x, min_y], [max_x, max_y]) {
+        Ok(mut tree) => {
+            if leaf_capacity > 0 {
+                tree.set_config(
+                    TreeConfig::default()
+                        .with_leaf_capacity(leaf_capacity as usize)
+                        .with_max_depth(max_depth as usize),
+                );
+            }
+            Box::into_raw(Box::new(Quadtree(tree)))
+        }
+        Err(_) => core::ptr::null_mut(),
+    }
+}
+
+/// Frees a quadtree.
+///
+/// # Safety
+///
+/// The pointer must be null or come from [`tpntree_quadtree_new`] and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn tpntree_quadtree_free(tree: *mut Quadtree) {
+    if !tree.is_null() {
+        drop(Box::from_raw(tree));
+    }
+}
+
+/// Inserts a point, returning whether it lies within the quadtree.
+///
+/// # Safety
+///
+/// The pointer must come from [`tpntree_quadtree_new`] and not be freed.
+#[no_mangle]
+pub unsafe extern "C" fn tpntree_quadtree_insert(tree: *mut Quadtree, x: f64, y: f64) -> bool {
+    (*tree).0.insert([x, y]).is_ok()
+}
+
+/// Writes the points within the radius around the center, closest first, as x and y pairs into the output buffer.
+///
+/// At most `capacity` points are written, the count of all points within the radius is returned.
+///
+/// # Safety
+///
+/// The pointer must come from [`tpntree_quadtree_new`] and not be freed.
+/// The output buffer must have room for `2 * capacity` values.
+#[no_mangle]
+pub unsafe extern "C" fn tpntree_quadtree_query(
+    tree: *const Quadtree,
+    x: f64,
+    y: f64,
+    radius: f64,
+    output: *mut f64,
+    capacity: u32,
+) -> u32 {
+    let found = (*tree).0.within_radius(&[x, y], radius);
+    if !output.is_null() {
+        let output = core::slice::from_raw_parts_mut(output, 2 * capacity as usize);
+        for (slot, point) in output.chunks_exact_mut(2).zip(&found) {
+            slot.copy_from_slice(*point);
+        }
+    }
+    found.len() as u32
+}
+
+/// Allocates a buffer of `len` zeroed values in the module memory, e.g. for query results.
+#[no_mangle]
+pub extern "C" fn tpntree_alloc_f64(len: u32) -> *mut f64 {
+    Box::into_raw(vec![0.0; len as usize].into_boxed_slice()) as *mut f64
+}
+
+/// Frees a buffer.
+///
+/// # Safety
+///
+/// The pointer must come from [`tpntree_alloc_f64`] with the same length and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn tpntree_free_f64(buffer: *mut f64, len: u32) {
+    if !buffer.is_null() {
+        drop(Box::from_raw(core::ptr::slice_from_raw_parts_mut(
+            buffer,
+            len as usize,
+        )));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_and_query_through_the_c_abi() {
+        assert!(tpntree_quadtree_new(1.0, 0.0, 0.0, 1.0, 0, 0).is_null());
+
+        let tree = tpntree_quadtree_new(0.0, 0.0, 800.0, 600.0, 2, 8);
+        let buffer = tpntree_alloc_f64(4);
+        unsafe {
+            for (x, y) in [(120.0, 80.0), (100.0, 110.0), (96.0, 100.0), (700.0, 500.0)] {
+                assert!(tpntree_quadtree_insert(tree, x, y));
+            }
+            assert!(!tpntree_quadtree_insert(tree, -1.0, 0.0));
+
+            assert_eq!(
+                tpntree_quadtree_query(tree, 100.0, 100.0, 50.0, buffer, 2),
+                3
+            );
+            let points = std::slice::from_raw_parts(buffer, 4);
+            assert_eq!(points, &[96.0, 100.0, 100.0, 110.0]);
+
+            tpntree_free_f64(buffer, 4);
+            tpntree_quadtree_free(tree);
+        }
+    }
+    #[test]
+    fn coincident_points_stop_at_the_max_depth() {
+        let tree = tpntree_quadtree_new(0.0, 0.0, 800.0, 600.0, 1, 4);
+        unsafe {
+            for _ in 0..3 {
+                assert!(tpntree_quadtree_insert(tree, 120.0, 80.0));
+            }
+            let depth = (*tree).0.iter_depth_first().map(|tree| tree.level()).max();
+            assert_eq!(depth, Some(4));
+
+            tpntree_quadtree_free(tree);
+        }
+    }
+}